@@ -0,0 +1,33 @@
+//! Packs every image of a directory into a texture atlas.
+//!
+//! ```text
+//! cargo run --example pack_atlas -- <image dir> <atlas.png> [padding] [extrude]
+//! ```
+//!
+//! Writes the atlas image and, next to it, `<atlas>.atlas` with the region
+//! table that `Atlas::load` reads back at runtime.
+
+use Engine::Render::AtlasBuilder;
+use std::{env, process};
+
+fn main() {
+	let args: Vec<String> = env::args().collect();
+	if args.len() < 3 {
+		eprintln!("usage: {} <image dir> <atlas.png> [padding] [extrude]", args[0]);
+		process::exit(1);
+	}
+
+	let number = |index: usize, default: u32| args.get(index).map_or(default, |arg| arg.parse().expect("expected a number"));
+
+	let atlas = AtlasBuilder::from_dir(&args[1])
+		.expect("could not read the images")
+		.padding(number(3, 1))
+		.extrude(number(4, 0))
+		.build()
+		.expect("could not pack the images");
+
+	let metadata = std::path::Path::new(&args[2]).with_extension("atlas");
+	atlas.save(&args[2], &metadata).expect("could not write the atlas");
+
+	println!("packed {} images into {}x{} '{}' ({})", atlas.names().len(), atlas.image.width(), atlas.image.height(), args[2], metadata.display());
+}
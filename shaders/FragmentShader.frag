@@ -2,7 +2,10 @@
 
 out vec4 FragColor;
 in vec4 vertexColor;
+in vec2 texCoord;
+
+uniform sampler2D image;
 
 void main() {
-	FragColor = vertexColor;
+	FragColor = vertexColor * texture(image, texCoord);
 }
\ No newline at end of file
@@ -0,0 +1,415 @@
+use super::{Collision2D, Shape2D, interacts, narrow::{Compound, Rounded}};
+use crate::{Position2D, Rect2D, Size2D, Transform2D, Math::Vector2D};
+
+use std::collections::{HashMap, HashSet};
+
+/// Proxies covering more cells than this are not stored in the grid.
+const MAX_PROXY_CELLS: i64 = 4096;
+
+/// Handle to a proxy of a `BroadPhase2D`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ProxyId(u32);
+
+impl ProxyId {
+	pub fn index(self) -> usize {
+		self.0 as usize
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Proxy {
+	bounds: Rect2D,
+	layer: u32,
+	mask: u32,
+	/// `None` for proxies kept in `large` instead of the grid.
+	cells: Option<(i32, i32, i32, i32)>,
+}
+
+/// A spatial hash grid of bounding boxes that finds which colliders may
+/// touch without testing every pair.
+///
+/// Every proxy is stored in each grid cell its bounds cover, so only
+/// proxies sharing a cell are compared. Pick a `cell_size` around the size
+/// of a typical collider: much smaller makes large colliders cover many
+/// cells, much larger puts many colliders in each cell. Proxies too large
+/// for the grid, such as ones with infinite bounds, are compared against
+/// every other proxy instead.
+///
+/// ```rust
+/// use Engine::{Rect2D, Position2D, Collision::BroadPhase2D};
+///
+/// let mut broad = BroadPhase2D::new(64.0);
+/// let player = broad.insert(Rect2D::from(0, 0, 32, 32), 1, u32::MAX);
+/// let bullet = broad.insert(Rect2D::from(24, 8, 4, 4), 1, u32::MAX);
+/// let far = broad.insert(Rect2D::from(900, 900, 32, 32), 1, u32::MAX);
+///
+/// assert_eq!(broad.pairs(), vec![(player, bullet)]);
+/// assert_eq!(broad.query_point(Position2D::from(910, 910), u32::MAX), vec![far]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BroadPhase2D {
+	pub cell_size: f32,
+	proxies: Vec<Option<Proxy>>,
+	free: Vec<u32>,
+	cells: HashMap<(i32, i32), Vec<ProxyId>>,
+	large: Vec<ProxyId>,
+}
+
+impl BroadPhase2D {
+	pub fn new(cell_size: f32) -> BroadPhase2D {
+		assert!(cell_size > 0.0, "cell size must be positive");
+		BroadPhase2D { cell_size, proxies: Vec::new(), free: Vec::new(), cells: HashMap::new(), large: Vec::new() }
+	}
+
+	/// Adds a bounding box on the layers of `layer` that collides with the
+	/// layers of `mask`, see `Collision2D`.
+	pub fn insert(&mut self, bounds: Rect2D, layer: u32, mask: u32) -> ProxyId {
+		let cells = self.proxy_cells(bounds);
+		let id = match self.free.pop() {
+			Some(index) => ProxyId(index),
+			None => {
+				self.proxies.push(None);
+				ProxyId(self.proxies.len() as u32 - 1)
+			},
+		};
+		self.proxies[id.index()] = Some(Proxy { bounds, layer, mask, cells });
+		self.add_to_cells(id, cells);
+		id
+	}
+
+	/// Adds a collider with the bounds of its shape placed by `transform`.
+	pub fn insert_collider(&mut self, collider: &Collision2D, transform: Transform2D) -> ProxyId {
+		self.insert(collider.bounds(transform), collider.layer, collider.mask)
+	}
+
+	/// Moves a proxy. The grid is only touched when it moves into other cells.
+	pub fn update(&mut self, id: ProxyId, bounds: Rect2D) {
+		let cells = self.proxy_cells(bounds);
+		let proxy = self.proxies[id.index()].as_mut().expect("proxy does not exist");
+		let old = proxy.cells;
+		proxy.bounds = bounds;
+		if old != cells {
+			proxy.cells = cells;
+			self.remove_from_cells(id, old);
+			self.add_to_cells(id, cells);
+		}
+	}
+
+	/// Changes the layers a proxy is on and collides with.
+	pub fn set_layers(&mut self, id: ProxyId, layer: u32, mask: u32) {
+		let proxy = self.proxies[id.index()].as_mut().expect("proxy does not exist");
+		proxy.layer = layer;
+		proxy.mask = mask;
+	}
+
+	/// Removes a proxy. Its id may be handed out again by `insert`.
+	pub fn remove(&mut self, id: ProxyId) -> bool {
+		match self.proxies.get_mut(id.index()).and_then(Option::take) {
+			Some(proxy) => {
+				self.remove_from_cells(id, proxy.cells);
+				self.free.push(id.0);
+				true
+			},
+			None => false,
+		}
+	}
+
+	pub fn contains(&self, id: ProxyId) -> bool {
+		self.proxy(id).is_some()
+	}
+
+	pub fn bounds(&self, id: ProxyId) -> Option<Rect2D> {
+		self.proxy(id).map(|proxy| proxy.bounds)
+	}
+
+	pub fn len(&self) -> usize {
+		self.proxies.len() - self.free.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Every pair of proxies whose bounds overlap and whose layers collide,
+	/// once each, sorted and with the smaller id first.
+	pub fn pairs(&self) -> Vec<(ProxyId, ProxyId)> {
+		let mut pairs = HashSet::new();
+		for ids in self.cells.values() {
+			for (i, &a) in ids.iter().enumerate() {
+				for &b in &ids[i + 1..] {
+					let (a, b) = if a < b { (a, b) } else { (b, a) };
+					if !pairs.contains(&(a, b)) && self.overlap(a, b) {
+						pairs.insert((a, b));
+					}
+				}
+			}
+		}
+		for &a in &self.large {
+			for (index, proxy) in self.proxies.iter().enumerate() {
+				let b = ProxyId(index as u32);
+				let (a, b) = if a < b { (a, b) } else { (b, a) };
+				if proxy.is_some() && a != b && !pairs.contains(&(a, b)) && self.overlap(a, b) {
+					pairs.insert((a, b));
+				}
+			}
+		}
+		let mut pairs: Vec<(ProxyId, ProxyId)> = pairs.into_iter().collect();
+		pairs.sort();
+		pairs
+	}
+
+	/// Proxies whose bounds overlap `region` and that are on any of the
+	/// layers of `mask`, `u32::MAX` for all. Sorted by id.
+	pub fn query_region(&self, region: Rect2D, mask: u32) -> Vec<ProxyId> {
+		self.query(self.cell_range(region), mask, |bounds| bounds.intersects(region))
+	}
+
+	/// Proxies whose bounds contain `point`, filtered like `query_region`.
+	pub fn query_point(&self, point: Position2D, mask: u32) -> Vec<ProxyId> {
+		let cell = self.cell(point.x, point.y);
+		self.query((cell.0, cell.1, cell.0, cell.1), mask, |bounds| bounds.contains(point))
+	}
+
+	fn query<F: Fn(Rect2D) -> bool>(&self, cells: (i32, i32, i32, i32), mask: u32, hit: F) -> Vec<ProxyId> {
+		let area = cell_count(cells);
+		let inside = |&(x, y): &(i32, i32)| x >= cells.0 && x <= cells.2 && y >= cells.1 && y <= cells.3;
+		// regions covering more cells than are occupied walk the occupied ones instead
+		let visited: Vec<&Vec<ProxyId>> = if area > self.cells.len() as i64 {
+			self.cells.iter().filter(|(cell, _)| inside(cell)).map(|(_, ids)| ids).collect()
+		} else {
+			(cells.0..=cells.2).flat_map(|x| (cells.1..=cells.3).map(move |y| (x, y))).filter_map(|cell| self.cells.get(&cell)).collect()
+		};
+
+		let mut found = HashSet::new();
+		for &id in visited.into_iter().flatten().chain(&self.large) {
+			let proxy = self.proxy(id).unwrap();
+			if proxy.layer & mask != 0 && hit(proxy.bounds) {
+				found.insert(id);
+			}
+		}
+		let mut found: Vec<ProxyId> = found.into_iter().collect();
+		found.sort();
+		found
+	}
+
+	fn overlap(&self, a: ProxyId, b: ProxyId) -> bool {
+		let (a, b) = (self.proxy(a).unwrap(), self.proxy(b).unwrap());
+		interacts(a.layer, a.mask, b.layer, b.mask) && a.bounds.intersects(b.bounds)
+	}
+
+	fn proxy(&self, id: ProxyId) -> Option<&Proxy> {
+		self.proxies.get(id.index()).and_then(Option::as_ref)
+	}
+
+	fn cell(&self, x: f32, y: f32) -> (i32, i32) {
+		((x / self.cell_size).floor() as i32, (y / self.cell_size).floor() as i32)
+	}
+
+	/// First and last cell covered by `bounds`, as (left, top, right, bottom).
+	/// Infinite bounds saturate to the first or last cell.
+	fn cell_range(&self, bounds: Rect2D) -> (i32, i32, i32, i32) {
+		let (left, top) = self.cell(bounds.left(), bounds.top());
+		let (right, bottom) = self.cell(bounds.right(), bounds.bottom());
+		(left, top, right, bottom)
+	}
+
+	/// The cells to store a proxy with `bounds` in, or `None` when it is
+	/// too large for the grid or its bounds are not finite.
+	fn proxy_cells(&self, bounds: Rect2D) -> Option<(i32, i32, i32, i32)> {
+		let finite = [bounds.left(), bounds.top(), bounds.right(), bounds.bottom()].iter().all(|value| value.is_finite());
+		let cells = self.cell_range(bounds);
+		if finite && cell_count(cells) <= MAX_PROXY_CELLS { Some(cells) } else { None }
+	}
+
+	fn add_to_cells(&mut self, id: ProxyId, cells: Option<(i32, i32, i32, i32)>) {
+		let cells = match cells {
+			Some(cells) => cells,
+			None => return self.large.push(id),
+		};
+		for x in cells.0..=cells.2 {
+			for y in cells.1..=cells.3 {
+				self.cells.entry((x, y)).or_default().push(id);
+			}
+		}
+	}
+
+	fn remove_from_cells(&mut self, id: ProxyId, cells: Option<(i32, i32, i32, i32)>) {
+		let cells = match cells {
+			Some(cells) => cells,
+			None => return self.large.retain(|&other| other != id),
+		};
+		for x in cells.0..=cells.2 {
+			for y in cells.1..=cells.3 {
+				if let Some(ids) = self.cells.get_mut(&(x, y)) {
+					ids.retain(|&other| other != id);
+					if ids.is_empty() {
+						self.cells.remove(&(x, y));
+					}
+				}
+			}
+		}
+	}
+}
+
+/// Number of cells in a range, saturating instead of overflowing.
+fn cell_count(cells: (i32, i32, i32, i32)) -> i64 {
+	(cells.2 as i64 - cells.0 as i64 + 1).saturating_mul(cells.3 as i64 - cells.1 as i64 + 1)
+}
+
+impl Shape2D {
+	/// Axis-aligned bounding box of the shape placed by `transform`.
+	pub fn bounds(&self, transform: Transform2D) -> Rect2D {
+		Compound::from(self, transform).bounds()
+	}
+}
+
+impl Collision2D {
+	pub fn bounds(&self, transform: Transform2D) -> Rect2D {
+		self.shape.bounds(transform)
+	}
+}
+
+impl Rounded {
+	pub fn bounds(&self) -> Rect2D {
+		let (left, right) = self.project(Vector2D::from(1.0, 0.0));
+		let (top, bottom) = self.project(Vector2D::from(0.0, 1.0));
+		Rect2D { position: Position2D { x: left, y: top }, size: Size2D { x: right - left, y: bottom - top } }
+	}
+}
+
+impl Compound {
+	/// The box around every piece.
+	pub fn bounds(&self) -> Rect2D {
+		let (left, top, right, bottom) = self.pieces.iter().map(Rounded::bounds).fold(
+			(f32::MAX, f32::MAX, f32::MIN, f32::MIN),
+			|(left, top, right, bottom), bounds| (left.min(bounds.left()), top.min(bounds.top()), right.max(bounds.right()), bottom.max(bounds.bottom())),
+		);
+		Rect2D { position: Position2D { x: left, y: top }, size: Size2D { x: right - left, y: bottom - top } }
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Rotation2D, Scale2D, Collision::{Capsule2D, Circle, Rectangle}};
+
+	fn ids(broad: &BroadPhase2D) -> Vec<(usize, usize)> {
+		broad.pairs().iter().map(|(a, b)| (a.index(), b.index())).collect()
+	}
+
+	#[test]
+	fn broad_phase_pairs() {
+		let mut broad = BroadPhase2D::new(10.0);
+		broad.insert(Rect2D::from(0, 0, 5, 5), 1, u32::MAX);
+		broad.insert(Rect2D::from(4, 4, 30, 2), 1, u32::MAX);
+		broad.insert(Rect2D::from(30, 0, 5, 5), 1, u32::MAX);
+		broad.insert(Rect2D::from(33, 3, 1, 1), 1, u32::MAX);
+		broad.insert(Rect2D::from(-50, -50, 1, 1), 1, u32::MAX);
+		assert_eq!(ids(&broad), vec![(0, 1), (1, 2), (2, 3)]);
+	}
+
+	#[test]
+	fn broad_phase_layers() {
+		let mut broad = BroadPhase2D::new(10.0);
+		let player = broad.insert(Rect2D::from(0, 0, 5, 5), 0b001, 0b110);
+		let enemy = broad.insert(Rect2D::from(1, 1, 5, 5), 0b010, 0b001);
+		let decoration = broad.insert(Rect2D::from(2, 2, 5, 5), 0b100, 0b000);
+		assert_eq!(broad.pairs(), vec![(player, enemy)]);
+		assert_eq!(broad.query_point(Position2D::from(3, 3), u32::MAX), vec![player, enemy, decoration]);
+		assert_eq!(broad.query_point(Position2D::from(3, 3), 0b100), vec![decoration]);
+
+		// the player's mask takes decorations but their mask must take the player too
+		broad.set_layers(decoration, 0b100, 0b001);
+		assert_eq!(broad.pairs(), vec![(player, enemy), (player, decoration)]);
+		broad.set_layers(enemy, 0b010, 0b100);
+		assert_eq!(broad.pairs(), vec![(player, decoration)]);
+	}
+
+	#[test]
+	fn broad_phase_update_remove() {
+		let mut broad = BroadPhase2D::new(10.0);
+		let a = broad.insert(Rect2D::from(0, 0, 5, 5), 1, u32::MAX);
+		let b = broad.insert(Rect2D::from(100, 100, 5, 5), 1, u32::MAX);
+		assert!(broad.pairs().is_empty());
+
+		broad.update(b, Rect2D::from(3, 3, 5, 5));
+		assert_eq!(broad.pairs(), vec![(a, b)]);
+		assert_eq!(broad.bounds(b), Some(Rect2D::from(3, 3, 5, 5)));
+		assert!(broad.query_region(Rect2D::from(95, 95, 20, 20), u32::MAX).is_empty());
+
+		assert!(broad.remove(a));
+		assert!(!broad.remove(a));
+		assert!(broad.pairs().is_empty());
+		assert_eq!(broad.len(), 1);
+		assert_eq!(broad.insert(Rect2D::from(0, 0, 1, 1), 1, u32::MAX), a);
+	}
+
+	#[test]
+	fn broad_phase_large_bounds() {
+		let mut broad = BroadPhase2D::new(10.0);
+		let a = broad.insert(Rect2D::from(0, 0, 5, 5), 1, u32::MAX);
+		let b = broad.insert(Rect2D::from(-1e11, -1.0, 2e11, 2.0), 1, u32::MAX);
+		let c = broad.insert(Rect2D { position: Position2D::from(0, 50), size: Size2D { x: f32::INFINITY, y: 1.0 } }, 1, u32::MAX);
+		let d = broad.insert(Rect2D::from(1000.0, 50.5, 1.0, 1.0), 1, u32::MAX);
+		assert_eq!(broad.pairs(), vec![(a, b), (c, d)]);
+
+		// a ray from far left to far right
+		assert_eq!(broad.query_region(Rect2D::from(-1e11, 2.0, 2e11, 0.0), u32::MAX), vec![a]);
+		assert_eq!(broad.query_region(Rect2D { position: Position2D::from(-1e30, -10.0), size: Size2D { x: f32::INFINITY, y: 100.0 } }, u32::MAX), vec![a, b, c, d]);
+		assert_eq!(broad.query_point(Position2D::from(1e9, 0.0), u32::MAX), vec![b]);
+
+		broad.update(b, Rect2D::from(200, 200, 1, 1));
+		assert_eq!(broad.pairs(), vec![(c, d)]);
+		broad.remove(c);
+		assert!(broad.pairs().is_empty());
+		assert_eq!(broad.query_point(Position2D::from(1e9, 50.5), u32::MAX), Vec::new());
+	}
+
+	#[test]
+	fn broad_phase_query_region() {
+		let mut broad = BroadPhase2D::new(8.0);
+		let proxies: Vec<ProxyId> = (0..20).map(|i| broad.insert(Rect2D::from(i * 10, 0, 4, 4), 1, u32::MAX)).collect();
+		assert_eq!(broad.query_region(Rect2D::from(25, -5, 30, 10), 1), proxies[3..6].to_vec());
+		assert_eq!(broad.query_region(Rect2D::from(-100, -100, 1000, 1000), u32::MAX).len(), 20);
+		assert_eq!(broad.query_region(Rect2D::from(-1e6, -1e6, 2e6, 2e6), u32::MAX).len(), 20);
+		assert!(broad.query_region(Rect2D::from(25, -5, 30, 10), 2).is_empty());
+	}
+
+	#[test]
+	fn broad_phase_matches_all_pairs() {
+		let mut broad = BroadPhase2D::new(16.0);
+		let mut boxes = Vec::new();
+		let mut seed = 7u32;
+		for _ in 0..200 {
+			let mut next = || {
+				seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+				(seed >> 16) % 400
+			};
+			let rect = Rect2D::from(next() as f32 - 200.0, next() as f32 - 200.0, (next() % 40) as f32 + 1.0, (next() % 40) as f32 + 1.0);
+			boxes.push(rect);
+			broad.insert(rect, 1, u32::MAX);
+		}
+		let mut expected = Vec::new();
+		for i in 0..boxes.len() {
+			for j in i + 1..boxes.len() {
+				if boxes[i].intersects(boxes[j]) {
+					expected.push((i, j));
+				}
+			}
+		}
+		assert_eq!(ids(&broad), expected);
+	}
+
+	#[test]
+	fn shape_bounds() {
+		let rotated = Transform2D::from(Position2D::from(10, 10), Rotation2D::from_deg(90), Scale2D::new());
+		let bounds = Shape2D::Rectangle(Rectangle::new(4.0, 2.0)).bounds(rotated);
+		assert!((bounds.left() - 9.0).abs() < 1e-4 && (bounds.top() - 8.0).abs() < 1e-4);
+		assert!((bounds.size.x - 2.0).abs() < 1e-4 && (bounds.size.y - 4.0).abs() < 1e-4);
+
+		let circle = Shape2D::Circle(Circle::new(3.0)).bounds(Transform2D::new());
+		assert_eq!(circle, Rect2D::from(-3, -3, 6, 6));
+		let capsule = Shape2D::Capsule(Capsule2D::new(2.0, 10.0)).bounds(Transform2D::new());
+		assert_eq!(capsule, Rect2D::from(-2, -5, 4, 10));
+	}
+}
@@ -0,0 +1,153 @@
+use std::collections::BTreeSet;
+
+/// Where a pair of colliders is in its collision.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CollisionPhase {
+	/// The pair started touching this frame.
+	Begin,
+	/// The pair touched last frame and still does.
+	Persist,
+	/// The pair touched last frame and no longer does.
+	End,
+}
+
+/// A change or continuation of the contact between two colliders, named by
+/// whatever ids the caller uses for them. `a` is always the smaller id.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CollisionEvent2D<T> {
+	pub phase: CollisionPhase,
+	pub a: T,
+	pub b: T,
+}
+
+impl<T: Copy + PartialEq> CollisionEvent2D<T> {
+	/// Whether `id` is one of the two colliders.
+	pub fn involves(&self, id: T) -> bool {
+		self.a == id || self.b == id
+	}
+
+	/// The collider paired with `id`, if `id` is in the pair.
+	pub fn other(&self, id: T) -> Option<T> {
+		if self.a == id {
+			Some(self.b)
+		} else if self.b == id {
+			Some(self.a)
+		} else {
+			None
+		}
+	}
+}
+
+/// Turns the pairs touching each frame into begin, persist and end events
+/// by remembering the pairs of the frame before.
+///
+/// ```rust
+/// use Engine::Collision::{CollisionTracker2D, CollisionPhase};
+///
+/// let mut tracker = CollisionTracker2D::new();
+/// let events = tracker.update(vec![(2, 1)]);
+/// assert_eq!((events[0].phase, events[0].a, events[0].b), (CollisionPhase::Begin, 1, 2));
+/// assert_eq!(tracker.update(vec![(1, 2)])[0].phase, CollisionPhase::Persist);
+/// assert_eq!(tracker.update(vec![])[0].phase, CollisionPhase::End);
+/// assert!(tracker.update(vec![]).is_empty());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CollisionTracker2D<T> {
+	touching: BTreeSet<(T, T)>,
+}
+
+impl<T: Copy + Ord> CollisionTracker2D<T> {
+	pub const fn new() -> CollisionTracker2D<T> {
+		CollisionTracker2D { touching: BTreeSet::new() }
+	}
+
+	/// Replaces the touching pairs with `pairs`, in any order and either way
+	/// round. Returns a begin or persist event for every pair, sorted, then
+	/// an end event for every pair that stopped touching.
+	pub fn update<I: IntoIterator<Item = (T, T)>>(&mut self, pairs: I) -> Vec<CollisionEvent2D<T>> {
+		let touching: BTreeSet<(T, T)> = pairs.into_iter().map(|(a, b)| if a <= b { (a, b) } else { (b, a) }).collect();
+		let mut events: Vec<CollisionEvent2D<T>> = touching.iter()
+			.map(|&(a, b)| {
+				let phase = if self.touching.contains(&(a, b)) { CollisionPhase::Persist } else { CollisionPhase::Begin };
+				CollisionEvent2D { phase, a, b }
+			})
+			.collect();
+		events.extend(self.touching.difference(&touching).map(|&(a, b)| CollisionEvent2D { phase: CollisionPhase::End, a, b }));
+		self.touching = touching;
+		events
+	}
+
+	/// Forgets every pair with `id`, e.g. when its collider is removed, and
+	/// returns their end events.
+	pub fn remove(&mut self, id: T) -> Vec<CollisionEvent2D<T>> {
+		let ended: Vec<(T, T)> = self.touching.iter().copied().filter(|&(a, b)| a == id || b == id).collect();
+		ended.into_iter()
+			.map(|(a, b)| {
+				self.touching.remove(&(a, b));
+				CollisionEvent2D { phase: CollisionPhase::End, a, b }
+			})
+			.collect()
+	}
+
+	pub fn is_touching(&self, a: T, b: T) -> bool {
+		self.touching.contains(&if a <= b { (a, b) } else { (b, a) })
+	}
+
+	/// Every pair touching since the last update, sorted.
+	pub fn touching(&self) -> impl Iterator<Item = (T, T)> + '_ {
+		self.touching.iter().copied()
+	}
+
+	pub fn clear(&mut self) {
+		self.touching.clear();
+	}
+}
+
+impl<T: Copy + Ord> Default for CollisionTracker2D<T> {
+	fn default() -> CollisionTracker2D<T> {
+		CollisionTracker2D::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn phases(events: &[CollisionEvent2D<u32>]) -> Vec<(CollisionPhase, u32, u32)> {
+		events.iter().map(|event| (event.phase, event.a, event.b)).collect()
+	}
+
+	#[test]
+	fn collision_tracker_phases() {
+		let mut tracker = CollisionTracker2D::new();
+		assert_eq!(phases(&tracker.update(vec![(3, 1), (1, 2)])), vec![(CollisionPhase::Begin, 1, 2), (CollisionPhase::Begin, 1, 3)]);
+		assert_eq!(phases(&tracker.update(vec![(1, 3), (2, 4)])), vec![
+			(CollisionPhase::Persist, 1, 3),
+			(CollisionPhase::Begin, 2, 4),
+			(CollisionPhase::End, 1, 2),
+		]);
+		assert!(tracker.is_touching(3, 1) && !tracker.is_touching(1, 2));
+		assert_eq!(tracker.touching().collect::<Vec<_>>(), vec![(1, 3), (2, 4)]);
+
+		// duplicates in either order are one pair
+		assert_eq!(tracker.update(vec![(4, 2), (2, 4)]).len(), 2);
+	}
+
+	#[test]
+	fn collision_tracker_remove() {
+		let mut tracker = CollisionTracker2D::new();
+		tracker.update(vec![(1, 2), (2, 3), (4, 5)]);
+		assert_eq!(phases(&tracker.remove(2)), vec![(CollisionPhase::End, 1, 2), (CollisionPhase::End, 2, 3)]);
+		assert_eq!(phases(&tracker.update(vec![(4, 5)])), vec![(CollisionPhase::Persist, 4, 5)]);
+
+		tracker.clear();
+		assert_eq!(tracker.update(vec![(4, 5)])[0].phase, CollisionPhase::Begin);
+	}
+
+	#[test]
+	fn collision_event_other() {
+		let event = CollisionEvent2D { phase: CollisionPhase::Begin, a: 1, b: 2 };
+		assert!(event.involves(2) && !event.involves(3));
+		assert_eq!((event.other(1), event.other(2), event.other(3)), (Some(2), Some(1), None));
+	}
+}
@@ -0,0 +1,168 @@
+//! Collision shapes, the tests between them and the events they raise.
+
+use crate::{
+	Position2D,
+	Rotation2D,
+};
+
+pub(crate) mod broad;
+pub(crate) mod events;
+pub(crate) mod narrow;
+pub(crate) mod query;
+
+pub use broad::*;
+pub use events::*;
+pub use narrow::*;
+pub use query::*;
+
+#[derive(Clone, Debug)]
+pub struct Rectangle {
+	pub width: f32,
+	pub height: f32,
+	pub offset: Position2D,
+	pub rotation: Rotation2D,
+}
+
+impl Rectangle {
+	pub const fn new(width: f32, height: f32) -> Rectangle {
+		Rectangle {
+			width,
+			height,
+			offset: Position2D::new(),
+			rotation: Rotation2D::new(),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct Circle {
+	pub radius: f32,
+	pub offset: Position2D,
+}
+
+impl Circle {
+	pub const fn new(radius: f32) -> Circle {
+		Circle {
+			radius,
+			offset: Position2D::new(),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub struct Capsule2D {
+	pub radius: f32,
+	pub height: f32,
+	pub offset: Position2D,
+	pub rotation: Rotation2D,
+}
+
+impl Capsule2D {
+	pub const fn new(radius: f32, height: f32) -> Capsule2D {
+		Capsule2D {
+			radius,
+			height,
+			offset: Position2D::new(),
+			rotation: Rotation2D::new(),
+		}
+	}
+}
+
+/// A simple polygon, convex or concave, in either winding order. Concave
+/// outlines collide as the convex pieces they are split into.
+#[derive(Clone, Debug)]
+pub struct Polygon2D {
+	pub points: Vec<Position2D>,
+	pub offset: Position2D,
+	pub rotation: Rotation2D,
+}
+
+impl Polygon2D {
+	pub const fn new(points: Vec<Position2D>) -> Polygon2D {
+		Polygon2D {
+			points,
+			offset: Position2D::new(),
+			rotation: Rotation2D::new(),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+pub enum Shape2D {
+	Rectangle(Rectangle),
+	Circle(Circle),
+	Capsule(Capsule2D),
+	Polygon(Polygon2D),
+}
+
+/// A shape that takes part in collision detection.
+///
+/// Two colliders only collide when each one's `layer` shares a bit with the
+/// other's `mask`, so either side can opt out of a pair.
+#[derive(Clone, Debug)]
+pub struct Collision2D {
+	pub shape: Shape2D,
+	pub colliding: bool,
+	/// Whether pairs with this collider are reported as collision events.
+	pub monitoring: bool,
+	/// The layers this collider is on, one per bit.
+	pub layer: u32,
+	/// The layers this collider collides with, one per bit.
+	pub mask: u32,
+	/// Sensors report overlaps but never block or push anything, e.g.
+	/// pickups and trigger zones.
+	pub sensor: bool,
+	/// Only blocks what moves against its top side, the collider's -y
+	/// rotated by its transform, so platforms can be jumped through from
+	/// below. Honoured by `CharacterBody2D`.
+	pub one_way: bool,
+}
+
+impl Collision2D {
+	pub fn new(shape: Shape2D) -> Collision2D {
+		Collision2D {
+			shape,
+			colliding: false,
+			monitoring: true,
+			layer: 1,
+			mask: u32::MAX,
+			sensor: false,
+			one_way: false,
+		}
+	}
+
+	pub fn layer(mut self, layer: u32) -> Collision2D {
+		self.layer = layer;
+		self
+	}
+
+	pub fn mask(mut self, mask: u32) -> Collision2D {
+		self.mask = mask;
+		self
+	}
+
+	pub fn sensor(mut self, sensor: bool) -> Collision2D {
+		self.sensor = sensor;
+		self
+	}
+
+	pub fn one_way(mut self, one_way: bool) -> Collision2D {
+		self.one_way = one_way;
+		self
+	}
+
+	pub fn monitoring(mut self, monitoring: bool) -> Collision2D {
+		self.monitoring = monitoring;
+		self
+	}
+
+	/// Whether the layers and masks of both colliders let them collide.
+	pub fn interacts(&self, other: &Collision2D) -> bool {
+		interacts(self.layer, self.mask, other.layer, other.mask)
+	}
+}
+
+/// Whether colliders with these layers and masks collide.
+pub(crate) fn interacts(layer: u32, mask: u32, other_layer: u32, other_mask: u32) -> bool {
+	layer & other_mask != 0 && other_layer & mask != 0
+}
\ No newline at end of file
@@ -0,0 +1,524 @@
+use super::{BroadPhase2D, Collision2D, Shape2D};
+use crate::{Position2D, Rect2D, Rotation2D, Scale2D, Transform2D, Math::Vector2D, Visual::triangulate};
+
+/// How two overlapping shapes touch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contact2D {
+	/// Unit vector pointing from the first shape into the second. Moving the
+	/// second shape by `normal * depth` separates them.
+	pub normal: Vector2D,
+	pub depth: f32,
+	/// One or two points where the shapes touch, on the surface of the second shape.
+	pub points: Vec<Position2D>,
+}
+
+/// A convex shape in world space as a core swept by a radius: a point for
+/// circles, a segment for capsules and a convex polygon for the rest.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Rounded {
+	pub points: Vec<Vector2D>,
+	pub radius: f32,
+}
+
+/// A shape in world space as the union of convex pieces. Concave polygons
+/// are split into several pieces, every other shape is a single one.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Compound {
+	pub pieces: Vec<Rounded>,
+}
+
+impl Compound {
+	/// Places `shape` with `transform`, see `Rounded::from`.
+	pub fn from(shape: &Shape2D, transform: Transform2D) -> Compound {
+		match shape {
+			Shape2D::Polygon(polygon) => {
+				let local = Transform2D::from(polygon.offset, polygon.rotation, Scale2D::new());
+				let points = polygon.points.iter().map(|&point| vector(transform.apply(local.apply(point)))).collect();
+				let pieces = convex_pieces(points).into_iter().map(|points| Rounded { points, radius: 0.0 }).collect();
+				Compound { pieces }
+			},
+			_ => Compound { pieces: vec![Rounded::from(shape, transform)] },
+		}
+	}
+}
+
+impl Rounded {
+	/// Places `shape` with `transform`. Radii are scaled by the larger scale
+	/// axis, so circles stay circles. Polygons become their convex hull, so
+	/// concave ones go through `Compound::from` instead.
+	pub fn from(shape: &Shape2D, transform: Transform2D) -> Rounded {
+		let place = |offset: Position2D, rotation: Rotation2D, points: &[Position2D]| -> Vec<Vector2D> {
+			let local = Transform2D::from(offset, rotation, Scale2D::new());
+			points.iter().map(|&point| vector(transform.apply(local.apply(point)))).collect()
+		};
+		let scale = transform.scale.x.abs().max(transform.scale.y.abs());
+
+		match shape {
+			Shape2D::Rectangle(rectangle) => {
+				let (w, h) = (rectangle.width / 2.0, rectangle.height / 2.0);
+				let corners = [Position2D { x: -w, y: -h }, Position2D { x: w, y: -h }, Position2D { x: w, y: h }, Position2D { x: -w, y: h }];
+				Rounded { points: place(rectangle.offset, rectangle.rotation, &corners), radius: 0.0 }
+			},
+			Shape2D::Circle(circle) => {
+				Rounded { points: place(circle.offset, Rotation2D::new(), &[Position2D::new()]), radius: circle.radius * scale }
+			},
+			Shape2D::Capsule(capsule) => {
+				// vertical like `Visual::Capsule2D`, `height` including both caps
+				let half = (capsule.height / 2.0 - capsule.radius).max(0.0);
+				let ends = [Position2D { x: 0.0, y: -half }, Position2D { x: 0.0, y: half }];
+				Rounded { points: place(capsule.offset, capsule.rotation, &ends), radius: capsule.radius * scale }
+			},
+			Shape2D::Polygon(polygon) => {
+				Rounded { points: convex_hull(place(polygon.offset, polygon.rotation, &polygon.points)), radius: 0.0 }
+			},
+		}
+	}
+
+	/// Smallest and largest projection on `axis`, radius included.
+	pub fn project(&self, axis: Vector2D) -> (f32, f32) {
+		let (min, max) = self.points.iter().fold((f32::MAX, f32::MIN), |(min, max), &point| {
+			let projection = point * axis;
+			(min.min(projection), max.max(projection))
+		});
+		(min - self.radius, max + self.radius)
+	}
+
+	/// Normals of the edges of the core.
+	fn normals(&self) -> Vec<Vector2D> {
+		let count = match self.points.len() {
+			0 | 1 => 0,
+			2 => 1,
+			count => count,
+		};
+		(0..count)
+			.filter_map(|i| unit(perpendicular(self.points[(i + 1) % self.points.len()] - self.points[i])))
+			.collect()
+	}
+
+	/// The core points furthest along `direction`: one point, or the two
+	/// ends of an edge facing `direction`. Edges within a few degrees of
+	/// facing it count, so a box resting slightly tilted touches with its
+	/// whole side rather than rocking on one corner.
+	fn feature(&self, direction: Vector2D) -> Vec<Vector2D> {
+		let count = self.points.len();
+		let furthest = match (0..count).max_by(|&i, &j| (self.points[i] * direction).total_cmp(&(self.points[j] * direction))) {
+			Some(furthest) => furthest,
+			None => return Vec::new(),
+		};
+		let point = self.points[furthest];
+		let neighbours = match count {
+			1 => Vec::new(),
+			2 => vec![self.points[1 - furthest]],
+			_ => vec![self.points[(furthest + count - 1) % count], self.points[(furthest + 1) % count]],
+		};
+		let facing = neighbours.into_iter()
+			.filter_map(|neighbour| unit(neighbour - point).map(|edge| (neighbour, (edge * direction).abs())))
+			.filter(|&(_, slope)| slope < 0.1)
+			.min_by(|a, b| a.1.total_cmp(&b.1));
+		match facing {
+			Some((neighbour, _)) => {
+				let tangent = perpendicular(direction);
+				if point * tangent <= neighbour * tangent { vec![point, neighbour] } else { vec![neighbour, point] }
+			},
+			None => vec![point],
+		}
+	}
+}
+
+/// Tests two shapes placed by their transforms against each other with the
+/// separating axis theorem. Returns `None` when they do not overlap; shapes
+/// that only touch do not overlap.
+///
+/// Concave polygons are tested as the convex pieces they split into, and
+/// their contact is the deepest one of those pieces.
+pub fn collide(a: &Shape2D, a_transform: Transform2D, b: &Shape2D, b_transform: Transform2D) -> Option<Contact2D> {
+	collide_compound(&Compound::from(a, a_transform), &Compound::from(b, b_transform))
+}
+
+/// Tests every piece of `a` against every piece of `b`. The deepest contact
+/// wins and takes the points of the others pushing the same way, so a box
+/// resting across two pieces still touches with both corners.
+pub(crate) fn collide_compound(a: &Compound, b: &Compound) -> Option<Contact2D> {
+	if let ([a], [b]) = (a.pieces.as_slice(), b.pieces.as_slice()) {
+		return collide_rounded(a, b);
+	}
+	let contacts: Vec<Contact2D> = a.pieces.iter()
+		.flat_map(|a| b.pieces.iter().filter_map(move |b| collide_rounded(a, b)))
+		.collect();
+	let deepest = contacts.iter().max_by(|x, y| x.depth.total_cmp(&y.depth))?;
+
+	let tangent = perpendicular(deepest.normal);
+	let mut points: Vec<Position2D> = contacts.iter()
+		.filter(|contact| contact.normal * deepest.normal > 0.99)
+		.flat_map(|contact| contact.points.iter().copied())
+		.collect();
+	points.sort_by(|p, q| (vector(*p) * tangent).total_cmp(&(vector(*q) * tangent)));
+	if points.len() > 2 {
+		points = vec![points[0], points[points.len() - 1]];
+	}
+	Some(Contact2D { normal: deepest.normal, depth: deepest.depth, points })
+}
+
+pub(crate) fn collide_rounded(a: &Rounded, b: &Rounded) -> Option<Contact2D> {
+	let mut axes = a.normals();
+	axes.extend(b.normals());
+	// rounded corners can be closest to each other in any direction between two core points
+	if a.radius > 0.0 || b.radius > 0.0 {
+		for &pa in &a.points {
+			axes.extend(b.points.iter().filter_map(|&pb| unit(pb - pa)));
+		}
+	}
+
+	let mut best: Option<(f32, Vector2D)> = None;
+	for axis in axes {
+		let (a_min, a_max) = a.project(axis);
+		let (b_min, b_max) = b.project(axis);
+		let (ahead, behind) = (a_max - b_min, b_max - a_min);
+		if ahead <= 0.0 || behind <= 0.0 {
+			return None;
+		}
+		let candidate = if ahead <= behind { (ahead, axis) } else { (behind, -axis) };
+		if best.is_none_or(|(depth, _)| candidate.0 < depth) {
+			best = Some(candidate);
+		}
+	}
+	// concentric circles have no axis to test: push them apart along x
+	let (depth, normal) = best.unwrap_or((a.radius + b.radius, Vector2D::from(1.0, 0.0)));
+
+	Some(Contact2D { normal, depth, points: contact_points(a, b, normal, depth) })
+}
+
+/// Where `b` touches `a`, on the surface of `b`.
+fn contact_points(a: &Rounded, b: &Rounded, normal: Vector2D, depth: f32) -> Vec<Position2D> {
+	let a_feature = a.feature(normal);
+	let b_feature = b.feature(-normal);
+
+	if b_feature.len() == 1 {
+		return vec![position(b_feature[0] - normal * b.radius)];
+	}
+	if a_feature.len() == 1 {
+		return vec![position(a_feature[0] + normal * (a.radius - depth))];
+	}
+
+	// two faces: clip the face of `b` to the extent of the face of `a`
+	let tangent = perpendicular(normal);
+	let (a0, a1) = (a_feature[0] * tangent, a_feature[1] * tangent);
+	let (low, high) = (a0.min(a1), a0.max(a1));
+	let (b0, b1) = (b_feature[0], b_feature[1]);
+	let (t0, t1) = (b0 * tangent, b1 * tangent);
+	let surface = a_feature[0] * normal + a.radius;
+
+	let mut points = Vec::with_capacity(2);
+	for &(point, t) in &[(b0, t0), (b1, t1)] {
+		let clipped = if t < low || t > high {
+			let target = if t < low { low } else { high };
+			if (t1 - t0).abs() < f32::EPSILON {
+				continue;
+			}
+			b0 + (b1 - b0) * ((target - t0) / (t1 - t0))
+		} else {
+			point
+		};
+		let on_surface = clipped - normal * b.radius;
+		if on_surface * normal <= surface + 1e-4 * (1.0 + surface.abs()) {
+			points.push(position(on_surface));
+		}
+	}
+	if points.is_empty() {
+		points.push(position((b0 + b1) * 0.5 - normal * b.radius));
+	}
+	points
+}
+
+impl Collision2D {
+	/// Tests this collider against `other`, see `collide`.
+	pub fn collide(&self, transform: Transform2D, other: &Collision2D, other_transform: Transform2D) -> Option<Contact2D> {
+		collide(&self.shape, transform, &other.shape, other_transform)
+	}
+}
+
+/// Tests every pair of colliders whose layers and masks let them collide and
+/// sets their `colliding` flag to whether they overlap any other collider.
+/// Returns the indices of every overlapping pair with its contact; feed the
+/// index pairs to a `CollisionTracker2D` for begin and end events.
+///
+/// Candidate pairs come from a `BroadPhase2D`, so only colliders whose
+/// bounding boxes overlap are tested exactly.
+pub fn detect(colliders: &mut [(&mut Collision2D, Transform2D)]) -> Vec<(usize, usize, Contact2D)> {
+	let shapes: Vec<Compound> = colliders.iter().map(|(collider, transform)| Compound::from(&collider.shape, *transform)).collect();
+	let bounds: Vec<Rect2D> = shapes.iter().map(Compound::bounds).collect();
+
+	// cells about as large as the average collider
+	let average = bounds.iter().map(|bounds| bounds.size.x.max(bounds.size.y)).sum::<f32>() / bounds.len().max(1) as f32;
+	let mut broad = BroadPhase2D::new(average.max(1.0));
+	for (i, (collider, _)) in colliders.iter().enumerate() {
+		broad.insert(bounds[i], collider.layer, collider.mask);
+	}
+
+	let contacts: Vec<(usize, usize, Contact2D)> = broad.pairs().into_iter()
+		.filter_map(|(a, b)| collide_compound(&shapes[a.index()], &shapes[b.index()]).map(|contact| (a.index(), b.index(), contact)))
+		.collect();
+	for (i, (collider, _)) in colliders.iter_mut().enumerate() {
+		collider.colliding = contacts.iter().any(|&(a, b, _)| a == i || b == i);
+	}
+	contacts
+}
+
+/// Counter-clockwise convex hull (in a y-up frame) without collinear points.
+pub(crate) fn convex_hull(mut points: Vec<Vector2D>) -> Vec<Vector2D> {
+	points.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+	points.dedup();
+	if points.len() < 3 {
+		return points;
+	}
+
+	let cross = |o: Vector2D, a: Vector2D, b: Vector2D| (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x);
+	let mut hull: Vec<Vector2D> = Vec::with_capacity(points.len() * 2);
+	for pass in 0..2 {
+		let start = hull.len();
+		let ordered: Box<dyn Iterator<Item = &Vector2D>> = if pass == 0 { Box::new(points.iter()) } else { Box::new(points.iter().rev()) };
+		for &point in ordered {
+			while hull.len() >= start + 2 && cross(hull[hull.len() - 2], hull[hull.len() - 1], point) <= 0.0 {
+				hull.pop();
+			}
+			hull.push(point);
+		}
+		hull.pop();
+	}
+	hull
+}
+
+/// Splits a simple polygon into convex pieces: the triangles of
+/// `Visual::triangulate`, merged back together across the diagonals
+/// between them as long as the result stays convex. Convex outlines stay
+/// whole. Every piece comes back as its convex hull.
+pub(crate) fn convex_pieces(points: Vec<Vector2D>) -> Vec<Vec<Vector2D>> {
+	let n = points.len();
+	let turns: Vec<f32> = (0..n).map(|i| turn(points[(i + n - 1) % n], points[i], points[(i + 1) % n])).collect();
+	if turns.iter().all(|&turn| turn >= 0.0) || turns.iter().all(|&turn| turn <= 0.0) {
+		return vec![convex_hull(points)];
+	}
+
+	let outline: Vec<Position2D> = points.iter().map(|&point| position(point)).collect();
+	let mut pieces: Vec<Vec<usize>> = triangulate(&outline).iter().map(|triangle| triangle.to_vec()).collect();
+	let mut merged = true;
+	while merged {
+		merged = false;
+		'search: for i in 0..pieces.len() {
+			for j in i + 1..pieces.len() {
+				if let Some(union) = merge_convex(&pieces[i], &pieces[j], &points) {
+					pieces[i] = union;
+					pieces.remove(j);
+					merged = true;
+					break 'search;
+				}
+			}
+		}
+	}
+	pieces.into_iter().map(|piece| convex_hull(piece.iter().map(|&i| points[i]).collect())).collect()
+}
+
+/// Joins two pieces sharing an edge, if the outline around both is convex.
+/// Pieces wind the way `triangulate` left them, turning left at every corner.
+fn merge_convex(a: &[usize], b: &[usize], points: &[Vector2D]) -> Option<Vec<usize>> {
+	let (na, nb) = (a.len(), b.len());
+	let (k, m) = (0..na).find_map(|k| {
+		let (start, end) = (a[k], a[(k + 1) % na]);
+		(0..nb).find(|&m| b[m] == end && b[(m + 1) % nb] == start).map(|m| (k, m))
+	})?;
+	// around `a` from the end of the shared edge back to its start, then
+	// around `b` past the shared edge
+	let mut union: Vec<usize> = (1..=na).map(|i| a[(k + i) % na]).collect();
+	union.extend((2..nb).map(|i| b[(m + i) % nb]));
+
+	let n = union.len();
+	let convex = (0..n).all(|i| turn(points[union[(i + n - 1) % n]], points[union[i]], points[union[(i + 1) % n]]) >= 0.0);
+	if convex { Some(union) } else { None }
+}
+
+/// Twice the signed area of the triangle `a`, `b`, `c`, with the sign of
+/// the turn at `b`.
+fn turn(a: Vector2D, b: Vector2D, c: Vector2D) -> f32 {
+	(b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+pub(crate) fn perpendicular(v: Vector2D) -> Vector2D {
+	Vector2D::from(-v.y, v.x)
+}
+
+/// `v` scaled to length 1, or `None` when it has no direction.
+pub(crate) fn unit(v: Vector2D) -> Option<Vector2D> {
+	let length = !v;
+	if length > f32::EPSILON { Some(v / length) } else { None }
+}
+
+pub(crate) fn vector(point: Position2D) -> Vector2D {
+	Vector2D::from(point.x, point.y)
+}
+
+pub(crate) fn position(v: Vector2D) -> Position2D {
+	Position2D { x: v.x, y: v.y }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{test_util::{at, close}, Collision::{Capsule2D, Circle, Polygon2D, Rectangle}};
+
+	fn square(size: f32) -> Shape2D {
+		Shape2D::Rectangle(Rectangle::new(size, size))
+	}
+
+	fn circle(radius: f32) -> Shape2D {
+		Shape2D::Circle(Circle::new(radius))
+	}
+
+	#[test]
+	fn collide_circle_circle() {
+		let contact = collide(&circle(10.0), at(0.0, 0.0), &circle(5.0), at(12.0, 0.0)).unwrap();
+		assert!(close(contact.normal.x, 1.0) && close(contact.normal.y, 0.0));
+		assert!(close(contact.depth, 3.0));
+		assert_eq!(contact.points.len(), 1);
+		assert!(close(contact.points[0].x, 7.0) && close(contact.points[0].y, 0.0));
+		assert!(collide(&circle(10.0), at(0.0, 0.0), &circle(5.0), at(15.0, 0.0)).is_none());
+		assert!(close(collide(&circle(1.0), at(3.0, 3.0), &circle(1.0), at(3.0, 3.0)).unwrap().depth, 2.0));
+	}
+
+	#[test]
+	fn collide_rectangle_rectangle() {
+		let contact = collide(&square(10.0), at(0.0, 0.0), &square(10.0), at(8.0, 1.0)).unwrap();
+		assert!(close(contact.normal.x, 1.0) && close(contact.depth, 2.0));
+		assert_eq!(contact.points.len(), 2);
+		let mut ys: Vec<f32> = contact.points.iter().map(|point| point.y).collect();
+		ys.sort_by(f32::total_cmp);
+		assert!(close(ys[0], -4.0) && close(ys[1], 5.0));
+		assert!(contact.points.iter().all(|point| close(point.x, 3.0)));
+		assert!(collide(&square(10.0), at(0.0, 0.0), &square(10.0), at(10.5, 0.0)).is_none());
+	}
+
+	#[test]
+	fn collide_rotated_rectangle() {
+		let diamond = Transform2D::from(Position2D::from(0, -13), Rotation2D::from_deg(45), Scale2D::new());
+		let contact = collide(&square(20.0), at(0.0, 0.0), &square(10.0), diamond).unwrap();
+		// the lower corner of the diamond reaches 13 - 5 * sqrt(2) = 5.93 below its center
+		assert!(close(contact.normal.x, 0.0) && close(contact.normal.y, -1.0));
+		assert!(close(contact.depth, 10.0 - (13.0 - 50f32.sqrt())));
+		assert_eq!(contact.points.len(), 1);
+		assert!(close(contact.points[0].x, 0.0) && close(contact.points[0].y, 50f32.sqrt() - 13.0));
+
+		// rotated far enough apart the bounding boxes still overlap but the shapes do not
+		let apart = Transform2D::from(Position2D::from(14, -14), Rotation2D::from_deg(45), Scale2D::new());
+		assert!(collide(&square(20.0), at(0.0, 0.0), &square(10.0), apart).is_none());
+	}
+
+	#[test]
+	fn collide_rectangle_circle() {
+		let contact = collide(&square(10.0), at(0.0, 0.0), &circle(2.0), at(6.0, 6.0)).unwrap();
+		let diagonal = 1.0 / 2f32.sqrt();
+		assert!(close(contact.normal.x, diagonal) && close(contact.normal.y, diagonal));
+		assert!(close(contact.depth, 2.0 - 2f32.sqrt()));
+		assert!(collide(&square(10.0), at(0.0, 0.0), &circle(1.0), at(6.0, 6.0)).is_none());
+
+		let contact = collide(&circle(2.0), at(0.0, -6.0), &square(10.0), at(0.0, 0.0)).unwrap();
+		assert!(close(contact.normal.y, 1.0) && close(contact.depth, 1.0));
+	}
+
+	#[test]
+	fn collide_capsule() {
+		let capsule = Shape2D::Capsule(Capsule2D::new(2.0, 20.0));
+		// the capsule stands on the top face of the box
+		let contact = collide(&capsule, at(0.0, -14.5), &square(10.0), at(0.0, 0.0)).unwrap();
+		assert!(close(contact.normal.y, 1.0) && close(contact.depth, 0.5));
+
+		// lying down and crossing another capsule in its middle
+		let lying = Transform2D::from(Position2D::new(), Rotation2D::from_deg(90), Scale2D::new());
+		let contact = collide(&capsule, lying, &capsule, at(0.0, 0.0)).unwrap();
+		assert!(close(contact.depth, 12.0));
+		assert!(collide(&capsule, lying, &capsule, at(0.0, 11.5)).is_some());
+		assert!(collide(&capsule, lying, &capsule, at(0.0, 12.5)).is_none());
+	}
+
+	#[test]
+	fn collide_polygon() {
+		let triangle = Shape2D::Polygon(Polygon2D::new(vec![Position2D::from(0, -10), Position2D::from(10, 10), Position2D::from(-10, 10)]));
+		let contact = collide(&triangle, at(0.0, 0.0), &square(4.0), at(0.0, 11.0)).unwrap();
+		assert!(close(contact.normal.y, 1.0) && close(contact.depth, 1.0));
+		assert_eq!(contact.points.len(), 2);
+		assert!(collide(&triangle, at(0.0, 0.0), &square(4.0), at(8.0, -8.0)).is_none());
+		assert!(collide(&triangle, at(0.0, 0.0), &circle(3.0), at(0.0, 0.0)).is_some());
+	}
+
+	#[test]
+	fn collide_concave_polygon() {
+		let notch = Shape2D::Polygon(Polygon2D::new(vec![
+			Position2D::from(-10, -10), Position2D::from(0, 0), Position2D::from(10, -10),
+			Position2D::from(10, 10), Position2D::from(-10, 10),
+		]));
+		// inside the notch, which the convex hull would fill
+		assert!(collide(&notch, at(0.0, 0.0), &circle(1.0), at(0.0, -8.0)).is_none());
+		let contact = collide(&notch, at(0.0, 0.0), &circle(1.0), at(0.0, -0.5)).unwrap();
+		assert!(contact.normal.y < 0.0);
+
+		// resting across the pieces on both sides of the notch
+		let contact = collide(&notch, at(0.0, 0.0), &Shape2D::Rectangle(Rectangle::new(30.0, 4.0)), at(0.0, 11.0)).unwrap();
+		assert!(close(contact.normal.y, 1.0) && close(contact.depth, 1.0));
+		assert_eq!(contact.points.len(), 2);
+		assert!(close(contact.points[0].x.abs(), 10.0) && close(contact.points[0].x, -contact.points[1].x));
+	}
+
+	#[test]
+	fn convex_pieces_split() {
+		let square = vec![Vector2D::from(0.0, 0.0), Vector2D::from(1.0, 0.0), Vector2D::from(1.0, 1.0), Vector2D::from(0.0, 1.0)];
+		assert_eq!(convex_pieces(square.clone()).len(), 1);
+		assert_eq!(convex_pieces(square.into_iter().rev().collect()).len(), 1);
+
+		// an L shape needs exactly two convex pieces
+		let l = vec![
+			Vector2D::from(0.0, 0.0), Vector2D::from(2.0, 0.0), Vector2D::from(2.0, 1.0),
+			Vector2D::from(1.0, 1.0), Vector2D::from(1.0, 2.0), Vector2D::from(0.0, 2.0),
+		];
+		for outline in vec![l.clone(), l.into_iter().rev().collect()] {
+			let pieces = convex_pieces(outline);
+			assert_eq!(pieces.len(), 2);
+			let area: f32 = pieces.iter().map(|piece| (0..piece.len()).map(|i| turn(Vector2D::new(), piece[i], piece[(i + 1) % piece.len()])).sum::<f32>().abs() / 2.0).sum();
+			assert!(close(area, 3.0));
+		}
+	}
+
+	#[test]
+	fn collide_scaled() {
+		let scaled = Transform2D::from(Position2D::new(), Rotation2D::new(), Scale2D::from(2, 2));
+		assert!(collide(&circle(5.0), scaled, &circle(5.0), at(14.0, 0.0)).is_some());
+		assert!(collide(&square(10.0), scaled, &square(10.0), at(14.0, 0.0)).is_some());
+		assert!(collide(&square(10.0), scaled, &square(10.0), at(16.0, 0.0)).is_none());
+	}
+
+	#[test]
+	fn collision_detect() {
+		let mut a = Collision2D::new(square(10.0));
+		let mut b = Collision2D::new(circle(3.0));
+		let mut c = Collision2D::new(circle(3.0));
+		let contacts = detect(&mut [(&mut a, at(0.0, 0.0)), (&mut b, at(7.0, 0.0)), (&mut c, at(100.0, 0.0))]);
+		assert_eq!(contacts.len(), 1);
+		assert_eq!((contacts[0].0, contacts[0].1), (0, 1));
+		assert!(a.colliding && b.colliding && !c.colliding);
+		assert_eq!(a.collide(at(0.0, 0.0), &b, at(7.0, 0.0)), Some(contacts[0].2.clone()));
+
+		detect(&mut [(&mut a, at(0.0, 0.0)), (&mut b, at(50.0, 0.0))]);
+		assert!(!a.colliding && !b.colliding);
+
+		b.layer = 2;
+		a.mask = 1;
+		assert!(detect(&mut [(&mut a, at(0.0, 0.0)), (&mut b, at(7.0, 0.0))]).is_empty());
+		assert!(!a.colliding);
+	}
+
+	#[test]
+	fn convex_hull_points() {
+		let hull = convex_hull(vec![
+			Vector2D::from(0.0, 0.0), Vector2D::from(2.0, 0.0), Vector2D::from(1.0, 1.0),
+			Vector2D::from(2.0, 2.0), Vector2D::from(0.0, 2.0), Vector2D::from(1.0, 0.0),
+		]);
+		assert_eq!(hull, vec![Vector2D::from(0.0, 0.0), Vector2D::from(2.0, 0.0), Vector2D::from(2.0, 2.0), Vector2D::from(0.0, 2.0)]);
+	}
+}
@@ -0,0 +1,316 @@
+use super::{Collision2D, Shape2D, narrow::{Compound, Rounded, collide_compound, collide_rounded, convex_hull, perpendicular, position, unit, vector}};
+use crate::{Position2D, Transform2D, Math::Vector2D};
+
+/// Where a ray or a moving shape first touches a shape.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CastHit2D {
+	/// The touching point on the surface of the shape hit.
+	pub point: Position2D,
+	/// Unit normal of the surface hit, pointing back towards the ray or the
+	/// moving shape.
+	pub normal: Vector2D,
+	/// How far along the ray or the motion the hit is, from 0 to 1.
+	pub fraction: f32,
+}
+
+/// Casts the segment from `from` to `to` against `shape` placed by
+/// `transform`. Rays starting inside the shape do not hit it.
+pub fn raycast(shape: &Shape2D, transform: Transform2D, from: Position2D, to: Position2D) -> Option<CastHit2D> {
+	let (from, to) = (vector(from), vector(to));
+	raycast_compound(&Compound::from(shape, transform), from, to - from)
+		.map(|(fraction, normal)| CastHit2D { point: position(from + (to - from) * fraction), normal, fraction })
+}
+
+/// Moves `shape` placed by `transform` along `motion` and returns where it
+/// first touches `target`. Shapes that overlap from the start hit at
+/// fraction 0.
+pub fn shape_cast(shape: &Shape2D, transform: Transform2D, motion: Vector2D, target: &Shape2D, target_transform: Transform2D) -> Option<CastHit2D> {
+	shape_cast_compound(&Compound::from(shape, transform), motion, &Compound::from(target, target_transform))
+}
+
+/// Whether `point` is inside `shape` placed by `transform`. Points on the
+/// surface are inside.
+pub fn contains_point(shape: &Shape2D, transform: Transform2D, point: Position2D) -> bool {
+	Compound::from(shape, transform).distance(vector(point)) <= 0.0
+}
+
+impl Shape2D {
+	/// See `raycast`.
+	pub fn raycast(&self, transform: Transform2D, from: Position2D, to: Position2D) -> Option<CastHit2D> {
+		raycast(self, transform, from, to)
+	}
+
+	/// See `contains_point`.
+	pub fn contains_point(&self, transform: Transform2D, point: Position2D) -> bool {
+		contains_point(self, transform, point)
+	}
+}
+
+impl Collision2D {
+	/// See `raycast`.
+	pub fn raycast(&self, transform: Transform2D, from: Position2D, to: Position2D) -> Option<CastHit2D> {
+		raycast(&self.shape, transform, from, to)
+	}
+
+	/// Moves this collider along `motion`, see `shape_cast`.
+	pub fn cast(&self, transform: Transform2D, motion: Vector2D, other: &Collision2D, other_transform: Transform2D) -> Option<CastHit2D> {
+		shape_cast(&self.shape, transform, motion, &other.shape, other_transform)
+	}
+
+	/// See `contains_point`.
+	pub fn contains_point(&self, transform: Transform2D, point: Position2D) -> bool {
+		contains_point(&self.shape, transform, point)
+	}
+}
+
+impl Compound {
+	/// Distance from `point` to the nearest piece: negative inside.
+	pub fn distance(&self, point: Vector2D) -> f32 {
+		self.pieces.iter().map(|piece| piece.distance(point)).fold(f32::MAX, f32::min)
+	}
+}
+
+impl Rounded {
+	/// Signed distance from `point` to the surface: negative inside.
+	pub fn distance(&self, point: Vector2D) -> f32 {
+		let edges = self.edges();
+		let core = match self.points.as_slice() {
+			[] => return f32::MAX,
+			[center] => !(point - *center),
+			_ => {
+				let outside = edges.iter().map(|&(start, _, normal)| (point - start) * normal).fold(f32::MIN, f32::max);
+				if self.points.len() > 2 && outside <= 0.0 {
+					outside
+				} else {
+					edges.iter().map(|&(start, end, _)| segment_distance(point, start, end)).fold(f32::MAX, f32::min)
+				}
+			},
+		};
+		core - self.radius
+	}
+
+	/// Edges of the core with their outward normals. A segment has two, one
+	/// for each side.
+	fn edges(&self) -> Vec<(Vector2D, Vector2D, Vector2D)> {
+		match self.points.len() {
+			0 | 1 => Vec::new(),
+			2 => {
+				let (start, end) = (self.points[0], self.points[1]);
+				match unit(perpendicular(end - start)) {
+					Some(normal) => vec![(start, end, normal), (end, start, -normal)],
+					None => Vec::new(),
+				}
+			},
+			count => {
+				let center = self.points.iter().fold(Vector2D::new(), |sum, &point| sum + point) * (1.0 / count as f32);
+				(0..count).filter_map(|i| {
+					let (start, end) = (self.points[i], self.points[(i + 1) % count]);
+					let normal = unit(perpendicular(end - start))?;
+					Some(if (center - start) * normal > 0.0 { (start, end, -normal) } else { (start, end, normal) })
+				}).collect()
+			},
+		}
+	}
+}
+
+fn segment_distance(point: Vector2D, start: Vector2D, end: Vector2D) -> f32 {
+	let edge = end - start;
+	let length = edge * edge;
+	let t = if length > 0.0 { ((point - start) * edge / length).clamp(0.0, 1.0) } else { 0.0 };
+	!(point - (start + edge * t))
+}
+
+/// Like `raycast_rounded`, for the nearest piece the ray enters.
+pub(crate) fn raycast_compound(shape: &Compound, origin: Vector2D, direction: Vector2D) -> Option<(f32, Vector2D)> {
+	if shape.distance(origin) < 0.0 {
+		return None;
+	}
+	shape.pieces.iter()
+		.filter_map(|piece| raycast_rounded(piece, origin, direction))
+		.min_by(|a, b| a.0.total_cmp(&b.0))
+}
+
+/// The fraction of `direction` from `origin` where the ray enters `shape`,
+/// with the surface normal there.
+pub(crate) fn raycast_rounded(shape: &Rounded, origin: Vector2D, direction: Vector2D) -> Option<(f32, Vector2D)> {
+	if shape.distance(origin) < 0.0 || direction * direction <= 0.0 {
+		return None;
+	}
+	let mut best: Option<(f32, Vector2D)> = None;
+	let mut consider = |fraction: f32, normal: Vector2D| {
+		if (0.0..=1.0).contains(&fraction) && best.is_none_or(|(closest, _)| fraction < closest) {
+			best = Some((fraction, normal));
+		}
+	};
+
+	// the sides of the core pushed out by the radius
+	for (start, end, normal) in shape.edges() {
+		let facing = direction * normal;
+		if facing >= 0.0 {
+			continue;
+		}
+		let offset = normal * shape.radius;
+		let fraction = ((start + offset - origin) * normal) / facing;
+		let hit = origin + direction * fraction - offset;
+		let (along, length) = ((hit - start) * (end - start), (end - start) * (end - start));
+		if along >= 0.0 && along <= length {
+			consider(fraction, normal);
+		}
+	}
+	// the rounded corners
+	if shape.radius > 0.0 {
+		for &center in &shape.points {
+			let relative = origin - center;
+			let (a, b, c) = (direction * direction, relative * direction, relative * relative - shape.radius * shape.radius);
+			let discriminant = b * b - a * c;
+			if discriminant >= 0.0 {
+				let fraction = (-b - discriminant.sqrt()) / a;
+				if let Some(normal) = unit(relative + direction * fraction) {
+					consider(fraction, normal);
+				}
+			}
+		}
+	}
+	best
+}
+
+/// Like `shape_cast_rounded`, for the first pair of pieces that touches.
+pub(crate) fn shape_cast_compound(shape: &Compound, motion: Vector2D, target: &Compound) -> Option<CastHit2D> {
+	if let Some(contact) = collide_compound(target, shape) {
+		return Some(CastHit2D { point: contact.points.first().copied().unwrap_or(Position2D::new()), normal: contact.normal, fraction: 0.0 });
+	}
+	shape.pieces.iter()
+		.flat_map(|piece| target.pieces.iter().filter_map(move |other| shape_cast_rounded(piece, motion, other)))
+		.min_by(|a, b| a.fraction.total_cmp(&b.fraction))
+}
+
+/// Moves `shape` along `motion` until it touches `target`, as a ray cast
+/// from the origin against the Minkowski difference of the two.
+pub(crate) fn shape_cast_rounded(shape: &Rounded, motion: Vector2D, target: &Rounded) -> Option<CastHit2D> {
+	if let Some(contact) = collide_rounded(target, shape) {
+		return Some(CastHit2D { point: contact.points.first().copied().unwrap_or(Position2D::new()), normal: contact.normal, fraction: 0.0 });
+	}
+	let difference = Rounded {
+		points: convex_hull(target.points.iter().flat_map(|&b| shape.points.iter().map(move |&a| b - a)).collect()),
+		radius: target.radius + shape.radius,
+	};
+	let (fraction, normal) = raycast_rounded(&difference, Vector2D::new(), motion)?;
+
+	// the middle of where the two surfaces meet
+	let moved: Vec<Vector2D> = shape.points.iter().map(|&point| point + motion * fraction - normal * shape.radius).collect();
+	let surface: Vec<Vector2D> = target.points.iter().map(|&point| point + normal * target.radius).collect();
+	let tangent = perpendicular(normal);
+	let touching = |points: &[Vector2D], toward: f32| -> (f32, f32) {
+		let extreme = points.iter().map(|&point| point * normal * toward).fold(f32::MIN, f32::max);
+		let epsilon = 1e-3 * (1.0 + extreme.abs());
+		points.iter()
+			.filter(|&&point| point * normal * toward >= extreme - epsilon)
+			.map(|&point| point * tangent)
+			.fold((f32::MAX, f32::MIN), |(low, high), t| (low.min(t), high.max(t)))
+	};
+	let (a_low, a_high) = touching(&moved, -1.0);
+	let (b_low, b_high) = touching(&surface, 1.0);
+	let (low, high) = (a_low.max(b_low), a_high.min(b_high));
+	let across = if low <= high { (low + high) / 2.0 } else { (a_low + a_high) / 2.0 };
+	let height = surface.iter().map(|&point| point * normal).fold(f32::MIN, f32::max);
+	Some(CastHit2D { point: position(tangent * across + normal * height), normal, fraction })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Rotation2D, Scale2D, test_util::{at, close}, Collision::{Capsule2D, Circle, Polygon2D, Rectangle}};
+
+	fn assert_hit(hit: Option<CastHit2D>, point: (f32, f32), normal: (f32, f32), fraction: f32) {
+		let hit = hit.expect("expected a hit");
+		assert!(close(hit.point.x, point.0) && close(hit.point.y, point.1), "{:?}", hit);
+		assert!(close(hit.normal.x, normal.0) && close(hit.normal.y, normal.1), "{:?}", hit);
+		assert!(close(hit.fraction, fraction), "{:?}", hit);
+	}
+
+	#[test]
+	fn raycast_rectangle() {
+		let square = Shape2D::Rectangle(Rectangle::new(20.0, 20.0));
+		assert_hit(square.raycast(at(50.0, 0.0), Position2D::from(0, 0), Position2D::from(100, 0)), (40.0, 0.0), (-1.0, 0.0), 0.4);
+		assert_hit(square.raycast(at(50.0, 0.0), Position2D::from(50, -100), Position2D::from(50, 0)), (50.0, -10.0), (0.0, -1.0), 0.9);
+		assert!(square.raycast(at(50.0, 0.0), Position2D::from(0, 0), Position2D::from(30, 0)).is_none());
+		assert!(square.raycast(at(50.0, 0.0), Position2D::from(0, 20), Position2D::from(100, 20)).is_none());
+		// starting inside
+		assert!(square.raycast(at(50.0, 0.0), Position2D::from(50, 0), Position2D::from(100, 0)).is_none());
+
+		let rotated = Transform2D::from(Position2D::from(50, 0), Rotation2D::from_deg(45), Scale2D::new());
+		let half_diagonal = 10.0 * std::f32::consts::SQRT_2;
+		let hit = square.raycast(rotated, Position2D::from(0, 0), Position2D::from(100, 0)).unwrap();
+		assert!(close(hit.point.x, 50.0 - half_diagonal) && close(hit.fraction, (50.0 - half_diagonal) / 100.0));
+	}
+
+	#[test]
+	fn raycast_circle() {
+		let circle = Shape2D::Circle(Circle::new(5.0));
+		assert_hit(circle.raycast(at(20.0, 0.0), Position2D::from(0, 0), Position2D::from(40, 0)), (15.0, 0.0), (-1.0, 0.0), 0.375);
+		let hit = circle.raycast(at(20.0, 3.0), Position2D::from(0, 0), Position2D::from(40, 0)).unwrap();
+		assert!(close(hit.point.x, 16.0) && close(hit.normal.x, -0.8) && close(hit.normal.y, -0.6));
+		assert!(circle.raycast(at(20.0, 6.0), Position2D::from(0, 0), Position2D::from(40, 0)).is_none());
+		assert!(circle.raycast(at(20.0, 0.0), Position2D::from(40, 0), Position2D::from(30, 0)).is_none());
+	}
+
+	#[test]
+	fn raycast_capsule_polygon() {
+		let capsule = Shape2D::Capsule(Capsule2D::new(5.0, 30.0));
+		// the straight side, then the rounded end
+		assert_hit(capsule.raycast(at(0.0, 0.0), Position2D::from(-20, 5), Position2D::from(20, 5)), (-5.0, 5.0), (-1.0, 0.0), 0.375);
+		assert_hit(capsule.raycast(at(0.0, 0.0), Position2D::from(0, -40), Position2D::from(0, 0)), (0.0, -15.0), (0.0, -1.0), 0.625);
+
+		let triangle = Shape2D::Polygon(Polygon2D::new(vec![Position2D::from(0, 0), Position2D::from(10, 0), Position2D::from(0, 10)]));
+		let hit = triangle.raycast(Transform2D::new(), Position2D::from(10, 10), Position2D::from(0, 0)).unwrap();
+		assert!(close(hit.point.x, 5.0) && close(hit.point.y, 5.0) && close(hit.fraction, 0.5));
+		let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+		assert!(close(hit.normal.x, diagonal) && close(hit.normal.y, diagonal));
+	}
+
+	#[test]
+	fn queries_concave_polygon() {
+		let notch = Shape2D::Polygon(Polygon2D::new(vec![
+			Position2D::from(-10, -10), Position2D::from(0, 0), Position2D::from(10, -10),
+			Position2D::from(10, 10), Position2D::from(-10, 10),
+		]));
+		// down into the notch, to its side rather than the top of the hull
+		let diagonal = std::f32::consts::FRAC_1_SQRT_2;
+		assert_hit(notch.raycast(at(0.0, 0.0), Position2D::from(-2, -20), Position2D::from(-2, 20)), (-2.0, -2.0), (diagonal, -diagonal), 0.45);
+		assert!(!notch.contains_point(at(0.0, 0.0), Position2D::from(0, -5)));
+		assert!(notch.contains_point(at(0.0, 0.0), Position2D::from(-8, -5)));
+		assert!(notch.raycast(at(0.0, 0.0), Position2D::from(-8, -5), Position2D::from(-8, 20)).is_none());
+	}
+
+	#[test]
+	fn contains_points() {
+		let capsule = Shape2D::Capsule(Capsule2D::new(5.0, 30.0));
+		assert!(capsule.contains_point(at(10.0, 0.0), Position2D::from(10, 14)));
+		assert!(capsule.contains_point(at(10.0, 0.0), Position2D::from(15, 0)));
+		assert!(!capsule.contains_point(at(10.0, 0.0), Position2D::from(14, 14)));
+		let square = Shape2D::Rectangle(Rectangle::new(20.0, 20.0));
+		assert!(square.contains_point(at(0.0, 0.0), Position2D::from(-9, 9)));
+		assert!(!square.contains_point(at(0.0, 0.0), Position2D::from(-11, 0)));
+		assert!(Shape2D::Circle(Circle::new(2.0)).contains_point(at(1.0, 1.0), Position2D::from(2, 2)));
+	}
+
+	#[test]
+	fn shape_cast_shapes() {
+		let ball = Shape2D::Circle(Circle::new(5.0));
+		let wall = Shape2D::Rectangle(Rectangle::new(20.0, 100.0));
+		assert_hit(shape_cast(&ball, at(0.0, 0.0), Vector2D::from(100.0, 0.0), &wall, at(50.0, 0.0)), (40.0, 0.0), (-1.0, 0.0), 0.35);
+		assert!(shape_cast(&ball, at(0.0, 0.0), Vector2D::from(0.0, 100.0), &wall, at(50.0, 0.0)).is_none());
+
+		// a box landing flat on a box touches along a face
+		let crate_ = Shape2D::Rectangle(Rectangle::new(10.0, 10.0));
+		let floor = Shape2D::Rectangle(Rectangle::new(100.0, 10.0));
+		assert_hit(shape_cast(&crate_, at(3.0, -50.0), Vector2D::from(0.0, 100.0), &floor, at(0.0, 0.0)), (3.0, -5.0), (0.0, -1.0), 0.4);
+
+		let capsule = Shape2D::Capsule(Capsule2D::new(5.0, 30.0));
+		assert_hit(shape_cast(&capsule, at(0.0, 0.0), Vector2D::from(0.0, 50.0), &floor, at(0.0, 30.0)), (0.0, 25.0), (0.0, -1.0), 0.2);
+
+		let overlapping = shape_cast(&ball, at(45.0, 0.0), Vector2D::from(100.0, 0.0), &wall, at(50.0, 0.0)).unwrap();
+		assert_eq!(overlapping.fraction, 0.0);
+		assert!(close(overlapping.normal.x, -1.0));
+	}
+}
@@ -0,0 +1,381 @@
+use crate::{Position2D, Rect2D, Size2D, Sprite2D, Render::Texture};
+
+/// How an `Animation2D` continues after its last frame.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AnimationMode {
+	/// Starts over from the first frame.
+	Loop,
+	/// Plays backwards to the first frame, then forwards again.
+	PingPong,
+	/// Stops on the last frame.
+	Once,
+}
+
+/// One frame of an animation, shown for `duration` seconds.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AnimationFrame {
+	pub sprite: Sprite2D,
+	pub duration: f32,
+}
+
+/// Calls `func` whenever the animation reaches frame `index`.
+#[derive(Copy, Clone, Debug)]
+pub struct AnimationTrigger {
+	pub index: usize,
+	pub func: fn(),
+}
+
+impl AnimationTrigger {
+	pub fn from(index: usize, func: fn()) -> AnimationTrigger {
+		AnimationTrigger { index, func }
+	}
+}
+
+/// Frame-based sprite animation driven by delta time.
+///
+/// ```rust
+/// use Engine::{Window, Size2D, Animation2D, AnimationMode, Render::Renderer2D};
+/// use image::RgbaImage;
+///
+/// fn footstep() { println!("step"); }
+///
+/// let mut renderer = Renderer2D::new(Window::new());
+/// let sheet = renderer.create_texture(RgbaImage::new(64, 16));
+///
+/// let mut walk = Animation2D::from_sheet(sheet, Size2D::from(16, 16), 4, 0.1).mode(AnimationMode::PingPong);
+/// walk.insert_trigger(2, footstep);
+///
+/// assert_eq!(walk.update(0.25), vec![1, 2]);
+/// assert_eq!(walk.frame(), 2);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Animation2D {
+	pub frames: Vec<AnimationFrame>,
+	pub triggers: Vec<AnimationTrigger>,
+	pub mode: AnimationMode,
+	/// Playback rate, 1.0 being normal speed. Negative values count as 0.
+	pub speed: f32,
+	index: usize,
+	elapsed: f32,
+	forward: bool,
+	paused: bool,
+	finished: bool,
+}
+
+impl Animation2D {
+	pub const fn new() -> Animation2D {
+		Animation2D {
+			frames: Vec::new(),
+			triggers: Vec::new(),
+			mode: AnimationMode::Loop,
+			speed: 1.0,
+			index: 0,
+			elapsed: 0.0,
+			forward: true,
+			paused: false,
+			finished: false,
+		}
+	}
+
+	pub fn from(frames: Vec<AnimationFrame>, mode: AnimationMode) -> Animation2D {
+		assert!(frames.iter().all(|frame| frame.duration > 0.0), "frame durations must be positive");
+		Animation2D { frames, mode, ..Animation2D::new() }
+	}
+
+	/// Cuts `count` frames of `frame` pixels out of a sprite sheet, left to
+	/// right and then top to bottom, each shown for `duration` seconds.
+	pub fn from_sheet(texture: Texture, frame: Size2D, count: usize, duration: f32) -> Animation2D {
+		let columns = ((texture.width as f32 / frame.x) as usize).max(1);
+		let frames = (0..count).map(|i| {
+			let position = Position2D { x: (i % columns) as f32 * frame.x, y: (i / columns) as f32 * frame.y };
+			let sprite = Sprite2D::new(texture).source(Rect2D { position, size: frame });
+			AnimationFrame { sprite, duration }
+		}).collect();
+		Animation2D::from(frames, AnimationMode::Loop)
+	}
+
+	pub fn mode(mut self, mode: AnimationMode) -> Animation2D {
+		self.mode = mode;
+		self
+	}
+
+	pub fn speed(mut self, speed: f32) -> Animation2D {
+		self.speed = speed;
+		self
+	}
+
+	pub fn insert_frame(&mut self, sprite: Sprite2D, duration: f32) {
+		assert!(duration > 0.0, "frame durations must be positive");
+		self.frames.push(AnimationFrame { sprite, duration });
+	}
+
+	pub fn insert_trigger(&mut self, index: usize, func: fn()) {
+		self.triggers.push(AnimationTrigger::from(index, func));
+	}
+
+	/// Advances the animation by `delta` seconds, scaled by `speed`, and
+	/// returns the indices of the frames reached in order. Triggers of those
+	/// frames are called as they are reached.
+	///
+	/// Frames whose duration is not positive are passed straight through.
+	/// Repeating animations play out at most one cycle of a large `delta`
+	/// and skip the whole cycles before it; an infinite one leaves them
+	/// where they are.
+	pub fn update(&mut self, delta: f32) -> Vec<usize> {
+		let mut reached = Vec::new();
+		if self.paused || self.finished || self.frames.is_empty() {
+			return reached;
+		}
+
+		self.elapsed += delta * self.speed.max(0.0);
+		if self.elapsed.is_nan() {
+			self.elapsed = 0.0;
+		}
+		if let Some(cycle) = self.cycle_duration() {
+			if cycle <= 0.0 || !self.elapsed.is_finite() {
+				self.elapsed = 0.0;
+				return reached;
+			}
+			if self.elapsed >= cycle {
+				self.elapsed = self.elapsed % cycle + cycle;
+			}
+		}
+		while self.elapsed >= self.duration(self.index) {
+			self.elapsed -= self.duration(self.index);
+			match self.next_index() {
+				Some(index) => self.index = index,
+				None => {
+					self.finished = true;
+					self.elapsed = 0.0;
+					break;
+				},
+			}
+			reached.push(self.index);
+			for trigger in self.triggers.iter().filter(|trigger| trigger.index == self.index) {
+				(trigger.func)();
+			}
+		}
+		reached
+	}
+
+	/// Time shown on frame `index`, with non-positive durations counting as 0.
+	fn duration(&self, index: usize) -> f32 {
+		let duration = self.frames[index].duration;
+		if duration > 0.0 { duration } else { 0.0 }
+	}
+
+	/// Time after which a repeating animation is back on the same frame,
+	/// heading the same way. `None` for animations that play once.
+	fn cycle_duration(&self) -> Option<f32> {
+		let last = self.frames.len() - 1;
+		let total: f32 = (0..=last).map(|index| self.duration(index)).sum();
+		match self.mode {
+			AnimationMode::Loop => Some(total),
+			AnimationMode::PingPong if last == 0 => Some(total),
+			AnimationMode::PingPong => Some(2.0 * total - self.duration(0) - self.duration(last)),
+			AnimationMode::Once => None,
+		}
+	}
+
+	fn next_index(&mut self) -> Option<usize> {
+		let last = self.frames.len() - 1;
+		match self.mode {
+			AnimationMode::Loop => Some(if self.index == last { 0 } else { self.index + 1 }),
+			AnimationMode::Once => if self.index == last { None } else { Some(self.index + 1) },
+			AnimationMode::PingPong => {
+				if last == 0 {
+					return Some(0);
+				}
+				if (self.forward && self.index == last) || (!self.forward && self.index == 0) {
+					self.forward = !self.forward;
+				}
+				Some(if self.forward { self.index + 1 } else { self.index - 1 })
+			},
+		}
+	}
+
+	pub fn pause(&mut self) {
+		self.paused = true;
+	}
+
+	pub fn resume(&mut self) {
+		self.paused = false;
+	}
+
+	pub fn is_paused(&self) -> bool {
+		self.paused
+	}
+
+	/// Whether a `Once` animation has played its last frame.
+	pub fn is_finished(&self) -> bool {
+		self.finished
+	}
+
+	/// Rewinds to the first frame and plays again.
+	pub fn restart(&mut self) {
+		self.set_frame(0);
+		self.paused = false;
+	}
+
+	/// Jumps to frame `index` without calling its triggers.
+	pub fn set_frame(&mut self, index: usize) {
+		assert!(index < self.frames.len(), "frame index out of range");
+		self.index = index;
+		self.elapsed = 0.0;
+		self.forward = true;
+		self.finished = false;
+	}
+
+	pub fn frame(&self) -> usize {
+		self.index
+	}
+
+	/// The sprite of the current frame, if the animation has any frames.
+	pub fn sprite(&self) -> Option<&Sprite2D> {
+		self.frames.get(self.index).map(|frame| &frame.sprite)
+	}
+}
+
+impl Default for Animation2D {
+	fn default() -> Animation2D {
+		Animation2D::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Window, Render::Renderer2D};
+	use image::RgbaImage;
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	fn animation(count: usize, mode: AnimationMode) -> Animation2D {
+		let texture = Renderer2D::new(Window::new()).create_texture(RgbaImage::new(32, 16));
+		Animation2D::from_sheet(texture, Size2D::from(8, 8), count, 0.5).mode(mode)
+	}
+
+	#[test]
+	fn animation_2d_new() {
+		let mut animation = Animation2D::new();
+		assert!(animation.sprite().is_none());
+		assert_eq!(animation.update(1.0), Vec::<usize>::new());
+		assert_eq!((animation.frame(), animation.speed, animation.mode), (0, 1.0, AnimationMode::Loop));
+	}
+
+	#[test]
+	fn animation_2d_from_sheet() {
+		let animation = animation(6, AnimationMode::Loop);
+		assert_eq!(animation.frames.len(), 6);
+		assert_eq!(animation.frames[1].sprite.source, Some(Rect2D::from(8, 0, 8, 8)));
+		assert_eq!(animation.frames[5].sprite.source, Some(Rect2D::from(8, 8, 8, 8)));
+		assert_eq!(animation.frames[5].sprite.size, Size2D::from(8, 8));
+	}
+
+	#[test]
+	fn animation_2d_loop() {
+		let mut animation = animation(3, AnimationMode::Loop);
+		assert_eq!(animation.update(0.4), Vec::<usize>::new());
+		assert_eq!(animation.update(0.1), vec![1]);
+		assert_eq!(animation.update(1.0), vec![2, 0]);
+		assert!(!animation.is_finished());
+	}
+
+	#[test]
+	fn animation_2d_ping_pong() {
+		let mut animation = animation(3, AnimationMode::PingPong);
+		assert_eq!(animation.update(3.0), vec![1, 2, 1, 0, 1, 2]);
+	}
+
+	#[test]
+	fn animation_2d_once() {
+		let mut animation = animation(3, AnimationMode::Once);
+		assert_eq!(animation.update(5.0), vec![1, 2]);
+		assert!(animation.is_finished());
+		assert_eq!(animation.frame(), 2);
+		assert_eq!(animation.update(1.0), Vec::<usize>::new());
+		animation.restart();
+		assert_eq!((animation.frame(), animation.is_finished()), (0, false));
+	}
+
+	#[test]
+	fn animation_2d_frame_durations() {
+		let mut animation = animation(2, AnimationMode::Loop);
+		animation.frames[0].duration = 0.3;
+		animation.frames[1].duration = 1.0;
+		assert_eq!(animation.update(0.5), vec![1]);
+		assert_eq!(animation.update(0.5), Vec::<usize>::new());
+		assert_eq!(animation.update(0.5), vec![0]);
+	}
+
+	#[test]
+	fn animation_2d_pause_speed() {
+		let mut animation = animation(3, AnimationMode::Loop).speed(2.0);
+		animation.pause();
+		assert!(animation.is_paused());
+		assert_eq!(animation.update(1.0), Vec::<usize>::new());
+		animation.resume();
+		assert_eq!(animation.update(0.5), vec![1, 2]);
+		animation.speed = -1.0;
+		assert_eq!(animation.update(10.0), Vec::<usize>::new());
+	}
+
+	static TRIGGERED: AtomicUsize = AtomicUsize::new(0);
+
+	fn count_trigger() {
+		TRIGGERED.fetch_add(1, Ordering::SeqCst);
+	}
+
+	#[test]
+	fn animation_2d_triggers() {
+		let mut animation = animation(4, AnimationMode::Loop);
+		animation.insert_trigger(2, count_trigger);
+		animation.insert_trigger(0, count_trigger);
+		animation.update(1.0);
+		assert_eq!(TRIGGERED.load(Ordering::SeqCst), 1);
+		animation.set_frame(1);
+		assert_eq!(TRIGGERED.load(Ordering::SeqCst), 1);
+		animation.update(1.5);
+		assert_eq!(TRIGGERED.load(Ordering::SeqCst), 3);
+	}
+
+	#[test]
+	fn animation_2d_skips_non_positive_durations() {
+		let mut animation = animation(3, AnimationMode::Loop);
+		animation.frames[1].duration = 0.0;
+		assert_eq!(animation.update(0.5), vec![1, 2]);
+		assert_eq!(animation.update(0.5), vec![0]);
+		for frame in animation.frames.iter_mut() {
+			frame.duration = 0.0;
+		}
+		assert_eq!(animation.update(1.0), Vec::<usize>::new());
+		animation.mode = AnimationMode::Once;
+		assert_eq!(animation.update(1.0), vec![1, 2]);
+		assert!(animation.is_finished());
+	}
+
+	#[test]
+	fn animation_2d_large_delta() {
+		let mut looping = animation(3, AnimationMode::Loop);
+		assert_eq!(looping.update(999_999.25), vec![1, 2, 0]);
+		assert_eq!(looping.update(f32::INFINITY), Vec::<usize>::new());
+		assert_eq!(looping.update(f32::NAN), Vec::<usize>::new());
+		assert_eq!(looping.update(0.5), vec![1]);
+
+		let mut ping_pong = animation(3, AnimationMode::PingPong);
+		ping_pong.update(0.5);
+		assert_eq!(ping_pong.update(200.5), vec![2, 1, 0, 1, 2]);
+		assert_eq!(ping_pong.update(f32::INFINITY), Vec::<usize>::new());
+
+		let mut once = animation(3, AnimationMode::Once);
+		assert_eq!(once.update(f32::INFINITY), vec![1, 2]);
+		assert!(once.is_finished());
+	}
+
+	#[test]
+	#[should_panic]
+	fn animation_2d_zero_duration() {
+		let mut animation = animation(1, AnimationMode::Loop);
+		let sprite = animation.frames[0].sprite;
+		animation.insert_frame(sprite, 0.0);
+	}
+}
@@ -0,0 +1,412 @@
+use crate::{Entity, Math, Window, World, FixedTimestep, Systems, SystemStage, Input::{InputState, InputEvent, ActionMap, Recording, Replay, Key}, Render::Renderer2D};
+use crate::{Collision::CollisionEvent2D, Physics::{BodyId, PhysicsWorld2D}};
+
+/// A stateful game driven by the engine.
+///
+/// Every method has an empty default so a game only implements the
+/// parts of the lifecycle it cares about.
+///
+/// ```rust,no_run
+/// use Engine::{Window, Size2D, Color, Game, Context};
+///
+/// struct MyGame { frames: u64 }
+///
+/// impl Game for MyGame {
+///     fn update(&mut self, ctx: &mut Context, _dt: f32) {
+///         self.frames += 1;
+///         if ctx.time.total > 10.0 { ctx.quit(); }
+///     }
+/// }
+///
+/// let window = Window::from("Title", Size2D::from(856, 482), Color::BLACK);
+/// Engine::RunGame(window, &mut MyGame { frames: 0 });
+/// ```
+pub trait Game {
+	/// Called once before the first frame.
+	fn start(&mut self, _ctx: &mut Context) {}
+
+	/// Called zero or more times per frame with the fixed step of
+	/// `Context::timestep`, before `update`.
+	fn physics_update(&mut self, _ctx: &mut Context, _dt: f32) {}
+
+	/// Called after every step of `Context::physics` for each collision
+	/// event of the step, right after the `physics_update` it follows.
+	fn collision(&mut self, _ctx: &mut Context, _event: CollisionEvent2D<BodyId>) {}
+
+	/// Called once per frame with the frame delta in seconds.
+	fn update(&mut self, _ctx: &mut Context, _dt: f32) {}
+
+	/// Called once per frame after `update` to queue shapes on
+	/// `Context::renderer`. `Context::time.alpha` holds the interpolation
+	/// factor between the last two physics steps.
+	fn draw(&mut self, _ctx: &mut Context) {}
+
+	/// Called once after the last frame.
+	fn end(&mut self) {}
+}
+
+/// Frame timing handed to the game through the `Context`.
+#[derive(Copy, Clone, Debug)]
+pub struct Time {
+	pub delta: f64,
+	pub total: f64,
+	pub frame: u64,
+	pub ticks: u64,
+	pub alpha: f64,
+}
+
+impl Time {
+	pub const fn new() -> Time {
+		Time { delta: 0.0, total: 0.0, frame: 0, ticks: 0, alpha: 0.0 }
+	}
+
+	/// Advances the clock by `delta` seconds and mirrors it into the
+	/// `Math` time globals.
+	pub fn tick(&mut self, delta: f64) {
+		self.delta = delta;
+		self.total += delta;
+		self.frame += 1;
+
+		unsafe {
+			Math::DELTA_TIME = self.delta;
+			Math::TOTAL_ELAPSED_SECONDS = self.total;
+		}
+	}
+}
+
+impl Default for Time {
+	fn default() -> Time {
+		Time::new()
+	}
+}
+
+/// Engine state passed to every `Game` callback.
+pub struct Context {
+	pub window: Window,
+	pub time: Time,
+	pub timestep: FixedTimestep,
+	pub input: InputState,
+	pub actions: ActionMap,
+	pub renderer: Renderer2D,
+	pub world: World,
+	pub systems: Systems,
+	/// Stepped after every `Game::physics_update`. Entities join it
+	/// through the `sync_physics` system.
+	pub physics: PhysicsWorld2D,
+	/// Bodies `sync_physics` added for entities.
+	pub(crate) entity_bodies: Vec<(Entity, BodyId)>,
+	pending: Vec<InputEvent>,
+	recording: Option<Recording>,
+	replay: Option<Replay>,
+	quit: bool,
+}
+
+impl Context {
+	pub fn new(window: Window) -> Context {
+		Context {
+			window,
+			time: Time::new(),
+			timestep: FixedTimestep::new(),
+			input: InputState::new(),
+			actions: ActionMap::new(),
+			renderer: Renderer2D::new(window),
+			world: World::new(),
+			systems: Systems::new(),
+			physics: PhysicsWorld2D::new(),
+			entity_bodies: Vec::new(),
+			pending: Vec::new(),
+			recording: None,
+			replay: None,
+			quit: false,
+		}
+	}
+
+	/// Requests the engine to stop after the current frame.
+	pub fn quit(&mut self) {
+		self.quit = true;
+	}
+
+	pub fn is_quitting(&self) -> bool {
+		self.quit
+	}
+
+	/// Queues a simulated input event that is handled at the start of the
+	/// next frame as if it came from a device, e.g. for headless tests.
+	pub fn send_input(&mut self, event: InputEvent) {
+		self.pending.push(event);
+	}
+
+	/// Starts recording every input event and frame delta from the next frame on.
+	pub fn start_recording(&mut self) {
+		self.recording = Some(Recording::new());
+	}
+
+	/// Stops recording and returns what was recorded.
+	pub fn stop_recording(&mut self) -> Option<Recording> {
+		self.recording.take()
+	}
+
+	pub fn is_recording(&self) -> bool {
+		self.recording.is_some()
+	}
+
+	/// Replaces live input and frame times with `recording` from the next
+	/// frame on. Live input resumes once the recording runs out.
+	pub fn start_replay(&mut self, recording: Recording) {
+		self.replay = Some(Replay::new(recording));
+	}
+
+	pub fn is_replaying(&self) -> bool {
+		self.replay.is_some()
+	}
+}
+
+/// Runs one frame of the game lifecycle shared by every runner, given the
+/// input events collected since the last frame.
+pub(crate) fn frame<G: Game>(game: &mut G, ctx: &mut Context, mut events: Vec<InputEvent>, mut delta: f64) {
+	events.append(&mut ctx.pending);
+
+	if let Some(replay) = ctx.replay.as_mut() {
+		match replay.next_frame() {
+			Some((recorded_delta, recorded_events)) => {
+				delta = recorded_delta;
+				events = recorded_events;
+			},
+			None => ctx.replay = None,
+		}
+	}
+
+	ctx.input.begin_frame();
+	for event in events {
+		if let Some(recording) = ctx.recording.as_mut() {
+			recording.push_event(event);
+		}
+		ctx.input.handle(event);
+	}
+	if let Some(recording) = ctx.recording.as_mut() {
+		recording.push_frame(delta);
+	}
+
+	ctx.time.tick(delta);
+	ctx.actions.update(&ctx.input);
+
+	if ctx.input.quit_on_escape && ctx.input.was_key_pressed(Key::Escape) {
+		ctx.quit();
+	}
+
+	let steps = ctx.timestep.advance(delta);
+	let step = ctx.timestep.step();
+	for _ in 0..steps {
+		game.physics_update(ctx, step as f32);
+		ctx.physics.step(step as f32);
+		for event in ctx.physics.events().to_vec() {
+			game.collision(ctx, event);
+		}
+		Systems::run(ctx, SystemStage::Fixed, step as f32);
+		ctx.time.ticks += 1;
+	}
+	ctx.time.alpha = ctx.timestep.alpha();
+
+	ctx.renderer.clear();
+	game.update(ctx, delta as f32);
+	Systems::run(ctx, SystemStage::Update, delta as f32);
+	game.draw(ctx);
+	Systems::run(ctx, SystemStage::Draw, delta as f32);
+}
+
+/// Adapts the plain `fn()` callbacks accepted by `Run` to the `Game` trait.
+pub(crate) struct Callbacks {
+	pub start: fn(),
+	pub update: fn(),
+	pub end: fn(),
+}
+
+impl Game for Callbacks {
+	fn start(&mut self, _ctx: &mut Context) {
+		(self.start)();
+	}
+
+	fn update(&mut self, _ctx: &mut Context, _dt: f32) {
+		(self.update)();
+	}
+
+	fn end(&mut self) {
+		(self.end)();
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Input::{InputEvent, Action, Binding};
+
+	struct Counter {
+		updates: u32,
+		draws: u32,
+		ticks: u32,
+		last_dt: f32,
+	}
+
+	impl Game for Counter {
+		fn physics_update(&mut self, _ctx: &mut Context, _dt: f32) {
+			self.ticks += 1;
+		}
+
+		fn update(&mut self, ctx: &mut Context, dt: f32) {
+			self.updates += 1;
+			self.last_dt = dt;
+			if self.updates == 2 {
+				ctx.quit();
+			}
+		}
+
+		fn draw(&mut self, ctx: &mut Context) {
+			self.draws += 1;
+			ctx.renderer.draw_rectangle(&crate::Visual::Rectangle::new(1.0, 1.0), crate::Transform2D::new());
+		}
+	}
+
+	#[test]
+	fn time_new() {
+		let time = Time::new();
+		assert_eq!((time.delta, time.total, time.frame, time.ticks, time.alpha), (0.0, 0.0, 0, 0, 0.0));
+	}
+
+	#[test]
+	fn time_tick() {
+		let mut time = Time::new();
+		time.tick(0.5);
+		time.tick(0.25);
+		assert_eq!((time.delta, time.total, time.frame), (0.25, 0.75, 2));
+	}
+
+	#[test]
+	fn context_quit() {
+		let mut ctx = Context::new(Window::new());
+		assert!(!ctx.is_quitting());
+		ctx.quit();
+		assert!(ctx.is_quitting());
+	}
+
+	#[test]
+	fn context_send_input() {
+		let mut game = Counter { updates: 0, draws: 0, ticks: 0, last_dt: 0.0 };
+		let mut ctx = Context::new(Window::new());
+		ctx.send_input(InputEvent::Key(Key::Q, Action::Press));
+		assert!(!ctx.input.is_key_down(Key::Q));
+		frame(&mut game, &mut ctx, Vec::new(), 0.1);
+		assert!(ctx.input.was_key_pressed(Key::Q));
+		frame(&mut game, &mut ctx, Vec::new(), 0.1);
+		assert!(!ctx.input.was_key_pressed(Key::Q));
+	}
+
+	#[test]
+	fn game_frame() {
+		let mut game = Counter { updates: 0, draws: 0, ticks: 0, last_dt: 0.0 };
+		let mut ctx = Context::new(Window::new());
+		frame(&mut game, &mut ctx, Vec::new(), 0.5);
+		assert_eq!((game.updates, game.draws, game.last_dt), (1, 1, 0.5));
+		assert!(!ctx.is_quitting());
+		frame(&mut game, &mut ctx, Vec::new(), 0.5);
+		assert!(ctx.is_quitting());
+		assert_eq!(ctx.time.frame, 2);
+	}
+
+	#[test]
+	fn game_frame_draw() {
+		let mut game = Counter { updates: 0, draws: 0, ticks: 0, last_dt: 0.0 };
+		let mut ctx = Context::new(Window::new());
+		frame(&mut game, &mut ctx, Vec::new(), 0.1);
+		frame(&mut game, &mut ctx, Vec::new(), 0.1);
+		assert_eq!(ctx.renderer.vertices().len(), 6);
+		assert_eq!(ctx.renderer.draw_calls(), 1);
+	}
+
+	#[test]
+	fn game_frame_escape() {
+		let mut game = Counter { updates: 0, draws: 0, ticks: 0, last_dt: 0.0 };
+		let mut ctx = Context::new(Window::new());
+		ctx.input.quit_on_escape = false;
+		frame(&mut game, &mut ctx, vec![InputEvent::Key(Key::Escape, Action::Press)], 0.1);
+		assert!(!ctx.is_quitting());
+		ctx.input.quit_on_escape = true;
+		frame(&mut game, &mut ctx, vec![InputEvent::Key(Key::Escape, Action::Release), InputEvent::Key(Key::Escape, Action::Press)], 0.1);
+		assert!(ctx.is_quitting());
+	}
+
+	#[test]
+	fn game_frame_actions() {
+		let mut game = Counter { updates: 0, draws: 0, ticks: 0, last_dt: 0.0 };
+		let mut ctx = Context::new(Window::new());
+		ctx.actions.bind("jump", Binding::Key(Key::Space));
+		frame(&mut game, &mut ctx, vec![InputEvent::Key(Key::Space, Action::Press)], 0.1);
+		assert!(ctx.actions.was_pressed("jump"));
+	}
+
+	#[test]
+	fn game_frame_fixed_steps() {
+		let mut game = Counter { updates: 0, draws: 0, ticks: 0, last_dt: 0.0 };
+		let mut ctx = Context::new(Window::new());
+		ctx.timestep = FixedTimestep::from(10, 4);
+		frame(&mut game, &mut ctx, Vec::new(), 0.25);
+		assert_eq!((game.ticks, game.updates), (2, 1));
+		assert_eq!(ctx.time.ticks, 2);
+		assert!((ctx.time.alpha - 0.5).abs() < 1e-9);
+	}
+
+	#[test]
+	fn game_frame_record_replay() {
+		let mut game = Counter { updates: 0, draws: 0, ticks: 0, last_dt: 0.0 };
+		let mut ctx = Context::new(Window::new());
+		ctx.start_recording();
+		frame(&mut game, &mut ctx, vec![InputEvent::Key(Key::W, Action::Press)], 0.25);
+		frame(&mut game, &mut ctx, Vec::new(), 0.5);
+		let recording = ctx.stop_recording().unwrap();
+		assert!(!ctx.is_recording());
+		assert_eq!(recording.len(), 2);
+		assert_eq!(recording.events(0), vec![InputEvent::Key(Key::W, Action::Press)]);
+
+		let mut ctx = Context::new(Window::new());
+		ctx.start_replay(recording);
+		frame(&mut game, &mut ctx, vec![InputEvent::Key(Key::S, Action::Press)], 1.0);
+		assert!(ctx.input.is_key_down(Key::W) && !ctx.input.is_key_down(Key::S));
+		assert_eq!(ctx.time.delta, 0.25);
+		frame(&mut game, &mut ctx, Vec::new(), 1.0);
+		assert!(ctx.is_replaying());
+		frame(&mut game, &mut ctx, vec![InputEvent::Key(Key::S, Action::Press)], 1.0);
+		assert!(!ctx.is_replaying());
+		assert!(ctx.input.is_key_down(Key::S));
+		assert_eq!(ctx.time.total, 1.75);
+	}
+
+	#[test]
+	fn game_frame_collisions() {
+		use crate::{Position2D, Rotation2D, Scale2D, Transform2D, Collision::{Circle, Collision2D, CollisionPhase, Rectangle, Shape2D}, Physics::{BodyType, RigidBody2D}};
+
+		struct Pickup {
+			coin: Option<BodyId>,
+			collected: u32,
+		}
+
+		impl Game for Pickup {
+			fn start(&mut self, ctx: &mut Context) {
+				let below = Transform2D::from(Position2D::from(0, 50), Rotation2D::new(), Scale2D::new());
+				let coin = Collision2D::new(Shape2D::Rectangle(Rectangle::new(20.0, 20.0))).sensor(true);
+				self.coin = Some(ctx.physics.insert(RigidBody2D::new(below).body_type(BodyType::Static), coin));
+				ctx.physics.insert(RigidBody2D::new(Transform2D::new()), Collision2D::new(Shape2D::Circle(Circle::new(5.0))));
+			}
+
+			fn collision(&mut self, ctx: &mut Context, event: CollisionEvent2D<BodyId>) {
+				if event.phase == CollisionPhase::Begin && event.involves(self.coin.unwrap()) {
+					self.collected += 1;
+					ctx.physics.remove(self.coin.unwrap());
+				}
+			}
+		}
+
+		let mut game = Pickup { coin: None, collected: 0 };
+		let ctx = crate::Headless::new(Window::new()).frames(60).run(&mut game);
+		assert_eq!(game.collected, 1);
+		assert_eq!(ctx.physics.len(), 1);
+	}
+}
@@ -0,0 +1,180 @@
+use super::game::frame;
+use crate::{Game, Context, Window};
+
+/// Drives a `Game` without creating a window or an OpenGL context.
+///
+/// Time is simulated: every frame advances the clock by a fixed `delta`,
+/// so runs are reproducible and can be used from `cargo test`. Input can be
+/// simulated with `Context::send_input` or replayed with `Context::start_replay`.
+///
+/// ```rust
+/// use Engine::{Window, Game, Context, Headless};
+///
+/// struct Ticker { ticks: u32 }
+///
+/// impl Game for Ticker {
+///     fn update(&mut self, _ctx: &mut Context, _dt: f32) { self.ticks += 1; }
+/// }
+///
+/// let mut game = Ticker { ticks: 0 };
+/// let ctx = Headless::new(Window::new()).frames(120).run(&mut game);
+/// assert_eq!(game.ticks, 120);
+/// assert_eq!(ctx.time.frame, 120);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Headless {
+	pub window: Window,
+	pub frames: Option<u64>,
+	pub delta: f64,
+}
+
+impl Headless {
+	pub const fn new(window: Window) -> Headless {
+		Headless { window, frames: None, delta: 1.0 / 60.0 }
+	}
+
+	/// Stops after `frames` frames. Without a frame limit the run only ends
+	/// when the game calls `Context::quit`.
+	pub const fn frames(mut self, frames: u64) -> Headless {
+		self.frames = Some(frames);
+		self
+	}
+
+	/// Sets the simulated seconds that pass every frame.
+	pub const fn delta(mut self, delta: f64) -> Headless {
+		self.delta = delta;
+		self
+	}
+
+	/// Runs the full start/update/draw/end lifecycle and returns the final context.
+	pub fn run<G: Game>(self, game: &mut G) -> Context {
+		let mut ctx = Context::new(self.window);
+
+		game.start(&mut ctx);
+
+		while !ctx.is_quitting() && self.frames.is_none_or(|frames| ctx.time.frame < frames) {
+			frame(game, &mut ctx, Vec::new(), self.delta);
+		}
+
+		game.end();
+		ctx
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Input::{Recording, InputEvent, Key, Action};
+
+	struct Lifecycle {
+		started: bool,
+		updates: u64,
+		ended: bool,
+		quit_at: Option<u64>,
+	}
+
+	impl Lifecycle {
+		fn new(quit_at: Option<u64>) -> Lifecycle {
+			Lifecycle { started: false, updates: 0, ended: false, quit_at }
+		}
+	}
+
+	impl Game for Lifecycle {
+		fn start(&mut self, _ctx: &mut Context) {
+			self.started = true;
+		}
+
+		fn update(&mut self, ctx: &mut Context, _dt: f32) {
+			self.updates += 1;
+			if Some(ctx.time.frame) == self.quit_at {
+				ctx.quit();
+			}
+		}
+
+		fn end(&mut self) {
+			self.ended = true;
+		}
+	}
+
+	#[test]
+	fn headless_new() {
+		let headless = Headless::new(Window::new());
+		assert_eq!(headless.window, Window::new());
+		assert_eq!(headless.frames, None);
+		assert_eq!(headless.delta, 1.0 / 60.0);
+	}
+
+	#[test]
+	fn headless_frames() {
+		let mut game = Lifecycle::new(None);
+		let ctx = Headless::new(Window::new()).frames(10).run(&mut game);
+		assert!(game.started && game.ended);
+		assert_eq!(game.updates, 10);
+		assert_eq!(ctx.time.frame, 10);
+	}
+
+	#[test]
+	fn headless_quit() {
+		let mut game = Lifecycle::new(Some(5));
+		let ctx = Headless::new(Window::new()).run(&mut game);
+		assert!(game.ended);
+		assert_eq!(game.updates, 5);
+		assert!(ctx.is_quitting());
+	}
+
+	#[test]
+	fn headless_delta() {
+		let mut game = Lifecycle::new(None);
+		let ctx = Headless::new(Window::new()).frames(4).delta(0.25).run(&mut game);
+		assert_eq!(ctx.time.delta, 0.25);
+		assert_eq!(ctx.time.total, 1.0);
+	}
+
+	struct Mover {
+		x: f32,
+		replay: Option<Recording>,
+		recorded: Option<Recording>,
+	}
+
+	impl Game for Mover {
+		fn start(&mut self, ctx: &mut Context) {
+			match self.replay.take() {
+				Some(recording) => ctx.start_replay(recording),
+				None => ctx.start_recording(),
+			}
+		}
+
+		fn physics_update(&mut self, ctx: &mut Context, dt: f32) {
+			if ctx.input.is_key_down(Key::D) {
+				self.x += 100.0 * dt;
+			}
+		}
+
+		fn update(&mut self, ctx: &mut Context, _dt: f32) {
+			// simulate the player pressing and releasing D while recording
+			if ctx.is_recording() {
+				match ctx.time.frame {
+					3 => ctx.send_input(InputEvent::Key(Key::D, Action::Press)),
+					9 => ctx.send_input(InputEvent::Key(Key::D, Action::Release)),
+					_ => {},
+				}
+			}
+			if ctx.time.frame == 12 {
+				self.recorded = ctx.stop_recording();
+			}
+		}
+	}
+
+	#[test]
+	fn headless_replay() {
+		let mut recorded = Mover { x: 0.0, replay: None, recorded: None };
+		Headless::new(Window::new()).frames(12).run(&mut recorded);
+		let recording = recorded.recorded.clone().unwrap();
+		assert_eq!(recording.len(), 12);
+		assert!(recorded.x > 0.0);
+
+		let mut replayed = Mover { x: 0.0, replay: Some(recording), recorded: None };
+		Headless::new(Window::new()).frames(12).run(&mut replayed);
+		assert_eq!(replayed.x, recorded.x);
+	}
+}
@@ -1,31 +1,45 @@
-use crate::{Camera2D, Debug, Math::{self, Matrix4x4, Vector2D}, gl::{self, types::*}, glfw::{self, Context, Action, Key}, shader};
+use crate::{Debug, Input::{InputState, InputEvent, GamepadSnapshot, MAX_GAMEPADS}, Render::{Backend, GlBackend, Presenter}, glfw::{self, Context as _}};
 use image::{self, RgbaImage};
 
 use std::sync::mpsc::Receiver;
-use std::ptr;
-use std::mem;
-use std::os::raw::c_void;
 
+mod animation;
 mod color;
+mod game;
+mod headless;
 mod position;
+mod rect;
 mod rotation;
 mod scale;
 mod size;
+mod sprite;
+mod systems;
+mod timestep;
 mod transform;
 mod window;
 
+pub use animation::*;
 pub use color::*;
+pub use game::*;
+pub use headless::*;
 pub use position::*;
+pub use rect::*;
 pub use rotation::*;
 pub use scale::*;
 pub use size::*;
+pub use sprite::*;
+pub use systems::*;
+pub use timestep::*;
 pub use transform::*;
 pub use window::*;
 
 pub fn Run(win: Window, start: fn(), update: fn(), end: fn()) {
+	RunGame(win, &mut Callbacks { start, update, end });
+}
+
+pub fn RunGame<G: Game>(win: Window, game: &mut G) {
 
-	start(); // user initialize function
-	//Initialize();
+	let mut ctx = Context::new(win);
 
 	// glfw: initialize and configure
 	// ------------------------------
@@ -66,114 +80,41 @@ pub fn Run(win: Window, start: fn(), update: fn(), end: fn()) {
 
 	window.make_current();
 	window.set_key_polling(true);
+	window.set_mouse_button_polling(true);
+	window.set_cursor_pos_polling(true);
+	window.set_scroll_polling(true);
+	window.set_size_polling(true);
 	window.set_framebuffer_size_polling(true);
 
 	// gl: load all OpenGL function pointers
 	// ---------------------------------------
-	gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
-
-	unsafe { gl::Viewport(0, 0, win.size.x as i32, win.size.y as i32) }
+	let mut presenter = Presenter::new(GlBackend::new(&mut window));
+	presenter.backend.resize(win.size.x as u32, win.size.y as u32);
 
 	glfw.set_swap_interval(glfw::SwapInterval::None); // VSync off (0)
 
-	//load(); // user load content function
-	// load content LoadContent();
-	// -----------
-
-	let (shader_program, VAO) = unsafe {
-
-		let shader_program = shader::Load("shaders/VertexShader.vert", "shaders/FragmentShader.frag");
-
-		let (mut VBO, mut VAO) = (0, 0);
-
-		gl::GenVertexArrays(1, &mut VAO);
-		gl::GenBuffers(1, &mut VBO);
-
-		gl::BindVertexArray(VAO);
+	let mut last_time: f64 = glfw.get_time();
 
-		gl::BindBuffer(gl::ARRAY_BUFFER, VBO);
-
-		let vertices: [f32; 30] = [
-			-0.5,  0.5,	 1.0, 0.0, 0.0, // top left
-			 0.5,  0.5,	 0.0, 1.0, 0.0, // top right
-			-0.5, -0.5,	 0.0, 0.0, 1.0, // bottom left
-
-			 0.5,  0.5,	 0.0, 1.0, 0.0, // top right
-			 0.5, -0.5,	 0.0, 1.0, 1.0, // bottom right
-			-0.5, -0.5,	 0.0, 0.0, 1.0, // bottom left
-		];
-
-		gl::BufferData(
-			gl::ARRAY_BUFFER,
-			(mem::size_of::<GLfloat>() * vertices.len()) as GLsizeiptr,
-			&vertices[0] as *const f32 as *const c_void,
-			gl::STATIC_DRAW
-		);
-
-		gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, 5 * mem::size_of::<GLfloat>() as GLsizei, ptr::null());
-		gl::EnableVertexAttribArray(0);
-
-		//let my_num_ptr: *const GLsizei = &(5 * mem::size_of::<GLfloat>() as GLsizei);
-		gl::VertexAttribPointer(1, 3, gl::FLOAT, gl::FALSE, 5 * mem::size_of::<GLfloat>() as GLsizei, ((2 * mem::size_of::<GLfloat>()) as GLsizei) as *const c_void);
-		gl::EnableVertexAttribArray(1);
-
-		gl::BindBuffer(gl::ARRAY_BUFFER, 0);
-		gl::BindVertexArray(0);
-
-		(shader_program, VAO)
-	};
-
-	let cam = Camera2D::from(Vector2D::from(win.size.x / 2.0, win.size.y / 2.0), 1.0);
-	let mut _delta_time: f64 = 0.0;
-	let mut total_elapsed_seconds: f64 = 0.0;
+	game.start(&mut ctx); // user initialize function
 
 	// render loop
 	// -----------
 	while !window.should_close() {
 
-		_delta_time = glfw.get_time() - total_elapsed_seconds;
-		total_elapsed_seconds = glfw.get_time();
-
-		unsafe {
-			Math::DELTA_TIME = _delta_time;
-			Math::TOTAL_ELAPSED_SECONDS = total_elapsed_seconds;
-		}
+		let now = glfw.get_time();
+		let delta_time = now - last_time;
+		last_time = now;
 
 		// events
         // -----
-		process_events(&mut window, &events);
-
-		update(); // user update function
-		//Update();
-
-		glfw.poll_events();
-
-		//render(); // user render function
-		// render
-		// ------
-		unsafe {
-			let win_color = win.color.unit_interval();
-			gl::ClearColor(win_color[0], win_color[1], win_color[2], win_color[3]);
-			gl::Clear(gl::COLOR_BUFFER_BIT);
-
-			let position = Position2D::from(400, 300);
-			let scale = Scale3D::from(150, 100, 1);
-			let rotation = Rotation2D::from_rad((total_elapsed_seconds.sin() * Math::TWO_PIE_F64) as f32);
-			//Rotation2D::from_rad(std::f32::consts::PI / 4.0);
-
-			let trans = Matrix4x4::create_translation(position.x, position.y, 0.0);
-			let sca = Matrix4x4::create_scale(scale);
-			let rot = Matrix4x4::create_rotation_z(rotation);
-
-			shader::SetMatrix4x4(shader_program, "model", sca.mult(rot).mult(trans));
-
-			shader::Use(vec![shader_program]);
-			shader::SetMatrix4x4(shader_program, "projection", cam.get_projection_matrix(win));
+		let mut input_events = process_events(&events, &mut presenter.backend);
+		input_events.append(&mut poll_gamepads(&glfw, &ctx.input));
 
-			gl::BindVertexArray(VAO);
-			gl::DrawArrays(gl::TRIANGLES, 0, 6);
-			gl::BindVertexArray(0);
+		frame(game, &mut ctx, input_events, delta_time); // user update and draw functions
+		presenter.present(&mut ctx.renderer, ctx.window);
 
+		if ctx.is_quitting() {
+			window.set_should_close(true);
 		}
 
 		// glfw: swap buffers and poll IO events (keys pressed/released, mouse moved etc.)
@@ -183,41 +124,36 @@ pub fn Run(win: Window, start: fn(), update: fn(), end: fn()) {
 		//glfw.wait_events();
 	}
 
-	end();
+	game.end(); // user end function
 }
 
 
-fn process_events(window: &mut glfw::Window, events: &Receiver<(f64, glfw::WindowEvent)>) {
+fn process_events<B: Backend>(events: &Receiver<(f64, glfw::WindowEvent)>, backend: &mut B) -> Vec<InputEvent> {
+	let mut input_events = Vec::new();
     for (_, event) in glfw::flush_messages(events) {
         match event { // general
             glfw::WindowEvent::FramebufferSize(width, height) => {
                 // make sure the viewport matches the new window dimensions; note that width and
                 // height will be significantly larger than specified on retina displays.
-                unsafe { gl::Viewport(0, 0, width, height) }
+                backend.resize(width as u32, height as u32);
             }
             _ => {}
 		}
-		KeyboardHandler(window, event);
+		if let Some(event) = InputEvent::from_glfw(&event) {
+			input_events.push(event);
+		}
     }
+	input_events
 }
 
-fn KeyboardHandler(window: &mut glfw::Window, event: glfw::WindowEvent) {
-	match event {
-		glfw::WindowEvent::Key(Key::Escape, _, Action::Press, _) => window.set_should_close(true),
-		glfw::WindowEvent::Key(Key::W, _, Action::Press, _) => {
-			println!("Up ^");
-		},
-		glfw::WindowEvent::Key(Key::A, _, Action::Press, _) => {
-			println!("Left <-");
-		},
-		glfw::WindowEvent::Key(Key::S, _, Action::Press, _) => {
-			println!("Down v");
-		},
-		glfw::WindowEvent::Key(Key::D, _, Action::Press, _) => {
-			println!("Right ->");
-		},
-		_ => {}
+fn poll_gamepads(glfw: &glfw::Glfw, input: &InputState) -> Vec<InputEvent> {
+	let mut input_events = Vec::new();
+	for id in 0..MAX_GAMEPADS {
+		let joystick = glfw.get_joystick(glfw::JoystickId::from_i32(id as i32).unwrap());
+		let snapshot = joystick.get_gamepad_state().map(|state| GamepadSnapshot::from_glfw(&state));
+		input_events.append(&mut input.gamepads[id].changes(snapshot));
 	}
+	input_events
 }
 
 // todo: fix icon data generator (make it work!)
@@ -238,111 +174,3 @@ fn gen_icon() -> Vec<glfw::PixelImage> {
 		}
 	]
 }
-
-/*
-// todo: create unit tests and impls
-#[derive(Copy, Clone, Debug)]
-pub struct Sprite2D {
-    pub src: &'static str,
-    pub size: Size2D,
-    pub offset: Position2D,
-    pub color: Color,
-}
-
-impl Sprite2D {
-	pub const fn new() -> Sprite2D {
-		Sprite2D { src: "", size: Size2D::new(), offset: Position2D::new(), color: Color::new() }
-	}
-
-    pub const fn from(src: &'static str, size: Size2D, offset: Position2D, color: Color) -> Sprite2D {
-        Sprite2D { src, size, offset, color }
-	}
-
-	pub fn is_valid(self) -> bool {
-		true
-	}
-}
-
-
-#[derive(Copy, Clone, Debug)]
-struct AnimationTrigger {
-	pub index: usize,
-	pub func: fn(),
-}
-
-impl AnimationTrigger {
-	pub fn from(index: usize, func: fn()) -> AnimationTrigger {
-		AnimationTrigger { index, func }
-	}
-}
-/*
-```rust
-fn pork() {
-	println!("i love pork");
-}
-
-let t1 = AnimationTrigger::from(3, pork);
-
-let anim = Animation2D::new();
-
-anim.insert_trigger(t1);
-
-```
-*/
-
-pub enum Trigger {
-	Animation,
-	None,
-}
-
-pub struct Animation2D {
-	pub sprite: Sprite2D,
-	pub index: usize,
-	pub tape: Vec<Sprite2D>,
-	pub triggers: Vec<(usize, fn())>,
-	pub pause: bool,
-}
-
-impl Animation2D {
-	pub const fn new() -> Animation2D {
-		Animation2D { sprite: Sprite2D::new(), index: 0, tape: Vec::<Sprite2D>::new(), triggers: Vec::<AnimationTrigger>::new(), pause: false }
-	}
-
-	pub const fn from(sprite: Sprite2D, tape: Vec<Sprite2D>, triggers: Vec<AnimationTrigger>) -> Animation2D {
-		Animation2D { sprite, index: 0, tape, triggers, pause: false }
-	}
-
-	pub fn insert_sprite(&mut self, sprite: Sprite2D) {
-		self.tape.push(sprite);
-	}
-
-	pub fn set_sprite(&mut self, index: usize) {
-		self.index = index;
-		self.sprite = self.tape[index];
-	}
-
-	pub fn insert_trigger(&mut self, index: usize, func: fn()) {
-		self.triggers.push((index, func));
-	}
-
-	pub fn play(&mut self) {
-		if !self.pause {
-			self.index += 1;
-			self.sprite = self.tape[self.index];
-
-
-			for trigger in &self.triggers {
-				if trigger.0 == self.index {
-					(trigger.1)();
-				}
-			}
-		}
-	}
-
-	pub fn stop(&mut self) {
-		self.pause = true;
-	}
-
-	pub fn Trigger(index: usize, trigger: fn()) ->
-}
-*/
\ No newline at end of file
@@ -0,0 +1,83 @@
+use crate::{Position2D, Size2D};
+use std::cmp::{Eq, PartialEq};
+
+/// Axis-aligned rectangle given by its top left corner and its size.
+#[derive(Clone, Copy, Debug)]
+pub struct Rect2D {
+	pub position: Position2D,
+	pub size: Size2D,
+}
+
+impl Rect2D {
+	pub const fn new() -> Rect2D {
+		Rect2D { position: Position2D::new(), size: Size2D::new() }
+	}
+
+	pub fn from<T: Into<f64>>(x: T, y: T, width: T, height: T) -> Rect2D {
+		Rect2D { position: Position2D::from(x, y), size: Size2D::from(width, height) }
+	}
+
+	pub fn left(self) -> f32 { self.position.x }
+
+	pub fn top(self) -> f32 { self.position.y }
+
+	pub fn right(self) -> f32 { self.position.x + self.size.x }
+
+	pub fn bottom(self) -> f32 { self.position.y + self.size.y }
+
+	/// Whether `point` lies inside, counting the top and left edges but not the bottom and right ones.
+	pub fn contains(self, point: Position2D) -> bool {
+		point.x >= self.left() && point.x < self.right() && point.y >= self.top() && point.y < self.bottom()
+	}
+
+	pub fn intersects(self, other: Rect2D) -> bool {
+		self.left() < other.right() && other.left() < self.right() && self.top() < other.bottom() && other.top() < self.bottom()
+	}
+}
+
+impl Default for Rect2D {
+	fn default() -> Rect2D {
+		Rect2D::new()
+	}
+}
+
+impl Eq for Rect2D {}
+
+impl PartialEq for Rect2D {
+	fn eq(&self, other: &Rect2D) -> bool {
+		self.position == other.position && self.size == other.size
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rect_2d_new() {
+		let rect = Rect2D::new();
+		assert_eq!(rect, Rect2D { position: Position2D { x: 0.0, y: 0.0 }, size: Size2D { x: 0.0, y: 0.0 } });
+	}
+
+	#[test]
+	fn rect_2d_from() {
+		let rect = Rect2D::from(1, 2, 3, 4);
+		assert_eq!(rect, Rect2D { position: Position2D { x: 1.0, y: 2.0 }, size: Size2D { x: 3.0, y: 4.0 } });
+		assert_eq!((rect.left(), rect.top(), rect.right(), rect.bottom()), (1.0, 2.0, 4.0, 6.0));
+	}
+
+	#[test]
+	fn rect_2d_contains() {
+		let rect = Rect2D::from(0, 0, 2, 2);
+		assert!(rect.contains(Position2D::from(0, 0)));
+		assert!(rect.contains(Position2D::from(1.5, 1.5)));
+		assert!(!rect.contains(Position2D::from(2, 1)));
+	}
+
+	#[test]
+	fn rect_2d_intersects() {
+		let rect = Rect2D::from(0, 0, 2, 2);
+		assert!(rect.intersects(Rect2D::from(1, 1, 2, 2)));
+		assert!(!rect.intersects(Rect2D::from(2, 0, 2, 2)));
+	}
+}
@@ -0,0 +1,159 @@
+use crate::{Color, Position2D, Rect2D, Size2D, Render::Texture};
+
+/// A textured quad showing a texture, or the `source` region of it.
+///
+/// The quad is `size` world units large and the `pivot` point, given in
+/// fractions of the size from the top left corner, is placed at `offset`.
+///
+/// ```rust,no_run
+/// use Engine::{Window, Game, Context, Sprite2D, Rect2D, Transform2D};
+///
+/// struct Player { sprite: Option<Sprite2D> }
+///
+/// impl Game for Player {
+///     fn start(&mut self, ctx: &mut Context) {
+///         let texture = ctx.renderer.load_texture("resources/gear.png").unwrap();
+///         self.sprite = Some(Sprite2D::new(texture).source(Rect2D::from(0, 0, 50, 50)).flip_h(true));
+///     }
+///
+///     fn draw(&mut self, ctx: &mut Context) {
+///         ctx.renderer.draw_sprite(self.sprite.as_ref().unwrap(), Transform2D::new());
+///     }
+/// }
+///
+/// Engine::RunGame(Window::new(), &mut Player { sprite: None });
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Sprite2D {
+	pub texture: Texture,
+	pub source: Option<Rect2D>,
+	pub size: Size2D,
+	pub offset: Position2D,
+	pub pivot: Position2D,
+	pub color: Color,
+	pub flip_h: bool,
+	pub flip_v: bool,
+}
+
+impl Sprite2D {
+	/// A sprite showing the whole texture at its pixel size, centered on its position.
+	pub fn new(texture: Texture) -> Sprite2D {
+		Sprite2D {
+			texture,
+			source: None,
+			size: texture.size(),
+			offset: Position2D::new(),
+			pivot: Position2D { x: 0.5, y: 0.5 },
+			color: Color::new(),
+			flip_h: false,
+			flip_v: false,
+		}
+	}
+
+	/// Shows only `source`, in texture pixels, and resizes the sprite to match.
+	pub const fn source(mut self, source: Rect2D) -> Sprite2D {
+		self.source = Some(source);
+		self.size = source.size;
+		self
+	}
+
+	pub const fn size(mut self, size: Size2D) -> Sprite2D {
+		self.size = size;
+		self
+	}
+
+	pub const fn offset(mut self, offset: Position2D) -> Sprite2D {
+		self.offset = offset;
+		self
+	}
+
+	pub const fn pivot(mut self, pivot: Position2D) -> Sprite2D {
+		self.pivot = pivot;
+		self
+	}
+
+	pub const fn color(mut self, color: Color) -> Sprite2D {
+		self.color = color;
+		self
+	}
+
+	pub const fn flip_h(mut self, flip_h: bool) -> Sprite2D {
+		self.flip_h = flip_h;
+		self
+	}
+
+	pub const fn flip_v(mut self, flip_v: bool) -> Sprite2D {
+		self.flip_v = flip_v;
+		self
+	}
+
+	/// Corners of the quad in local space: top left, top right, bottom right, bottom left.
+	pub fn corners(&self) -> [Position2D; 4] {
+		let left = self.offset.x - self.pivot.x * self.size.x;
+		let top = self.offset.y - self.pivot.y * self.size.y;
+		let (right, bottom) = (left + self.size.x, top + self.size.y);
+		[
+			Position2D { x: left, y: top },
+			Position2D { x: right, y: top },
+			Position2D { x: right, y: bottom },
+			Position2D { x: left, y: bottom },
+		]
+	}
+
+	/// Texture coordinates matching `corners`, with the flips applied.
+	pub fn uvs(&self) -> [Position2D; 4] {
+		let (width, height) = (self.texture.width as f32, self.texture.height as f32);
+		let source = self.source.unwrap_or(Rect2D { position: Position2D::new(), size: self.texture.size() });
+		let (mut left, mut right) = (source.left() / width, source.right() / width);
+		let (mut top, mut bottom) = (source.top() / height, source.bottom() / height);
+		if self.flip_h {
+			std::mem::swap(&mut left, &mut right);
+		}
+		if self.flip_v {
+			std::mem::swap(&mut top, &mut bottom);
+		}
+		[
+			Position2D { x: left, y: top },
+			Position2D { x: right, y: top },
+			Position2D { x: right, y: bottom },
+			Position2D { x: left, y: bottom },
+		]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Window, Render::Renderer2D};
+	use image::RgbaImage;
+
+	fn texture() -> Texture {
+		Renderer2D::new(Window::new()).create_texture(RgbaImage::new(8, 4))
+	}
+
+	#[test]
+	fn sprite_2d_new() {
+		let sprite = Sprite2D::new(texture());
+		assert_eq!(sprite.size, Size2D::from(8, 4));
+		assert_eq!(sprite.pivot, Position2D::from(0.5, 0.5));
+		assert_eq!(sprite.color, Color::new());
+		assert_eq!(sprite.corners()[0], Position2D::from(-4, -2));
+		assert_eq!(sprite.uvs()[2], Position2D::from(1, 1));
+	}
+
+	#[test]
+	fn sprite_2d_source() {
+		let sprite = Sprite2D::new(texture()).source(Rect2D::from(2, 0, 2, 2)).pivot(Position2D::new()).offset(Position2D::from(1, 1));
+		assert_eq!(sprite.size, Size2D::from(2, 2));
+		assert_eq!(sprite.corners(), [Position2D::from(1, 1), Position2D::from(3, 1), Position2D::from(3, 3), Position2D::from(1, 3)]);
+		assert_eq!(sprite.uvs()[0], Position2D::from(0.25, 0.0));
+		assert_eq!(sprite.uvs()[2], Position2D::from(0.5, 0.5));
+	}
+
+	#[test]
+	fn sprite_2d_flip() {
+		let sprite = Sprite2D::new(texture()).flip_h(true).flip_v(true);
+		assert_eq!(sprite.uvs()[0], Position2D::from(1, 1));
+		assert_eq!(sprite.uvs()[2], Position2D::from(0, 0));
+	}
+}
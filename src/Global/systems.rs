@@ -0,0 +1,266 @@
+use crate::{Animation2D, Context, Sprite2D, Transform2D, Collision::Collision2D, Physics::{BodyId, RigidBody2D}};
+
+/// A system gets the whole `Context`, usually to query `Context::world`,
+/// and the seconds its stage covers.
+pub type System = fn(&mut Context, f32);
+
+/// When during a frame a system runs.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SystemStage {
+	/// After every `Game::physics_update`, with the fixed step.
+	Fixed,
+	/// After `Game::update`, with the frame delta.
+	Update,
+	/// After `Game::draw`, with the frame delta.
+	Draw,
+}
+
+/// The systems the engine runs every frame.
+///
+/// Within a stage, systems run by ascending `order` and in the order they
+/// were added when their order is equal.
+///
+/// ```rust
+/// use Engine::{Context, Game, Headless, SystemStage, Transform2D, Window};
+///
+/// fn drift(ctx: &mut Context, dt: f32) {
+///     for (_, transform) in ctx.world.query_mut::<Transform2D>() {
+///         transform.position.x += 60.0 * dt;
+///     }
+/// }
+///
+/// struct Drifting;
+///
+/// impl Game for Drifting {
+///     fn start(&mut self, ctx: &mut Context) {
+///         let entity = ctx.world.spawn();
+///         ctx.world.insert(entity, Transform2D::new());
+///         ctx.systems.add("drift", SystemStage::Fixed, 0, drift);
+///     }
+/// }
+///
+/// let ctx = Headless::new(Window::new()).frames(60).run(&mut Drifting);
+/// let (_, transform) = ctx.world.query::<Transform2D>().next().unwrap();
+/// // one pixel per fixed step at the default 60 steps per second
+/// assert!((transform.position.x - ctx.time.ticks as f32).abs() < 1e-3);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Systems {
+	entries: Vec<(&'static str, SystemStage, i32, System)>,
+}
+
+impl Systems {
+	pub const fn new() -> Systems {
+		Systems { entries: Vec::new() }
+	}
+
+	/// Registers `system` under `name` to run in `stage`.
+	pub fn add(&mut self, name: &'static str, stage: SystemStage, order: i32, system: System) {
+		let index = self.entries.iter().position(|entry| entry.2 > order).unwrap_or(self.entries.len());
+		self.entries.insert(index, (name, stage, order, system));
+	}
+
+	/// Unregisters every system called `name`. Returns `false` if there was none.
+	pub fn remove(&mut self, name: &str) -> bool {
+		let count = self.entries.len();
+		self.entries.retain(|entry| entry.0 != name);
+		self.entries.len() != count
+	}
+
+	pub fn contains(&self, name: &str) -> bool {
+		self.entries.iter().any(|entry| entry.0 == name)
+	}
+
+	/// Names of the systems in `stage`, in the order they run.
+	pub fn names(&self, stage: SystemStage) -> Vec<&'static str> {
+		self.entries.iter().filter(|entry| entry.1 == stage).map(|entry| entry.0).collect()
+	}
+
+	/// Runs the systems of `stage`. The list is copied first so systems may
+	/// add or remove systems, which takes effect the next time the stage runs.
+	pub fn run(ctx: &mut Context, stage: SystemStage, delta: f32) {
+		let systems: Vec<System> = ctx.systems.entries.iter().filter(|entry| entry.1 == stage).map(|entry| entry.3).collect();
+		for system in systems {
+			system(ctx, delta);
+		}
+	}
+}
+
+/// Advances every `Animation2D` component. Meant for `SystemStage::Update`.
+pub fn animate_sprites(ctx: &mut Context, delta: f32) {
+	for (_, animation) in ctx.world.query_mut::<Animation2D>() {
+		animation.update(delta);
+	}
+}
+
+/// Draws every entity with a `Transform2D` and a `Sprite2D` or `Animation2D`.
+/// Meant for `SystemStage::Draw`.
+pub fn draw_sprites(ctx: &mut Context, _delta: f32) {
+	for (_, transform, sprite) in ctx.world.query2::<Transform2D, Sprite2D>() {
+		ctx.renderer.draw_sprite(sprite, *transform);
+	}
+	for (_, transform, animation) in ctx.world.query2::<Transform2D, Animation2D>() {
+		if let Some(sprite) = animation.sprite() {
+			ctx.renderer.draw_sprite(sprite, *transform);
+		}
+	}
+}
+
+/// Connects entities to `Context::physics`. Meant for `SystemStage::Fixed`,
+/// where it runs right after each physics step.
+///
+/// An entity with both a `RigidBody2D` and a `Collision2D` component has
+/// them moved into the physics world, placed at its `Transform2D` if it has
+/// one, and gets the `BodyId` of the new body as a component instead. From
+/// then on the body is changed through `Context::physics` and its transform
+/// is copied to the entity's `Transform2D` every step. Bodies of despawned
+/// entities, or of entities whose `BodyId` was removed, are removed.
+pub fn sync_physics(ctx: &mut Context, _delta: f32) {
+	let (world, physics) = (&ctx.world, &mut ctx.physics);
+	ctx.entity_bodies.retain(|&(entity, id)| {
+		let linked = world.get::<BodyId>(entity) == Some(&id);
+		if !linked {
+			physics.remove(id);
+		}
+		linked
+	});
+
+	let added: Vec<_> = ctx.world.query2::<RigidBody2D, Collision2D>().map(|(entity, _, _)| entity).collect();
+	for entity in added {
+		let mut body = ctx.world.remove::<RigidBody2D>(entity).unwrap();
+		let collider = ctx.world.remove::<Collision2D>(entity).unwrap();
+		match ctx.world.get::<Transform2D>(entity) {
+			Some(transform) => body.transform = *transform,
+			None => {
+				ctx.world.insert(entity, body.transform);
+			},
+		}
+		let id = ctx.physics.insert(body, collider);
+		if let Some(old) = ctx.world.insert(entity, id) {
+			ctx.physics.remove(old);
+			ctx.entity_bodies.retain(|&(_, other)| other != old);
+		}
+		ctx.entity_bodies.push((entity, id));
+	}
+
+	for (_, id, transform) in ctx.world.query2_mut::<BodyId, Transform2D>() {
+		if let Some(body) = ctx.physics.body(*id) {
+			*transform = body.transform;
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Game, Headless, Window, Render::Renderer2D, Size2D, Physics::BodyType, test_util::{STEP, at, ball, boxed}};
+
+	#[derive(Default)]
+	struct Log(Vec<&'static str>);
+
+	fn first(ctx: &mut Context, _dt: f32) {
+		log(ctx, "first");
+	}
+
+	fn second(ctx: &mut Context, _dt: f32) {
+		log(ctx, "second");
+	}
+
+	fn third(ctx: &mut Context, _dt: f32) {
+		log(ctx, "third");
+	}
+
+	fn fixed(ctx: &mut Context, _dt: f32) {
+		log(ctx, "fixed");
+	}
+
+	fn log(ctx: &mut Context, name: &'static str) {
+		let entity = ctx.world.entities()[0];
+		ctx.world.get_mut::<Log>(entity).unwrap().0.push(name);
+	}
+
+	struct Logged;
+
+	impl Game for Logged {
+		fn start(&mut self, ctx: &mut Context) {
+			let entity = ctx.world.spawn();
+			ctx.world.insert(entity, Log::default());
+			ctx.systems.add("third", SystemStage::Update, 10, third);
+			ctx.systems.add("first", SystemStage::Update, -1, first);
+			ctx.systems.add("second", SystemStage::Update, 10, second);
+			ctx.systems.add("fixed", SystemStage::Fixed, 0, fixed);
+		}
+	}
+
+	#[test]
+	fn systems_add_remove() {
+		let mut systems = Systems::new();
+		systems.add("second", SystemStage::Update, 1, second);
+		systems.add("first", SystemStage::Update, 0, first);
+		systems.add("fixed", SystemStage::Fixed, 0, fixed);
+		assert_eq!(systems.names(SystemStage::Update), vec!["first", "second"]);
+		assert_eq!(systems.names(SystemStage::Fixed), vec!["fixed"]);
+		assert!(systems.remove("first"));
+		assert!(!systems.remove("first"));
+		assert!(!systems.contains("first") && systems.contains("second"));
+	}
+
+	#[test]
+	fn systems_run_order() {
+		let ctx = Headless::new(Window::new()).frames(1).delta(0.04).run(&mut Logged);
+		let (_, log) = ctx.world.query::<Log>().next().unwrap();
+		assert_eq!(log.0, vec!["fixed", "fixed", "first", "third", "second"]);
+	}
+
+	#[test]
+	fn systems_draw_sprites() {
+		let window = Window::new().size(Size2D::from(64, 64));
+		let mut ctx = Context::new(window);
+		let texture = Renderer2D::new(window).create_texture(image::RgbaImage::new(8, 8));
+		for _ in 0..3 {
+			let entity = ctx.world.spawn();
+			ctx.world.insert(entity, Transform2D::new());
+			ctx.world.insert(entity, Sprite2D::new(texture));
+		}
+		let hidden = ctx.world.spawn();
+		ctx.world.insert(hidden, Sprite2D::new(texture));
+
+		Systems::run(&mut ctx, SystemStage::Draw, 0.0);
+		assert_eq!(ctx.renderer.vertices().len(), 0);
+		ctx.systems.add("draw_sprites", SystemStage::Draw, 0, draw_sprites);
+		Systems::run(&mut ctx, SystemStage::Draw, 0.0);
+		assert_eq!(ctx.renderer.vertices().len(), 3 * 6);
+	}
+
+	#[test]
+	fn systems_sync_physics() {
+		let mut ctx = Context::new(Window::new());
+		ctx.systems.add("sync_physics", SystemStage::Fixed, 0, sync_physics);
+		let ground = ctx.world.spawn();
+		ctx.world.insert(ground, RigidBody2D::new(at(0.0, 50.0)).body_type(BodyType::Static));
+		ctx.world.insert(ground, boxed(400.0, 100.0));
+		let falling = ctx.world.spawn();
+		ctx.world.insert(falling, at(0.0, -100.0));
+		ctx.world.insert(falling, RigidBody2D::new(Transform2D::new()));
+		ctx.world.insert(falling, ball(10.0));
+
+		Systems::run(&mut ctx, SystemStage::Fixed, STEP);
+		assert_eq!(ctx.physics.len(), 2);
+		assert!(!ctx.world.has::<RigidBody2D>(falling) && !ctx.world.has::<Collision2D>(falling));
+		let id = *ctx.world.get::<BodyId>(falling).unwrap();
+		assert_eq!(ctx.physics.body(id).unwrap().transform, at(0.0, -100.0));
+		assert_eq!(ctx.world.get::<Transform2D>(ground), Some(&at(0.0, 50.0)));
+
+		for _ in 0..120 {
+			ctx.physics.step(STEP);
+			Systems::run(&mut ctx, SystemStage::Fixed, STEP);
+		}
+		let resting = ctx.world.get::<Transform2D>(falling).unwrap().position.y;
+		assert!((resting + 10.0).abs() < 1.0, "{}", resting);
+
+		ctx.world.despawn(falling);
+		Systems::run(&mut ctx, SystemStage::Fixed, STEP);
+		assert_eq!(ctx.physics.len(), 1);
+		assert!(!ctx.physics.contains(id));
+	}
+}
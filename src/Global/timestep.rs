@@ -0,0 +1,161 @@
+use std::cmp::PartialEq;
+
+/// Accumulator that turns variable frame times into a whole number of
+/// fixed simulation steps.
+///
+/// At most `max_steps` steps are run per frame. When a frame takes longer
+/// than that, the excess time is dropped instead of being carried over, so
+/// a slow frame cannot snowball into ever longer catch-up frames.
+#[derive(Copy, Clone, Debug)]
+pub struct FixedTimestep {
+	tick_rate: f64,
+	pub max_steps: u32,
+	accumulator: f64,
+}
+
+impl FixedTimestep {
+	pub const fn new() -> FixedTimestep {
+		FixedTimestep { tick_rate: 60.0, max_steps: 5, accumulator: 0.0 }
+	}
+
+	pub fn from<T: Into<f64>>(tick_rate: T, max_steps: u32) -> FixedTimestep {
+		let mut timestep = FixedTimestep { tick_rate: 60.0, max_steps, accumulator: 0.0 };
+		timestep.set_tick_rate(tick_rate);
+		timestep
+	}
+
+	/// Steps per second.
+	pub fn tick_rate(self) -> f64 {
+		self.tick_rate
+	}
+
+	/// Panics unless `tick_rate` is positive and finite, as every step
+	/// would otherwise last forever or no time at all.
+	pub fn set_tick_rate<T: Into<f64>>(&mut self, tick_rate: T) {
+		let tick_rate = tick_rate.into();
+		assert!(tick_rate > 0.0 && tick_rate.is_finite(), "tick rate must be positive, got {}", tick_rate);
+		self.tick_rate = tick_rate;
+	}
+
+	/// Seconds simulated by a single step.
+	pub fn step(self) -> f64 {
+		1.0 / self.tick_rate
+	}
+
+	/// Adds `delta` seconds and returns how many steps should be simulated.
+	/// Negative, NaN and infinite deltas count as no time passing.
+	pub fn advance(&mut self, delta: f64) -> u32 {
+		let step = self.step();
+		if delta.is_finite() {
+			self.accumulator += delta.max(0.0);
+		}
+
+		let mut steps = 0;
+		while self.accumulator >= step && steps < self.max_steps {
+			self.accumulator -= step;
+			steps += 1;
+		}
+
+		// spiral-of-death guard: drop whatever could not be caught up
+		if self.accumulator >= step {
+			self.accumulator %= step;
+		}
+
+		steps
+	}
+
+	/// How far the leftover time is into the next step, from 0 to 1.
+	/// Renderers use it to interpolate between the last two simulated states.
+	pub fn alpha(self) -> f64 {
+		self.accumulator / self.step()
+	}
+
+	pub fn reset(&mut self) {
+		self.accumulator = 0.0;
+	}
+}
+
+impl Default for FixedTimestep {
+	fn default() -> FixedTimestep {
+		FixedTimestep::new()
+	}
+}
+
+impl PartialEq for FixedTimestep {
+	fn eq(&self, other: &FixedTimestep) -> bool {
+		self.tick_rate == other.tick_rate && self.max_steps == other.max_steps && self.accumulator == other.accumulator
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fixed_timestep_new() {
+		let timestep = FixedTimestep::new();
+		assert_eq!(timestep, FixedTimestep { tick_rate: 60.0, max_steps: 5, accumulator: 0.0 });
+	}
+
+	#[test]
+	fn fixed_timestep_from() {
+		let timestep = FixedTimestep::from(120, 8);
+		assert_eq!(timestep, FixedTimestep { tick_rate: 120.0, max_steps: 8, accumulator: 0.0 });
+		assert_eq!(timestep.step(), 1.0 / 120.0);
+	}
+
+	#[test]
+	#[should_panic]
+	fn fixed_timestep_from_zero() {
+		FixedTimestep::from(0, 5);
+	}
+
+	#[test]
+	fn fixed_timestep_set_tick_rate() {
+		let mut timestep = FixedTimestep::new();
+		timestep.set_tick_rate(30);
+		assert_eq!((timestep.tick_rate(), timestep.step()), (30.0, 1.0 / 30.0));
+	}
+
+	#[test]
+	#[should_panic]
+	fn fixed_timestep_set_tick_rate_zero() {
+		FixedTimestep::new().set_tick_rate(0);
+	}
+
+	#[test]
+	fn fixed_timestep_advance() {
+		let mut timestep = FixedTimestep::from(10, 5);
+		assert_eq!(timestep.advance(0.05), 0);
+		assert_eq!(timestep.alpha(), 0.5);
+		assert_eq!(timestep.advance(0.2), 2);
+		assert!((timestep.alpha() - 0.5).abs() < 1e-9);
+	}
+
+	#[test]
+	fn fixed_timestep_max_steps() {
+		let mut timestep = FixedTimestep::from(10, 3);
+		assert_eq!(timestep.advance(1.05), 3);
+		assert!(timestep.alpha() < 1.0);
+		assert_eq!(timestep.advance(0.0), 0);
+	}
+
+	#[test]
+	fn fixed_timestep_invalid_delta() {
+		let mut timestep = FixedTimestep::from(10, 3);
+		timestep.advance(0.05);
+		for &delta in &[f64::INFINITY, f64::NEG_INFINITY, f64::NAN, -1.0] {
+			assert_eq!(timestep.advance(delta), 0);
+			assert_eq!(timestep.alpha(), 0.5);
+		}
+		assert_eq!(timestep.advance(0.1), 1);
+	}
+
+	#[test]
+	fn fixed_timestep_reset() {
+		let mut timestep = FixedTimestep::from(10, 3);
+		timestep.advance(0.05);
+		timestep.reset();
+		assert_eq!(timestep.alpha(), 0.0);
+	}
+}
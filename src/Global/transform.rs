@@ -20,13 +20,63 @@ impl Transform2D {
     pub const fn from(position: Position2D, rotation: Rotation2D, scale: Scale2D) -> Transform2D {
         Transform2D { position, rotation, scale }
     }
+
+	/// Maps a point from local space to the space this transform lives in:
+	/// scaled first, then rotated, then translated.
+	pub fn apply(self, point: Position2D) -> Position2D {
+		let (sin, cos) = self.rotation.rad().sin_cos();
+		let x = point.x * self.scale.x;
+		let y = point.y * self.scale.y;
+		Position2D {
+			x: self.position.x + x * cos - y * sin,
+			y: self.position.y + x * sin + y * cos,
+		}
+	}
+
+	/// Maps a point back into local space, undoing `apply`.
+	pub fn inverse_apply(self, point: Position2D) -> Position2D {
+		let (sin, cos) = self.rotation.rad().sin_cos();
+		let x = point.x - self.position.x;
+		let y = point.y - self.position.y;
+		Position2D {
+			x: (x * cos + y * sin) / self.scale.x,
+			y: (y * cos - x * sin) / self.scale.y,
+		}
+	}
+
+	/// The transform of a child placed by `local` inside this transform.
+	///
+	/// Rotations add up and scales multiply, so a non-uniformly scaled
+	/// parent does not skew its rotated children.
+	pub fn combine(self, local: Transform2D) -> Transform2D {
+		Transform2D {
+			position: self.apply(local.position),
+			rotation: self.rotation + local.rotation,
+			scale: self.scale * local.scale,
+		}
+	}
+
+	/// The local transform that `combine`d with `parent` gives this transform.
+	pub fn relative_to(self, parent: Transform2D) -> Transform2D {
+		Transform2D {
+			position: parent.inverse_apply(self.position),
+			rotation: self.rotation - parent.rotation,
+			scale: self.scale / parent.scale,
+		}
+	}
+}
+
+impl Default for Transform2D {
+	fn default() -> Transform2D {
+		Transform2D::new()
+	}
 }
 
 impl Eq for Transform2D {}
 
 impl PartialEq for Transform2D {
     fn eq(&self, other: &Transform2D) -> bool {
-        self.position == other.position && self.rotation == other.rotation && self.rotation == other.rotation
+        self.position == other.position && self.rotation == other.rotation && self.scale == other.scale
     }
 }
 
@@ -55,5 +105,34 @@ mod tests {
 		};
         assert_eq!(transform, raw);
     }
+
+    #[test]
+    fn transform_2d_apply() {
+        let transform = Transform2D::from(Position2D::from(10, 20), Rotation2D::from_deg(90), Scale2D::from(2, 3));
+        let point = transform.apply(Position2D::from(1, 1));
+        assert!((point.x - 7.0).abs() < 1e-5 && (point.y - 22.0).abs() < 1e-5);
+        assert_eq!(Transform2D::new().apply(Position2D::from(4, 5)), Position2D::from(4, 5));
+    }
+
+    #[test]
+    fn transform_2d_inverse_apply() {
+        let transform = Transform2D::from(Position2D::from(10, 20), Rotation2D::from_deg(30), Scale2D::from(2, 3));
+        let point = transform.inverse_apply(transform.apply(Position2D::from(4, -5)));
+        assert!((point.x - 4.0).abs() < 1e-4 && (point.y + 5.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn transform_2d_combine() {
+        let parent = Transform2D::from(Position2D::from(10, 0), Rotation2D::from_deg(90), Scale2D::from(2, 2));
+        let local = Transform2D::from(Position2D::from(5, 0), Rotation2D::from_deg(45), Scale2D::from(1, 3));
+        let world = parent.combine(local);
+        assert!((world.position.x - 10.0).abs() < 1e-4 && (world.position.y - 10.0).abs() < 1e-4);
+        assert_eq!(world.rotation.deg(), 135.0);
+        assert_eq!(world.scale, Scale2D::from(2, 6));
+
+        let back = world.relative_to(parent);
+        assert!((back.position.x - 5.0).abs() < 1e-4 && back.position.y.abs() < 1e-4);
+        assert_eq!((back.rotation.deg(), back.scale), (45.0, Scale2D::from(1, 3)));
+    }
 }
 
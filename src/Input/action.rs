@@ -0,0 +1,385 @@
+use crate::Input::{
+	InputState, Key, MouseButton, GamepadButton, GamepadAxis,
+	key_from_name, mouse_button_name, mouse_button_from_name,
+};
+use std::{fmt, fs, io, path::Path, str::FromStr};
+
+/// How far an axis has to be pushed before its action counts as down.
+pub const AXIS_PRESS_THRESHOLD: f32 = 0.5;
+
+/// A physical input that drives an action.
+///
+/// In config files bindings are written as `Key:Space`, `Mouse:Left`,
+/// `Button:A`, `Axis:LeftX` or `Keys:A/D` (negative/positive).
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Binding {
+	Key(Key),
+	Mouse(MouseButton),
+	/// A button on any connected gamepad.
+	Button(GamepadButton),
+	/// An axis on any connected gamepad.
+	Axis(GamepadAxis),
+	/// Two keys forming an axis: the first gives -1, the second +1.
+	Keys(Key, Key),
+}
+
+impl Binding {
+	/// Current value of the binding, from -1 to 1.
+	pub fn value(self, input: &InputState) -> f32 {
+		let digital = |down: bool| if down { 1.0 } else { 0.0 };
+		match self {
+			Binding::Key(key) => digital(input.is_key_down(key)),
+			Binding::Mouse(button) => digital(input.is_mouse_down(button)),
+			Binding::Button(button) => digital(input.gamepads.iter().any(|pad| pad.is_connected() && pad.is_down(button))),
+			Binding::Axis(axis) => input.gamepads.iter()
+				.filter(|pad| pad.is_connected())
+				.map(|pad| pad.axis(axis))
+				.fold(0.0, |best, value| if value.abs() > f32::abs(best) { value } else { best }),
+			Binding::Keys(negative, positive) => digital(input.is_key_down(positive)) - digital(input.is_key_down(negative)),
+		}
+	}
+}
+
+impl fmt::Display for Binding {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Binding::Key(key) => write!(f, "Key:{:?}", key),
+			Binding::Mouse(button) => write!(f, "Mouse:{}", mouse_button_name(*button)),
+			Binding::Button(button) => write!(f, "Button:{:?}", button),
+			Binding::Axis(axis) => write!(f, "Axis:{:?}", axis),
+			Binding::Keys(negative, positive) => write!(f, "Keys:{:?}/{:?}", negative, positive),
+		}
+	}
+}
+
+impl FromStr for Binding {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Binding, String> {
+		let text = text.trim();
+		let (kind, name) = text.split_once(':').ok_or_else(|| format!("binding '{}' is missing a ':'", text))?;
+		let key = |name: &str| key_from_name(name.trim()).ok_or_else(|| format!("unknown key '{}'", name.trim()));
+		let name = name.trim();
+
+		match kind.trim() {
+			"Key" => Ok(Binding::Key(key(name)?)),
+			"Mouse" => mouse_button_from_name(name).map(Binding::Mouse).ok_or_else(|| format!("unknown mouse button '{}'", name)),
+			"Button" => GamepadButton::from_name(name).map(Binding::Button).ok_or_else(|| format!("unknown gamepad button '{}'", name)),
+			"Axis" => GamepadAxis::from_name(name).map(Binding::Axis).ok_or_else(|| format!("unknown gamepad axis '{}'", name)),
+			"Keys" => {
+				let (negative, positive) = name.split_once('/').ok_or_else(|| format!("binding '{}' needs two keys split by '/'", text))?;
+				Ok(Binding::Keys(key(negative)?, key(positive)?))
+			},
+			other => Err(format!("unknown binding type '{}'", other)),
+		}
+	}
+}
+
+#[derive(Clone, Debug)]
+struct Action {
+	name: String,
+	bindings: Vec<Binding>,
+	value: f32,
+	down: bool,
+	was_down: bool,
+}
+
+/// Named actions such as `"jump"` or `"move_x"` bound to physical inputs.
+///
+/// The engine calls `update` once per frame after the input events have
+/// been handled, so games only query actions by name.
+///
+/// Action names must be non-empty, must not start or end with whitespace or
+/// start with `#`, and must not contain `=`, `,` or line breaks, so they can
+/// be written to a config file. Binding an invalid name panics.
+///
+/// ```rust
+/// use Engine::Input::{ActionMap, Binding, Key, GamepadAxis};
+///
+/// let mut actions = ActionMap::new();
+/// actions.bind("jump", Binding::Key(Key::Space));
+/// actions.bind("move_x", Binding::Keys(Key::A, Key::D));
+/// actions.bind("move_x", Binding::Axis(GamepadAxis::LeftX));
+///
+/// let config = actions.to_config();
+/// assert_eq!(config, "jump = Key:Space\nmove_x = Keys:A/D, Axis:LeftX\n");
+/// assert_eq!(config.parse::<ActionMap>().unwrap().to_config(), config);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ActionMap {
+	actions: Vec<Action>,
+}
+
+impl ActionMap {
+	pub fn new() -> ActionMap {
+		ActionMap { actions: Vec::new() }
+	}
+
+	/// Adds a binding to an action, creating the action if needed.
+	/// Panics if `name` is not a valid action name.
+	pub fn bind(&mut self, name: &str, binding: Binding) {
+		let action = self.entry(name);
+		if !action.bindings.contains(&binding) {
+			action.bindings.push(binding);
+		}
+	}
+
+	/// Removes a binding from an action.
+	pub fn unbind(&mut self, name: &str, binding: Binding) {
+		if let Some(action) = self.find_mut(name) {
+			action.bindings.retain(|other| *other != binding);
+		}
+	}
+
+	/// Replaces every binding of an action. Panics if `name` is not a valid
+	/// action name.
+	pub fn rebind(&mut self, name: &str, bindings: &[Binding]) {
+		self.entry(name).bindings = bindings.to_vec();
+	}
+
+	/// Removes an action and its bindings.
+	pub fn remove(&mut self, name: &str) {
+		self.actions.retain(|action| action.name != name);
+	}
+
+	pub fn bindings(&self, name: &str) -> &[Binding] {
+		self.find(name).map_or(&[], |action| &action.bindings)
+	}
+
+	/// Action names in the order they were first bound.
+	pub fn names(&self) -> Vec<&str> {
+		self.actions.iter().map(|action| action.name.as_str()).collect()
+	}
+
+	/// Re-evaluates every action against the current input state.
+	pub fn update(&mut self, input: &InputState) {
+		for action in self.actions.iter_mut() {
+			action.value = action.bindings.iter()
+				.map(|binding| binding.value(input))
+				.fold(0.0, |best, value| if value.abs() > f32::abs(best) { value } else { best });
+			action.was_down = action.down;
+			action.down = action.value.abs() >= AXIS_PRESS_THRESHOLD;
+		}
+	}
+
+	/// Value of the strongest binding, from -1 to 1.
+	pub fn value(&self, name: &str) -> f32 {
+		self.find(name).map_or(0.0, |action| action.value)
+	}
+
+	pub fn is_down(&self, name: &str) -> bool {
+		self.find(name).is_some_and(|action| action.down)
+	}
+
+	pub fn was_pressed(&self, name: &str) -> bool {
+		self.find(name).is_some_and(|action| action.down && !action.was_down)
+	}
+
+	pub fn was_released(&self, name: &str) -> bool {
+		self.find(name).is_some_and(|action| !action.down && action.was_down)
+	}
+
+	/// Writes the bindings as `name = binding, binding` lines.
+	pub fn to_config(&self) -> String {
+		let mut config = String::new();
+		for action in self.actions.iter() {
+			let bindings: Vec<String> = action.bindings.iter().map(|binding| binding.to_string()).collect();
+			config.push_str(&format!("{} = {}\n", action.name, bindings.join(", ")));
+		}
+		config
+	}
+
+	pub fn load<P: AsRef<Path>>(path: P) -> io::Result<ActionMap> {
+		fs::read_to_string(path)?
+			.parse()
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+	}
+
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		fs::write(path, self.to_config())
+	}
+
+	fn find(&self, name: &str) -> Option<&Action> {
+		self.actions.iter().find(|action| action.name == name)
+	}
+
+	fn find_mut(&mut self, name: &str) -> Option<&mut Action> {
+		self.actions.iter_mut().find(|action| action.name == name)
+	}
+
+	fn entry(&mut self, name: &str) -> &mut Action {
+		assert!(is_valid_name(name), "invalid action name {:?}", name);
+		let index = match self.actions.iter().position(|action| action.name == name) {
+			Some(index) => index,
+			None => {
+				self.actions.push(Action { name: String::from(name), bindings: Vec::new(), value: 0.0, down: false, was_down: false });
+				self.actions.len() - 1
+			},
+		};
+		&mut self.actions[index]
+	}
+}
+
+impl Default for ActionMap {
+	fn default() -> ActionMap {
+		ActionMap::new()
+	}
+}
+
+/// Whether `name` reads back unchanged from a `name = bindings` line.
+fn is_valid_name(name: &str) -> bool {
+	!name.is_empty() && name.trim() == name && !name.starts_with('#') && !name.contains(['=', ',', '\n', '\r'])
+}
+
+/// Parses the format written by `ActionMap::to_config`. Blank lines and
+/// lines starting with `#` are ignored.
+impl FromStr for ActionMap {
+	type Err = String;
+
+	fn from_str(config: &str) -> Result<ActionMap, String> {
+		let mut actions = ActionMap::new();
+		for (number, line) in config.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let (name, bindings) = line.split_once('=').ok_or_else(|| format!("line {}: expected 'action = bindings'", number + 1))?;
+			let name = name.trim();
+			if name.is_empty() {
+				return Err(format!("line {}: missing action name", number + 1));
+			}
+			if !is_valid_name(name) {
+				return Err(format!("line {}: invalid action name '{}'", number + 1, name));
+			}
+			actions.entry(name);
+			for binding in bindings.split(',').filter(|binding| !binding.trim().is_empty()) {
+				let binding = binding.parse().map_err(|error| format!("line {}: {}", number + 1, error))?;
+				actions.bind(name, binding);
+			}
+		}
+		Ok(actions)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Input::{InputEvent, Action as KeyAction, MouseButtonLeft};
+
+	fn press(input: &mut InputState, key: Key) {
+		input.handle(InputEvent::Key(key, KeyAction::Press));
+	}
+
+	#[test]
+	fn binding_display_parse() {
+		let bindings = [
+			Binding::Key(Key::Space),
+			Binding::Mouse(MouseButtonLeft),
+			Binding::Button(GamepadButton::A),
+			Binding::Axis(GamepadAxis::LeftX),
+			Binding::Keys(Key::A, Key::D),
+		];
+		for binding in bindings.iter() {
+			assert_eq!(binding.to_string().parse::<Binding>(), Ok(*binding));
+		}
+		assert_eq!(" Keys: Left / Right ".parse::<Binding>(), Ok(Binding::Keys(Key::Left, Key::Right)));
+		assert!("Key:Nope".parse::<Binding>().is_err());
+		assert!("Space".parse::<Binding>().is_err());
+		assert!("Wheel:Up".parse::<Binding>().is_err());
+	}
+
+	#[test]
+	fn action_map_bind() {
+		let mut actions = ActionMap::new();
+		actions.bind("jump", Binding::Key(Key::Space));
+		actions.bind("jump", Binding::Key(Key::Space));
+		actions.bind("jump", Binding::Button(GamepadButton::A));
+		assert_eq!(actions.bindings("jump"), &[Binding::Key(Key::Space), Binding::Button(GamepadButton::A)]);
+		actions.unbind("jump", Binding::Key(Key::Space));
+		assert_eq!(actions.bindings("jump"), &[Binding::Button(GamepadButton::A)]);
+		actions.rebind("jump", &[Binding::Key(Key::W)]);
+		assert_eq!(actions.bindings("jump"), &[Binding::Key(Key::W)]);
+		actions.remove("jump");
+		assert!(actions.bindings("jump").is_empty());
+		assert!(actions.names().is_empty());
+	}
+
+	#[test]
+	fn action_map_pressed_released() {
+		let mut actions = ActionMap::new();
+		actions.bind("jump", Binding::Key(Key::Space));
+		let mut input = InputState::new();
+
+		press(&mut input, Key::Space);
+		actions.update(&input);
+		assert!(actions.is_down("jump") && actions.was_pressed("jump"));
+
+		actions.update(&input);
+		assert!(actions.is_down("jump") && !actions.was_pressed("jump"));
+
+		input.handle(InputEvent::Key(Key::Space, KeyAction::Release));
+		actions.update(&input);
+		assert!(!actions.is_down("jump") && actions.was_released("jump"));
+		assert!(!actions.is_down("unknown"));
+	}
+
+	#[test]
+	fn action_map_composite_axis() {
+		let mut actions = ActionMap::new();
+		actions.bind("move_x", Binding::Keys(Key::A, Key::D));
+		actions.bind("move_x", Binding::Axis(GamepadAxis::LeftX));
+		let mut input = InputState::new();
+
+		press(&mut input, Key::A);
+		actions.update(&input);
+		assert_eq!(actions.value("move_x"), -1.0);
+
+		press(&mut input, Key::D);
+		actions.update(&input);
+		assert_eq!(actions.value("move_x"), 0.0);
+
+		input.handle(InputEvent::GamepadConnection(0, true));
+		input.handle(InputEvent::GamepadAxis(0, GamepadAxis::LeftX, 1.0));
+		actions.update(&input);
+		assert_eq!(actions.value("move_x"), 1.0);
+		assert!(actions.is_down("move_x"));
+	}
+
+	#[test]
+	fn action_map_config() {
+		let config = "# controls\n\njump = Key:Space, Button:A\nfire = Mouse:Left\nmove_x = Keys:A/D, Axis:LeftX\nidle =\n";
+		let actions: ActionMap = config.parse().unwrap();
+		assert_eq!(actions.names(), vec!["jump", "fire", "move_x", "idle"]);
+		assert_eq!(actions.bindings("fire"), &[Binding::Mouse(MouseButtonLeft)]);
+		assert_eq!(actions.to_config(), "jump = Key:Space, Button:A\nfire = Mouse:Left\nmove_x = Keys:A/D, Axis:LeftX\nidle = \n");
+		assert!("jump Key:Space".parse::<ActionMap>().is_err());
+		assert_eq!("a = Key:Space\nb = Key:Nope".parse::<ActionMap>().unwrap_err(), "line 2: unknown key 'Nope'");
+	}
+
+	#[test]
+	fn action_map_names_round_trip() {
+		let mut actions = ActionMap::new();
+		actions.bind("move left", Binding::Key(Key::A));
+		actions.bind("saut#2", Binding::Key(Key::Space));
+		let parsed: ActionMap = actions.to_config().parse().unwrap();
+		assert_eq!(parsed.names(), vec!["move left", "saut#2"]);
+		assert_eq!(parsed.to_config(), actions.to_config());
+
+		for &name in &["", " jump", "jump ", "#jump", "a=b", "a,b", "a\nb = Key:W", "a\rb"] {
+			let bound = std::panic::catch_unwind(|| ActionMap::new().bind(name, Binding::Key(Key::Space)));
+			assert!(bound.is_err(), "{:?}", name);
+		}
+		assert_eq!("a,b = Key:Space".parse::<ActionMap>().unwrap_err(), "line 1: invalid action name 'a,b'");
+	}
+
+	#[test]
+	fn action_map_save_load() {
+		let path = std::env::temp_dir().join(format!("engine_action_map_{}.cfg", std::process::id()));
+		let mut actions = ActionMap::new();
+		actions.bind("jump", Binding::Key(Key::Space));
+		actions.save(&path).unwrap();
+		let loaded = ActionMap::load(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(loaded.bindings("jump"), &[Binding::Key(Key::Space)]);
+	}
+}
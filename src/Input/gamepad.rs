@@ -0,0 +1,375 @@
+use crate::{Input::{Action, InputEvent}, Math::Vector2D};
+
+/// Number of gamepad slots, matching the joysticks GLFW can report.
+pub const MAX_GAMEPADS: usize = 16;
+
+/// Buttons of the standard (Xbox style) gamepad layout.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+	A,
+	B,
+	X,
+	Y,
+	LeftBumper,
+	RightBumper,
+	Back,
+	Start,
+	Guide,
+	LeftStick,
+	RightStick,
+	DpadUp,
+	DpadRight,
+	DpadDown,
+	DpadLeft,
+}
+
+impl GamepadButton {
+	pub const ALL: [GamepadButton; 15] = [
+		GamepadButton::A, GamepadButton::B, GamepadButton::X, GamepadButton::Y,
+		GamepadButton::LeftBumper, GamepadButton::RightBumper,
+		GamepadButton::Back, GamepadButton::Start, GamepadButton::Guide,
+		GamepadButton::LeftStick, GamepadButton::RightStick,
+		GamepadButton::DpadUp, GamepadButton::DpadRight, GamepadButton::DpadDown, GamepadButton::DpadLeft,
+	];
+
+	/// Finds a button by the name it is printed with, e.g. `"LeftBumper"`.
+	pub fn from_name(name: &str) -> Option<GamepadButton> {
+		GamepadButton::ALL.iter().copied().find(|button| format!("{:?}", button) == name)
+	}
+}
+
+/// Axes of the standard gamepad layout. Sticks range from -1 to 1 (down and
+/// right are positive), triggers from 0 (released) to 1 (fully pressed).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+	LeftX,
+	LeftY,
+	RightX,
+	RightY,
+	LeftTrigger,
+	RightTrigger,
+}
+
+impl GamepadAxis {
+	pub const ALL: [GamepadAxis; 6] = [
+		GamepadAxis::LeftX, GamepadAxis::LeftY,
+		GamepadAxis::RightX, GamepadAxis::RightY,
+		GamepadAxis::LeftTrigger, GamepadAxis::RightTrigger,
+	];
+
+	/// Finds an axis by the name it is printed with, e.g. `"LeftX"`.
+	pub fn from_name(name: &str) -> Option<GamepadAxis> {
+		GamepadAxis::ALL.iter().copied().find(|axis| format!("{:?}", axis) == name)
+	}
+
+	pub fn is_trigger(self) -> bool {
+		self == GamepadAxis::LeftTrigger || self == GamepadAxis::RightTrigger
+	}
+}
+
+/// Raw state of a mapped gamepad as read from the device.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GamepadSnapshot {
+	pub buttons: [bool; 15],
+	pub axes: [f32; 6],
+}
+
+impl GamepadSnapshot {
+	pub const fn new() -> GamepadSnapshot {
+		GamepadSnapshot { buttons: [false; 15], axes: [0.0; 6] }
+	}
+
+	/// Converts GLFW's state, remapping triggers from -1..1 to 0..1.
+	pub fn from_glfw(state: &glfw::GamepadState) -> GamepadSnapshot {
+		let mut snapshot = GamepadSnapshot::new();
+		for (index, button) in GamepadButton::ALL.iter().enumerate() {
+			let button = glfw::GamepadButton::from_i32(*button as i32).unwrap();
+			snapshot.buttons[index] = state.get_button_state(button) == Action::Press;
+		}
+		for (index, axis) in GamepadAxis::ALL.iter().enumerate() {
+			let value = state.get_axis(glfw::GamepadAxis::from_i32(*axis as i32).unwrap());
+			snapshot.axes[index] = if axis.is_trigger() { (value + 1.0) / 2.0 } else { value };
+		}
+		snapshot
+	}
+}
+
+impl Default for GamepadSnapshot {
+	fn default() -> GamepadSnapshot {
+		GamepadSnapshot::new()
+	}
+}
+
+/// State of one gamepad slot for the current frame.
+#[derive(Copy, Clone, Debug)]
+pub struct Gamepad {
+	pub id: usize,
+	/// Axis values whose magnitude is below this are reported as 0.
+	pub deadzone: f32,
+	connected: bool,
+	connection_changed: bool,
+	buttons: [bool; 15],
+	pressed: [bool; 15],
+	released: [bool; 15],
+	axes: [f32; 6],
+}
+
+impl Gamepad {
+	pub const fn new(id: usize) -> Gamepad {
+		Gamepad {
+			id,
+			deadzone: 0.15,
+			connected: false,
+			connection_changed: false,
+			buttons: [false; 15],
+			pressed: [false; 15],
+			released: [false; 15],
+			axes: [0.0; 6],
+		}
+	}
+
+	pub fn begin_frame(&mut self) {
+		self.connection_changed = false;
+		self.pressed = [false; 15];
+		self.released = [false; 15];
+	}
+
+	pub fn handle_connection(&mut self, connected: bool) {
+		if self.connected != connected {
+			let deadzone = self.deadzone;
+			*self = Gamepad::new(self.id);
+			self.deadzone = deadzone;
+			self.connected = connected;
+			self.connection_changed = true;
+		}
+	}
+
+	pub fn handle_button(&mut self, button: GamepadButton, action: Action) {
+		let index = button as usize;
+		match action {
+			Action::Press => {
+				if !self.buttons[index] {
+					self.buttons[index] = true;
+					self.pressed[index] = true;
+				}
+			},
+			Action::Release => {
+				if self.buttons[index] {
+					self.buttons[index] = false;
+					self.released[index] = true;
+				}
+			},
+			Action::Repeat => {},
+		}
+	}
+
+	pub fn handle_axis(&mut self, axis: GamepadAxis, value: f32) {
+		self.axes[axis as usize] = value;
+	}
+
+	/// Returns the events that turn this gamepad's state into `snapshot`,
+	/// where `None` means the device is not present.
+	pub fn changes(&self, snapshot: Option<GamepadSnapshot>) -> Vec<InputEvent> {
+		let mut events = Vec::new();
+		match snapshot {
+			None => {
+				if self.connected {
+					events.push(InputEvent::GamepadConnection(self.id, false));
+				}
+			},
+			Some(snapshot) => {
+				if !self.connected {
+					events.push(InputEvent::GamepadConnection(self.id, true));
+				}
+				for (index, button) in GamepadButton::ALL.iter().enumerate() {
+					let was_down = self.connected && self.buttons[index];
+					if snapshot.buttons[index] != was_down {
+						let action = if snapshot.buttons[index] { Action::Press } else { Action::Release };
+						events.push(InputEvent::GamepadButton(self.id, *button, action));
+					}
+				}
+				for (index, axis) in GamepadAxis::ALL.iter().enumerate() {
+					let previous = if self.connected { self.axes[index] } else { 0.0 };
+					if snapshot.axes[index] != previous {
+						events.push(InputEvent::GamepadAxis(self.id, *axis, snapshot.axes[index]));
+					}
+				}
+			},
+		}
+		events
+	}
+
+	pub fn is_connected(&self) -> bool {
+		self.connected
+	}
+
+	/// Did the gamepad get connected this frame?
+	pub fn was_connected(&self) -> bool {
+		self.connection_changed && self.connected
+	}
+
+	/// Did the gamepad get disconnected this frame?
+	pub fn was_disconnected(&self) -> bool {
+		self.connection_changed && !self.connected
+	}
+
+	pub fn is_down(&self, button: GamepadButton) -> bool {
+		self.buttons[button as usize]
+	}
+
+	pub fn was_pressed(&self, button: GamepadButton) -> bool {
+		self.pressed[button as usize]
+	}
+
+	pub fn was_released(&self, button: GamepadButton) -> bool {
+		self.released[button as usize]
+	}
+
+	/// Axis value without any deadzone applied.
+	pub fn raw_axis(&self, axis: GamepadAxis) -> f32 {
+		self.axes[axis as usize]
+	}
+
+	/// Axis value with the deadzone removed and the rest rescaled to the full range.
+	pub fn axis(&self, axis: GamepadAxis) -> f32 {
+		apply_deadzone(self.axes[axis as usize], self.deadzone)
+	}
+
+	/// Left stick with a radial deadzone, so diagonals are not cut off.
+	pub fn left_stick(&self) -> Vector2D {
+		self.stick(GamepadAxis::LeftX, GamepadAxis::LeftY)
+	}
+
+	/// Right stick with a radial deadzone, so diagonals are not cut off.
+	pub fn right_stick(&self) -> Vector2D {
+		self.stick(GamepadAxis::RightX, GamepadAxis::RightY)
+	}
+
+	fn stick(&self, x: GamepadAxis, y: GamepadAxis) -> Vector2D {
+		let raw = Vector2D::from(self.raw_axis(x), self.raw_axis(y));
+		let length = !raw;
+		if length <= self.deadzone {
+			return Vector2D::new();
+		}
+		raw * (apply_deadzone(length.min(1.0), self.deadzone) / length)
+	}
+}
+
+fn apply_deadzone(value: f32, deadzone: f32) -> f32 {
+	// a deadzone of 1 or more covers the whole range and would divide by zero
+	let deadzone = if deadzone.is_nan() { 0.0 } else { deadzone.clamp(0.0, 1.0) };
+	if value.abs() <= deadzone || deadzone == 1.0 {
+		0.0
+	} else {
+		value.signum() * ((value.abs() - deadzone) / (1.0 - deadzone)).min(1.0)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn connected() -> Gamepad {
+		let mut gamepad = Gamepad::new(0);
+		gamepad.handle_connection(true);
+		gamepad
+	}
+
+	#[test]
+	fn gamepad_new() {
+		let gamepad = Gamepad::new(3);
+		assert_eq!(gamepad.id, 3);
+		assert!(!gamepad.is_connected());
+		assert!(!gamepad.is_down(GamepadButton::A));
+	}
+
+	#[test]
+	fn gamepad_connection() {
+		let mut gamepad = connected();
+		assert!(gamepad.is_connected() && gamepad.was_connected());
+		gamepad.handle_button(GamepadButton::A, Action::Press);
+		gamepad.begin_frame();
+		assert!(!gamepad.was_connected());
+		gamepad.handle_connection(false);
+		assert!(gamepad.was_disconnected());
+		assert!(!gamepad.is_down(GamepadButton::A));
+	}
+
+	#[test]
+	fn gamepad_buttons() {
+		let mut gamepad = connected();
+		gamepad.handle_button(GamepadButton::Y, Action::Press);
+		assert!(gamepad.is_down(GamepadButton::Y) && gamepad.was_pressed(GamepadButton::Y));
+		gamepad.begin_frame();
+		gamepad.handle_button(GamepadButton::Y, Action::Release);
+		assert!(!gamepad.is_down(GamepadButton::Y) && gamepad.was_released(GamepadButton::Y));
+	}
+
+	#[test]
+	fn gamepad_axis_deadzone() {
+		let mut gamepad = connected();
+		gamepad.deadzone = 0.2;
+		gamepad.handle_axis(GamepadAxis::LeftX, 0.1);
+		assert_eq!(gamepad.axis(GamepadAxis::LeftX), 0.0);
+		assert_eq!(gamepad.raw_axis(GamepadAxis::LeftX), 0.1);
+		gamepad.handle_axis(GamepadAxis::LeftX, -0.6);
+		assert!((gamepad.axis(GamepadAxis::LeftX) + 0.5).abs() < 1e-6);
+		gamepad.handle_axis(GamepadAxis::LeftX, 1.0);
+		assert_eq!(gamepad.axis(GamepadAxis::LeftX), 1.0);
+	}
+
+	#[test]
+	fn gamepad_stick_deadzone() {
+		let mut gamepad = connected();
+		gamepad.deadzone = 0.2;
+		gamepad.handle_axis(GamepadAxis::RightX, 0.1);
+		gamepad.handle_axis(GamepadAxis::RightY, 0.1);
+		assert_eq!(gamepad.right_stick(), Vector2D::new());
+		gamepad.handle_axis(GamepadAxis::RightX, 0.0);
+		gamepad.handle_axis(GamepadAxis::RightY, 0.6);
+		assert!((gamepad.right_stick().y - 0.5).abs() < 1e-6);
+	}
+
+	#[test]
+	fn gamepad_full_deadzone() {
+		let mut gamepad = connected();
+		gamepad.handle_axis(GamepadAxis::LeftX, 1.0);
+		gamepad.handle_axis(GamepadAxis::LeftY, 1.0);
+		for &deadzone in &[1.0, 2.0, f32::NAN] {
+			gamepad.deadzone = deadzone;
+			assert!(!gamepad.axis(GamepadAxis::LeftX).is_nan());
+			assert!(!(!gamepad.left_stick()).is_nan());
+		}
+		gamepad.deadzone = 1.0;
+		assert_eq!(gamepad.axis(GamepadAxis::LeftX), 0.0);
+		assert_eq!(gamepad.left_stick(), Vector2D::new());
+	}
+
+	#[test]
+	fn gamepad_names() {
+		assert_eq!(GamepadButton::from_name("DpadLeft"), Some(GamepadButton::DpadLeft));
+		assert_eq!(GamepadButton::from_name("Z"), None);
+		assert_eq!(GamepadAxis::from_name("RightTrigger"), Some(GamepadAxis::RightTrigger));
+		assert_eq!(GamepadAxis::from_name("Up"), None);
+	}
+
+	#[test]
+	fn gamepad_changes() {
+		let gamepad = Gamepad::new(2);
+		assert!(gamepad.changes(None).is_empty());
+
+		let mut snapshot = GamepadSnapshot::new();
+		snapshot.buttons[GamepadButton::B as usize] = true;
+		snapshot.axes[GamepadAxis::LeftTrigger as usize] = 0.5;
+		let events = gamepad.changes(Some(snapshot));
+		assert_eq!(events, vec![
+			InputEvent::GamepadConnection(2, true),
+			InputEvent::GamepadButton(2, GamepadButton::B, Action::Press),
+			InputEvent::GamepadAxis(2, GamepadAxis::LeftTrigger, 0.5),
+		]);
+
+		let mut gamepad = connected();
+		gamepad.handle_button(GamepadButton::B, Action::Press);
+		assert_eq!(gamepad.changes(None), vec![InputEvent::GamepadConnection(0, false)]);
+	}
+}
@@ -0,0 +1,146 @@
+use crate::Input::{Key, Action};
+use std::collections::HashSet;
+
+/// Keyboard state for the current frame.
+#[derive(Clone, Debug)]
+pub struct Keyboard {
+	down: HashSet<Key>,
+	pressed: HashSet<Key>,
+	released: HashSet<Key>,
+}
+
+impl Keyboard {
+	pub fn new() -> Keyboard {
+		Keyboard { down: HashSet::new(), pressed: HashSet::new(), released: HashSet::new() }
+	}
+
+	/// Forgets the keys pressed and released during the previous frame.
+	pub fn begin_frame(&mut self) {
+		self.pressed.clear();
+		self.released.clear();
+	}
+
+	pub fn handle(&mut self, key: Key, action: Action) {
+		match action {
+			Action::Press => {
+				if self.down.insert(key) {
+					self.pressed.insert(key);
+				}
+			},
+			Action::Release => {
+				if self.down.remove(&key) {
+					self.released.insert(key);
+				}
+			},
+			Action::Repeat => {},
+		}
+	}
+
+	pub fn is_down(&self, key: Key) -> bool {
+		self.down.contains(&key)
+	}
+
+	pub fn was_pressed(&self, key: Key) -> bool {
+		self.pressed.contains(&key)
+	}
+
+	pub fn was_released(&self, key: Key) -> bool {
+		self.released.contains(&key)
+	}
+}
+
+impl Default for Keyboard {
+	fn default() -> Keyboard {
+		Keyboard::new()
+	}
+}
+
+/// Every key, used to look keys up by their name. Includes `Unknown` so
+/// that recorded events for unmapped keys read back.
+pub const KEYS: [Key; 121] = [
+	Key::Space, Key::Apostrophe, Key::Comma, Key::Minus, Key::Period, Key::Slash, Key::Num0,
+	Key::Num1, Key::Num2, Key::Num3, Key::Num4, Key::Num5, Key::Num6, Key::Num7, Key::Num8, Key::Num9,
+	Key::Semicolon, Key::Equal, Key::A, Key::B, Key::C, Key::D, Key::E, Key::F, Key::G, Key::H,
+	Key::I, Key::J, Key::K, Key::L, Key::M, Key::N, Key::O, Key::P, Key::Q, Key::R, Key::S, Key::T,
+	Key::U, Key::V, Key::W, Key::X, Key::Y, Key::Z, Key::LeftBracket, Key::Backslash,
+	Key::RightBracket, Key::GraveAccent, Key::World1, Key::World2, Key::Escape, Key::Enter, Key::Tab,
+	Key::Backspace, Key::Insert, Key::Delete, Key::Right, Key::Left, Key::Down, Key::Up, Key::PageUp,
+	Key::PageDown, Key::Home, Key::End, Key::CapsLock, Key::ScrollLock, Key::NumLock,
+	Key::PrintScreen, Key::Pause, Key::F1, Key::F2, Key::F3, Key::F4, Key::F5, Key::F6, Key::F7,
+	Key::F8, Key::F9, Key::F10, Key::F11, Key::F12, Key::F13, Key::F14, Key::F15, Key::F16, Key::F17,
+	Key::F18, Key::F19, Key::F20, Key::F21, Key::F22, Key::F23, Key::F24, Key::F25, Key::Kp0,
+	Key::Kp1, Key::Kp2, Key::Kp3, Key::Kp4, Key::Kp5, Key::Kp6, Key::Kp7, Key::Kp8, Key::Kp9,
+	Key::KpDecimal, Key::KpDivide, Key::KpMultiply, Key::KpSubtract, Key::KpAdd, Key::KpEnter,
+	Key::KpEqual, Key::LeftShift, Key::LeftControl, Key::LeftAlt, Key::LeftSuper, Key::RightShift,
+	Key::RightControl, Key::RightAlt, Key::RightSuper, Key::Menu, Key::Unknown,
+];
+
+/// Finds a key by the name it is printed with, e.g. `"Space"` or `"LeftShift"`.
+pub fn key_from_name(name: &str) -> Option<Key> {
+	KEYS.iter().copied().find(|key| format!("{:?}", key) == name)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn keyboard_new() {
+		let keyboard = Keyboard::new();
+		assert!(!keyboard.is_down(Key::W));
+		assert!(!keyboard.was_pressed(Key::W));
+		assert!(!keyboard.was_released(Key::W));
+	}
+
+	#[test]
+	fn keyboard_press() {
+		let mut keyboard = Keyboard::new();
+		keyboard.handle(Key::W, Action::Press);
+		assert!(keyboard.is_down(Key::W));
+		assert!(keyboard.was_pressed(Key::W));
+		keyboard.begin_frame();
+		assert!(keyboard.is_down(Key::W));
+		assert!(!keyboard.was_pressed(Key::W));
+	}
+
+	#[test]
+	fn keyboard_repeat() {
+		let mut keyboard = Keyboard::new();
+		keyboard.handle(Key::A, Action::Press);
+		keyboard.begin_frame();
+		keyboard.handle(Key::A, Action::Repeat);
+		keyboard.handle(Key::A, Action::Press);
+		assert!(keyboard.is_down(Key::A));
+		assert!(!keyboard.was_pressed(Key::A));
+	}
+
+	#[test]
+	fn keyboard_release() {
+		let mut keyboard = Keyboard::new();
+		keyboard.handle(Key::S, Action::Press);
+		keyboard.begin_frame();
+		keyboard.handle(Key::S, Action::Release);
+		assert!(!keyboard.is_down(Key::S));
+		assert!(keyboard.was_released(Key::S));
+		keyboard.begin_frame();
+		assert!(!keyboard.was_released(Key::S));
+	}
+
+	#[test]
+	fn keyboard_key_from_name() {
+		assert_eq!(key_from_name("Space"), Some(Key::Space));
+		assert_eq!(key_from_name("LeftShift"), Some(Key::LeftShift));
+		assert_eq!(key_from_name("F12"), Some(Key::F12));
+		assert_eq!(key_from_name("NotAKey"), None);
+	}
+
+	#[test]
+	fn keyboard_tap_in_one_frame() {
+		let mut keyboard = Keyboard::new();
+		keyboard.handle(Key::D, Action::Press);
+		keyboard.handle(Key::D, Action::Release);
+		assert!(!keyboard.is_down(Key::D));
+		assert!(keyboard.was_pressed(Key::D));
+		assert!(keyboard.was_released(Key::D));
+	}
+}
@@ -0,0 +1,331 @@
+//! Keyboard, mouse and gamepad state fed from the window events of the running game.
+//!
+//! The engine updates an `InputState` at the start of every frame, which
+//! games read through `Context::input`, and evaluates the named actions of
+//! `Context::actions` against it. Input can be recorded and replayed
+//! through `Context::start_recording` and `Context::start_replay`.
+
+pub(crate) mod action;
+pub(crate) mod gamepad;
+pub(crate) mod keyboard;
+pub(crate) mod mouse;
+pub(crate) mod record;
+
+pub use action::*;
+pub use gamepad::*;
+pub use keyboard::*;
+pub use mouse::*;
+pub use record::*;
+
+pub use glfw::{Key, Action, MouseButton, MouseButtonLeft, MouseButtonRight, MouseButtonMiddle};
+
+use crate::{Camera2D, Window, Size2D, Math::Vector2D};
+use std::{fmt, str::FromStr};
+
+/// A single input change, independent of the windowing backend.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum InputEvent {
+	Key(Key, Action),
+	MouseButton(MouseButton, Action),
+	CursorPos(f32, f32),
+	Scroll(f32, f32),
+	WindowSize(f32, f32),
+	GamepadConnection(usize, bool),
+	GamepadButton(usize, GamepadButton, Action),
+	GamepadAxis(usize, GamepadAxis, f32),
+}
+
+impl InputEvent {
+	/// Converts a GLFW window event, returning `None` for non-input events.
+	pub fn from_glfw(event: &glfw::WindowEvent) -> Option<InputEvent> {
+		match *event {
+			glfw::WindowEvent::Key(key, _, action, _) => Some(InputEvent::Key(key, action)),
+			glfw::WindowEvent::MouseButton(button, action, _) => Some(InputEvent::MouseButton(button, action)),
+			glfw::WindowEvent::CursorPos(x, y) => Some(InputEvent::CursorPos(x as f32, y as f32)),
+			glfw::WindowEvent::Scroll(x, y) => Some(InputEvent::Scroll(x as f32, y as f32)),
+			glfw::WindowEvent::Size(width, height) => Some(InputEvent::WindowSize(width as f32, height as f32)),
+			_ => None,
+		}
+	}
+}
+
+/// Written as the variant name followed by its values, e.g. `Key Space Press`
+/// or `GamepadAxis 0 LeftX -0.5`.
+impl fmt::Display for InputEvent {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			InputEvent::Key(key, action) => write!(f, "Key {:?} {:?}", key, action),
+			InputEvent::MouseButton(button, action) => write!(f, "MouseButton {} {:?}", mouse_button_name(*button), action),
+			InputEvent::CursorPos(x, y) => write!(f, "CursorPos {} {}", x, y),
+			InputEvent::Scroll(x, y) => write!(f, "Scroll {} {}", x, y),
+			InputEvent::WindowSize(width, height) => write!(f, "WindowSize {} {}", width, height),
+			InputEvent::GamepadConnection(id, connected) => write!(f, "GamepadConnection {} {}", id, connected),
+			InputEvent::GamepadButton(id, button, action) => write!(f, "GamepadButton {} {:?} {:?}", id, button, action),
+			InputEvent::GamepadAxis(id, axis, value) => write!(f, "GamepadAxis {} {:?} {}", id, axis, value),
+		}
+	}
+}
+
+impl FromStr for InputEvent {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<InputEvent, String> {
+		let parts: Vec<&str> = text.split_whitespace().collect();
+		let part = |index: usize| parts.get(index).copied().ok_or_else(|| format!("event '{}' is missing values", text));
+		let number = |index: usize| part(index)?.parse::<f32>().map_err(|_| format!("invalid number in event '{}'", text));
+		let id = |index: usize| match part(index)?.parse::<usize>() {
+			Ok(id) if id < MAX_GAMEPADS => Ok(id),
+			_ => Err(format!("invalid gamepad in event '{}'", text)),
+		};
+		let action = |index: usize| match part(index)? {
+			"Press" => Ok(Action::Press),
+			"Release" => Ok(Action::Release),
+			"Repeat" => Ok(Action::Repeat),
+			other => Err(format!("unknown action '{}'", other)),
+		};
+
+		let event = match part(0)? {
+			"Key" => InputEvent::Key(key_from_name(part(1)?).ok_or_else(|| format!("unknown key '{}'", parts[1]))?, action(2)?),
+			"MouseButton" => InputEvent::MouseButton(mouse_button_from_name(part(1)?).ok_or_else(|| format!("unknown mouse button '{}'", parts[1]))?, action(2)?),
+			"CursorPos" => InputEvent::CursorPos(number(1)?, number(2)?),
+			"Scroll" => InputEvent::Scroll(number(1)?, number(2)?),
+			"WindowSize" => InputEvent::WindowSize(number(1)?, number(2)?),
+			"GamepadConnection" => InputEvent::GamepadConnection(id(1)?, part(2)? == "true"),
+			"GamepadButton" => InputEvent::GamepadButton(id(1)?, GamepadButton::from_name(part(2)?).ok_or_else(|| format!("unknown gamepad button '{}'", parts[2]))?, action(3)?),
+			"GamepadAxis" => InputEvent::GamepadAxis(id(1)?, GamepadAxis::from_name(part(2)?).ok_or_else(|| format!("unknown gamepad axis '{}'", parts[2]))?, number(3)?),
+			other => return Err(format!("unknown event '{}'", other)),
+		};
+		Ok(event)
+	}
+}
+
+/// Every input device the engine tracks.
+#[derive(Clone, Debug)]
+pub struct InputState {
+	pub keyboard: Keyboard,
+	pub mouse: Mouse,
+	pub gamepads: [Gamepad; MAX_GAMEPADS],
+	/// Current size of the window in screen coordinates, once it is known.
+	pub window_size: Option<Size2D>,
+	/// Quit the game when Escape is pressed. Enabled by default.
+	pub quit_on_escape: bool,
+}
+
+impl InputState {
+	pub fn new() -> InputState {
+		let mut gamepads = [Gamepad::new(0); MAX_GAMEPADS];
+		for (id, gamepad) in gamepads.iter_mut().enumerate() {
+			gamepad.id = id;
+		}
+
+		InputState { keyboard: Keyboard::new(), mouse: Mouse::new(), gamepads, window_size: None, quit_on_escape: true }
+	}
+
+	/// Starts a new frame, clearing the per-frame pressed/released state.
+	pub fn begin_frame(&mut self) {
+		self.keyboard.begin_frame();
+		self.mouse.begin_frame();
+		for gamepad in self.gamepads.iter_mut() {
+			gamepad.begin_frame();
+		}
+	}
+
+	pub fn handle(&mut self, event: InputEvent) {
+		match event {
+			InputEvent::Key(key, action) => self.keyboard.handle(key, action),
+			InputEvent::MouseButton(button, action) => self.mouse.handle_button(button, action),
+			InputEvent::CursorPos(x, y) => self.mouse.handle_cursor(x, y),
+			InputEvent::Scroll(x, y) => self.mouse.handle_scroll(x, y),
+			InputEvent::WindowSize(width, height) => self.window_size = Some(Size2D::from(width, height)),
+			InputEvent::GamepadConnection(id, connected) => {
+				if let Some(gamepad) = self.gamepads.get_mut(id) {
+					gamepad.handle_connection(connected);
+				}
+			},
+			InputEvent::GamepadButton(id, button, action) => {
+				if let Some(gamepad) = self.gamepads.get_mut(id) {
+					gamepad.handle_button(button, action);
+				}
+			},
+			InputEvent::GamepadAxis(id, axis, value) => {
+				if let Some(gamepad) = self.gamepads.get_mut(id) {
+					gamepad.handle_axis(axis, value);
+				}
+			},
+		}
+	}
+
+	/// Is `key` currently held down?
+	pub fn is_key_down(&self, key: Key) -> bool {
+		self.keyboard.is_down(key)
+	}
+
+	/// Was `key` pressed this frame?
+	pub fn was_key_pressed(&self, key: Key) -> bool {
+		self.keyboard.was_pressed(key)
+	}
+
+	/// Was `key` released this frame?
+	pub fn was_key_released(&self, key: Key) -> bool {
+		self.keyboard.was_released(key)
+	}
+
+	/// Is `button` currently held down?
+	pub fn is_mouse_down(&self, button: MouseButton) -> bool {
+		self.mouse.is_down(button)
+	}
+
+	/// Was `button` pressed this frame?
+	pub fn was_mouse_pressed(&self, button: MouseButton) -> bool {
+		self.mouse.was_pressed(button)
+	}
+
+	/// Was `button` released this frame?
+	pub fn was_mouse_released(&self, button: MouseButton) -> bool {
+		self.mouse.was_released(button)
+	}
+
+	/// The gamepad in slot `id`, `None` past `MAX_GAMEPADS`.
+	pub fn gamepad(&self, id: usize) -> Option<&Gamepad> {
+		self.gamepads.get(id)
+	}
+
+	/// The lowest numbered gamepad that is connected, if any.
+	pub fn first_gamepad(&self) -> Option<&Gamepad> {
+		self.gamepads.iter().find(|gamepad| gamepad.is_connected())
+	}
+
+	/// Cursor position in window pixels.
+	pub fn cursor_position(&self) -> Vector2D {
+		self.mouse.position
+	}
+
+	/// Scroll wheel offset accumulated this frame.
+	pub fn scroll(&self) -> Vector2D {
+		self.mouse.scroll
+	}
+
+	/// Cursor position in world coordinates as seen through `camera`.
+	///
+	/// The projection is built from `window.size`, so when the window has
+	/// been resized the cursor is first rescaled to that size.
+	pub fn cursor_world(&self, camera: Camera2D, window: Window) -> Vector2D {
+		let mut screen = self.mouse.position;
+		if let Some(size) = self.window_size {
+			if size.x > 0.0 && size.y > 0.0 {
+				screen.x *= window.size.x / size.x;
+				screen.y *= window.size.y / size.y;
+			}
+		}
+		camera.screen_to_world(window, screen)
+	}
+}
+
+impl Default for InputState {
+	fn default() -> InputState {
+		InputState::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn input_event_from_glfw() {
+		let event = glfw::WindowEvent::Key(Key::W, 17, Action::Press, glfw::Modifiers::empty());
+		assert_eq!(InputEvent::from_glfw(&event), Some(InputEvent::Key(Key::W, Action::Press)));
+		assert_eq!(InputEvent::from_glfw(&glfw::WindowEvent::Focus(true)), None);
+		assert_eq!(InputEvent::from_glfw(&glfw::WindowEvent::CursorPos(4.0, 2.0)), Some(InputEvent::CursorPos(4.0, 2.0)));
+		assert_eq!(InputEvent::from_glfw(&glfw::WindowEvent::Scroll(0.0, -1.0)), Some(InputEvent::Scroll(0.0, -1.0)));
+	}
+
+	#[test]
+	fn input_event_display_parse() {
+		let events = [
+			InputEvent::Key(Key::Space, Action::Press),
+			InputEvent::MouseButton(MouseButtonLeft, Action::Release),
+			InputEvent::CursorPos(1.5, -2.0),
+			InputEvent::Scroll(0.0, 1.0),
+			InputEvent::WindowSize(800.0, 600.0),
+			InputEvent::GamepadConnection(3, true),
+			InputEvent::GamepadButton(0, GamepadButton::Start, Action::Repeat),
+			InputEvent::GamepadAxis(15, GamepadAxis::LeftY, -0.125),
+		];
+		for event in events.iter() {
+			assert_eq!(event.to_string().parse::<InputEvent>(), Ok(*event));
+		}
+		assert_eq!(InputEvent::Key(Key::W, Action::Press).to_string(), "Key W Press");
+		assert!("Key".parse::<InputEvent>().is_err());
+		assert!("GamepadButton 16 A Press".parse::<InputEvent>().is_err());
+		assert!("Teleport 1 2".parse::<InputEvent>().is_err());
+	}
+
+	#[test]
+	fn input_state_keys() {
+		let mut input = InputState::new();
+		assert!(input.quit_on_escape);
+		input.handle(InputEvent::Key(Key::Space, Action::Press));
+		assert!(input.is_key_down(Key::Space));
+		assert!(input.was_key_pressed(Key::Space));
+		input.begin_frame();
+		input.handle(InputEvent::Key(Key::Space, Action::Release));
+		assert!(!input.is_key_down(Key::Space));
+		assert!(!input.was_key_pressed(Key::Space));
+		assert!(input.was_key_released(Key::Space));
+	}
+
+	#[test]
+	fn input_state_mouse() {
+		let mut input = InputState::new();
+		input.handle(InputEvent::MouseButton(MouseButtonRight, Action::Press));
+		input.handle(InputEvent::CursorPos(12.0, 8.0));
+		input.handle(InputEvent::Scroll(0.0, 1.0));
+		assert!(input.is_mouse_down(MouseButtonRight));
+		assert!(input.was_mouse_pressed(MouseButtonRight));
+		assert_eq!(input.cursor_position(), Vector2D::from(12.0, 8.0));
+		assert_eq!(input.scroll(), Vector2D::from(0.0, 1.0));
+		input.begin_frame();
+		input.handle(InputEvent::MouseButton(MouseButtonRight, Action::Release));
+		assert!(input.was_mouse_released(MouseButtonRight));
+		assert_eq!(input.scroll(), Vector2D::new());
+	}
+
+	#[test]
+	fn input_state_gamepads() {
+		let mut input = InputState::new();
+		assert!(input.first_gamepad().is_none());
+		input.handle(InputEvent::GamepadConnection(1, true));
+		input.handle(InputEvent::GamepadButton(1, GamepadButton::Start, Action::Press));
+		input.handle(InputEvent::GamepadAxis(1, GamepadAxis::LeftY, 1.0));
+		let gamepad = input.first_gamepad().unwrap();
+		assert_eq!(gamepad.id, 1);
+		assert!(gamepad.was_connected() && gamepad.was_pressed(GamepadButton::Start));
+		assert_eq!(gamepad.axis(GamepadAxis::LeftY), 1.0);
+		input.begin_frame();
+		assert!(!input.gamepad(1).unwrap().was_pressed(GamepadButton::Start));
+		assert!(input.gamepad(1).unwrap().is_down(GamepadButton::Start));
+	}
+
+	#[test]
+	fn input_state_gamepad_out_of_range() {
+		let mut input = InputState::new();
+		input.handle(InputEvent::GamepadConnection(MAX_GAMEPADS, true));
+		input.handle(InputEvent::GamepadButton(MAX_GAMEPADS, GamepadButton::A, Action::Press));
+		input.handle(InputEvent::GamepadAxis(usize::MAX, GamepadAxis::LeftX, 1.0));
+		assert!(input.first_gamepad().is_none());
+		assert!(input.gamepad(MAX_GAMEPADS).is_none());
+	}
+
+	#[test]
+	fn input_state_cursor_world() {
+		let window = Window::new().size(Size2D::from(800, 600));
+		let camera = Camera2D::from(Vector2D::from(400.0, 300.0), 1.0);
+		let mut input = InputState::new();
+		input.handle(InputEvent::CursorPos(100.0, 50.0));
+		assert_eq!(input.cursor_world(camera, window), Vector2D::from(100.0, 50.0));
+		input.handle(InputEvent::WindowSize(1600.0, 1200.0));
+		input.handle(InputEvent::CursorPos(200.0, 100.0));
+		assert_eq!(input.cursor_world(camera, window), Vector2D::from(100.0, 50.0));
+	}
+}
@@ -0,0 +1,157 @@
+use crate::{Input::{MouseButton, Action}, Math::Vector2D};
+use std::collections::HashSet;
+
+/// Mouse state for the current frame. Positions are in window pixels
+/// with the origin at the top left corner.
+#[derive(Clone, Debug)]
+pub struct Mouse {
+	pub position: Vector2D,
+	pub motion: Vector2D,
+	pub scroll: Vector2D,
+	down: HashSet<MouseButton>,
+	pressed: HashSet<MouseButton>,
+	released: HashSet<MouseButton>,
+}
+
+impl Mouse {
+	pub fn new() -> Mouse {
+		Mouse {
+			position: Vector2D::new(),
+			motion: Vector2D::new(),
+			scroll: Vector2D::new(),
+			down: HashSet::new(),
+			pressed: HashSet::new(),
+			released: HashSet::new(),
+		}
+	}
+
+	/// Forgets the buttons, motion and scrolling of the previous frame.
+	pub fn begin_frame(&mut self) {
+		self.motion = Vector2D::new();
+		self.scroll = Vector2D::new();
+		self.pressed.clear();
+		self.released.clear();
+	}
+
+	pub fn handle_button(&mut self, button: MouseButton, action: Action) {
+		match action {
+			Action::Press => {
+				if self.down.insert(button) {
+					self.pressed.insert(button);
+				}
+			},
+			Action::Release => {
+				if self.down.remove(&button) {
+					self.released.insert(button);
+				}
+			},
+			Action::Repeat => {},
+		}
+	}
+
+	pub fn handle_cursor(&mut self, x: f32, y: f32) {
+		let position = Vector2D::from(x, y);
+		self.motion += position - self.position;
+		self.position = position;
+	}
+
+	pub fn handle_scroll(&mut self, x: f32, y: f32) {
+		self.scroll += Vector2D::from(x, y);
+	}
+
+	pub fn is_down(&self, button: MouseButton) -> bool {
+		self.down.contains(&button)
+	}
+
+	pub fn was_pressed(&self, button: MouseButton) -> bool {
+		self.pressed.contains(&button)
+	}
+
+	pub fn was_released(&self, button: MouseButton) -> bool {
+		self.released.contains(&button)
+	}
+}
+
+impl Default for Mouse {
+	fn default() -> Mouse {
+		Mouse::new()
+	}
+}
+
+const MOUSE_BUTTONS: [MouseButton; 8] = [
+	MouseButton::Button1, MouseButton::Button2, MouseButton::Button3, MouseButton::Button4,
+	MouseButton::Button5, MouseButton::Button6, MouseButton::Button7, MouseButton::Button8,
+];
+
+/// Name of a mouse button, using `Left`, `Right` and `Middle` for the first three.
+pub fn mouse_button_name(button: MouseButton) -> String {
+	match button {
+		MouseButton::Button1 => String::from("Left"),
+		MouseButton::Button2 => String::from("Right"),
+		MouseButton::Button3 => String::from("Middle"),
+		_ => format!("{:?}", button),
+	}
+}
+
+/// Finds a mouse button by the name returned from `mouse_button_name`.
+pub fn mouse_button_from_name(name: &str) -> Option<MouseButton> {
+	MOUSE_BUTTONS.iter().copied().find(|button| mouse_button_name(*button) == name)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Input::MouseButtonLeft;
+
+	#[test]
+	fn mouse_new() {
+		let mouse = Mouse::new();
+		assert_eq!(mouse.position, Vector2D::new());
+		assert_eq!(mouse.scroll, Vector2D::new());
+		assert!(!mouse.is_down(MouseButtonLeft));
+	}
+
+	#[test]
+	fn mouse_buttons() {
+		let mut mouse = Mouse::new();
+		mouse.handle_button(MouseButtonLeft, Action::Press);
+		assert!(mouse.is_down(MouseButtonLeft));
+		assert!(mouse.was_pressed(MouseButtonLeft));
+		mouse.begin_frame();
+		mouse.handle_button(MouseButtonLeft, Action::Release);
+		assert!(!mouse.is_down(MouseButtonLeft));
+		assert!(!mouse.was_pressed(MouseButtonLeft));
+		assert!(mouse.was_released(MouseButtonLeft));
+	}
+
+	#[test]
+	fn mouse_cursor() {
+		let mut mouse = Mouse::new();
+		mouse.handle_cursor(10.0, 20.0);
+		mouse.handle_cursor(15.0, 30.0);
+		assert_eq!(mouse.position, Vector2D::from(15.0, 30.0));
+		assert_eq!(mouse.motion, Vector2D::from(15.0, 30.0));
+		mouse.begin_frame();
+		assert_eq!(mouse.motion, Vector2D::new());
+		assert_eq!(mouse.position, Vector2D::from(15.0, 30.0));
+	}
+
+	#[test]
+	fn mouse_button_names() {
+		assert_eq!(mouse_button_name(MouseButtonLeft), "Left");
+		assert_eq!(mouse_button_name(MouseButton::Button5), "Button5");
+		assert_eq!(mouse_button_from_name("Middle"), Some(MouseButton::Button3));
+		assert_eq!(mouse_button_from_name("Button8"), Some(MouseButton::Button8));
+		assert_eq!(mouse_button_from_name("Button1"), None);
+	}
+
+	#[test]
+	fn mouse_scroll() {
+		let mut mouse = Mouse::new();
+		mouse.handle_scroll(0.0, 1.0);
+		mouse.handle_scroll(0.0, 2.0);
+		assert_eq!(mouse.scroll, Vector2D::from(0.0, 3.0));
+		mouse.begin_frame();
+		assert_eq!(mouse.scroll, Vector2D::new());
+	}
+}
@@ -0,0 +1,232 @@
+use crate::Input::InputEvent;
+use std::{fs, io, path::Path, str::FromStr};
+
+/// Every input event of a run together with the frame it arrived in and
+/// the delta time of each frame, so a replay reproduces the same simulation.
+///
+/// Saved recordings are plain text with one entry per line:
+///
+/// ```text
+/// 0 delta 0.016666666666666666
+/// 0 Key Space Press
+/// 1 delta 0.017
+/// 1 CursorPos 120 48.5
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+	deltas: Vec<f64>,
+	/// Sorted by frame.
+	events: Vec<(u64, InputEvent)>,
+}
+
+impl Recording {
+	pub fn new() -> Recording {
+		Recording { deltas: Vec::new(), events: Vec::new() }
+	}
+
+	/// Adds an event to the frame that is currently being recorded.
+	pub fn push_event(&mut self, event: InputEvent) {
+		self.events.push((self.deltas.len() as u64, event));
+	}
+
+	/// Closes the current frame with its delta time.
+	pub fn push_frame(&mut self, delta: f64) {
+		self.deltas.push(delta);
+	}
+
+	/// Number of recorded frames.
+	pub fn len(&self) -> usize {
+		self.deltas.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.deltas.is_empty()
+	}
+
+	pub fn delta(&self, frame: u64) -> Option<f64> {
+		self.deltas.get(frame as usize).copied()
+	}
+
+	/// Events recorded during `frame`, in the order they arrived.
+	pub fn events(&self, frame: u64) -> Vec<InputEvent> {
+		let start = self.events.partition_point(|(at, _)| *at < frame);
+		let end = self.events.partition_point(|(at, _)| *at <= frame);
+		self.events[start..end].iter().map(|(_, event)| *event).collect()
+	}
+
+	pub fn to_text(&self) -> String {
+		let mut text = String::new();
+		let mut events = self.events.iter().peekable();
+		for (frame, delta) in self.deltas.iter().enumerate() {
+			text.push_str(&format!("{} delta {}\n", frame, delta));
+			while let Some((_, event)) = events.next_if(|(at, _)| *at == frame as u64) {
+				text.push_str(&format!("{} {}\n", frame, event));
+			}
+		}
+		for (frame, event) in events {
+			text.push_str(&format!("{} {}\n", frame, event));
+		}
+		text
+	}
+
+	pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Recording> {
+		fs::read_to_string(path)?
+			.parse()
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+	}
+
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+		fs::write(path, self.to_text())
+	}
+}
+
+impl Default for Recording {
+	fn default() -> Recording {
+		Recording::new()
+	}
+}
+
+impl FromStr for Recording {
+	type Err = String;
+
+	fn from_str(text: &str) -> Result<Recording, String> {
+		let mut recording = Recording::new();
+		for (number, line) in text.lines().enumerate() {
+			let line = line.trim();
+			if line.is_empty() || line.starts_with('#') {
+				continue;
+			}
+			let error = |message: String| format!("line {}: {}", number + 1, message);
+			let (frame, entry) = line.split_once(' ').ok_or_else(|| error(String::from("expected '<frame> <entry>'")))?;
+			let frame: u64 = frame.parse().map_err(|_| error(format!("invalid frame '{}'", frame)))?;
+
+			match entry.trim().strip_prefix("delta ") {
+				Some(delta) => {
+					if frame != recording.deltas.len() as u64 {
+						return Err(error(format!("expected the delta of frame {}", recording.deltas.len())));
+					}
+					let delta = delta.trim().parse().map_err(|_| error(format!("invalid delta '{}'", delta.trim())))?;
+					recording.deltas.push(delta);
+				},
+				None => {
+					let event = entry.parse().map_err(error)?;
+					recording.events.push((frame, event));
+				},
+			}
+		}
+		// events may be listed out of frame order, the sort keeps their order within a frame
+		recording.events.sort_by_key(|(frame, _)| *frame);
+		Ok(recording)
+	}
+}
+
+/// Plays a `Recording` back one frame at a time.
+#[derive(Clone, Debug)]
+pub struct Replay {
+	recording: Recording,
+	frame: u64,
+}
+
+impl Replay {
+	pub fn new(recording: Recording) -> Replay {
+		Replay { recording, frame: 0 }
+	}
+
+	/// Returns the delta and events of the next frame, or `None` once
+	/// every recorded frame has been played.
+	pub fn next_frame(&mut self) -> Option<(f64, Vec<InputEvent>)> {
+		let delta = self.recording.delta(self.frame)?;
+		let events = self.recording.events(self.frame);
+		self.frame += 1;
+		Some((delta, events))
+	}
+
+	pub fn is_finished(&self) -> bool {
+		self.frame as usize >= self.recording.len()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::Input::{Key, Action, MouseButtonLeft, GamepadButton, GamepadAxis};
+
+	fn sample() -> Recording {
+		let mut recording = Recording::new();
+		recording.push_event(InputEvent::Key(Key::Space, Action::Press));
+		recording.push_event(InputEvent::CursorPos(120.0, 48.5));
+		recording.push_frame(1.0 / 60.0);
+		recording.push_frame(0.017);
+		recording.push_event(InputEvent::MouseButton(MouseButtonLeft, Action::Release));
+		recording.push_event(InputEvent::GamepadButton(1, GamepadButton::X, Action::Press));
+		recording.push_event(InputEvent::GamepadAxis(1, GamepadAxis::RightTrigger, 0.25));
+		recording.push_frame(0.02);
+		recording
+	}
+
+	#[test]
+	fn recording_push() {
+		let recording = sample();
+		assert_eq!(recording.len(), 3);
+		assert_eq!(recording.delta(1), Some(0.017));
+		assert_eq!(recording.delta(3), None);
+		assert_eq!(recording.events(0), vec![InputEvent::Key(Key::Space, Action::Press), InputEvent::CursorPos(120.0, 48.5)]);
+		assert!(recording.events(1).is_empty());
+		assert_eq!(recording.events(2).len(), 3);
+	}
+
+	#[test]
+	fn recording_text() {
+		let recording = sample();
+		let text = recording.to_text();
+		assert!(text.starts_with("0 delta 0.016666666666666666\n0 Key Space Press\n0 CursorPos 120 48.5\n1 delta 0.017\n"));
+		assert_eq!(text.parse::<Recording>(), Ok(recording));
+	}
+
+	#[test]
+	fn recording_text_every_key() {
+		let mut recording = Recording::new();
+		for key in crate::Input::KEYS.iter().copied() {
+			recording.push_event(InputEvent::Key(key, Action::Press));
+		}
+		recording.push_frame(0.02);
+		assert!(recording.to_text().contains("0 Key Unknown Press\n"));
+		assert_eq!(recording.to_text().parse::<Recording>(), Ok(recording));
+	}
+
+	#[test]
+	fn recording_text_out_of_order() {
+		let recording: Recording = "0 delta 0.1\n1 Key A Press\n1 delta 0.1\n0 Key B Press\n1 Key C Press\n".parse().unwrap();
+		assert_eq!(recording.events(0), vec![InputEvent::Key(Key::B, Action::Press)]);
+		assert_eq!(recording.events(1), vec![InputEvent::Key(Key::A, Action::Press), InputEvent::Key(Key::C, Action::Press)]);
+	}
+
+	#[test]
+	fn recording_parse_errors() {
+		assert!("1 delta 0.1".parse::<Recording>().is_err());
+		assert!("0 delta fast".parse::<Recording>().is_err());
+		assert!("x Key Space Press".parse::<Recording>().is_err());
+		assert!("0 Key Space Hold".parse::<Recording>().is_err());
+	}
+
+	#[test]
+	fn recording_save_load() {
+		let path = std::env::temp_dir().join(format!("engine_recording_{}.txt", std::process::id()));
+		let recording = sample();
+		recording.save(&path).unwrap();
+		let loaded = Recording::load(&path).unwrap();
+		std::fs::remove_file(&path).unwrap();
+		assert_eq!(loaded, recording);
+	}
+
+	#[test]
+	fn replay_next_frame() {
+		let mut replay = Replay::new(sample());
+		assert_eq!(replay.next_frame().unwrap().1.len(), 2);
+		assert_eq!(replay.next_frame(), Some((0.017, Vec::new())));
+		assert!(!replay.is_finished());
+		assert_eq!(replay.next_frame().unwrap().0, 0.02);
+		assert!(replay.is_finished());
+		assert_eq!(replay.next_frame(), None);
+	}
+}
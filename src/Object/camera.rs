@@ -1,5 +1,5 @@
 use std::cmp::{Eq, PartialEq};
-use crate::{Math::{Vector2D, Matrix4x4}, Window, Scale3D};
+use crate::{Math::{Vector2D, Matrix4x4}, Window};
 
 #[derive(Copy, Clone, Debug)]
 pub struct Camera2D {
@@ -16,16 +16,32 @@ impl Camera2D {
         Camera2D { focus_position, zoom: zoom.into() as f32 }
     }
 
+	/// Orthographic projection showing `window.size / zoom` world units
+	/// around the focus position, with y pointing down.
 	pub fn get_projection_matrix(self, window: Window) -> Matrix4x4 {
-		let left = self.focus_position.x - window.size.x / 2.0;
-		let right = self.focus_position.x + window.size.x / 2.0;
-		let top = self.focus_position.y - window.size.y / 2.0;
-		let bottom = self.focus_position.y + window.size.y / 2.0;
+		let half_width = window.size.x / 2.0 / self.zoom;
+		let half_height = window.size.y / 2.0 / self.zoom;
 
-		let orthoMatrix: Matrix4x4 = Matrix4x4::create_orthographic_off_center(left, right, bottom, top, 0.01, 100.0);
-		let zoomMatrix: Matrix4x4 = Matrix4x4::create_scale(Scale3D::from(self.zoom, self.zoom, self.zoom));
+		let left = self.focus_position.x - half_width;
+		let right = self.focus_position.x + half_width;
+		let top = self.focus_position.y - half_height;
+		let bottom = self.focus_position.y + half_height;
 
-		orthoMatrix.mult(zoomMatrix)
+		Matrix4x4::create_orthographic_off_center(left, right, bottom, top, 0.01, 100.0)
+	}
+
+	/// Converts a point in window pixels (origin top left) to world coordinates.
+	/// This is the inverse of `get_projection_matrix` followed by the viewport
+	/// transform, written out so integer pixels map to exact world positions.
+	pub fn screen_to_world(self, window: Window, screen: Vector2D) -> Vector2D {
+		let center = Vector2D::from(window.size.x / 2.0, window.size.y / 2.0);
+		self.focus_position + (screen - center) / self.zoom
+	}
+
+	/// Converts a point in world coordinates to window pixels (origin top left).
+	pub fn world_to_screen(self, window: Window, world: Vector2D) -> Vector2D {
+		let center = Vector2D::from(window.size.x / 2.0, window.size.y / 2.0);
+		center + (world - self.focus_position) * self.zoom
 	}
 }
 
@@ -52,4 +68,48 @@ mod tests {
         let cam = Camera2D::from(Vector2D::from(10.0, 10.0), 2.0);
         assert_eq!(cam, Camera2D { focus_position: Vector2D { x: 10.0, y: 10.0 }, zoom: 2.0 });
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn camera_screen_to_world() {
+        let window = Window::new().size(crate::Size2D::from(800, 600));
+        let cam = Camera2D::from(Vector2D::from(100.0, 100.0), 2.0);
+        assert_eq!(cam.screen_to_world(window, Vector2D::from(400.0, 300.0)), Vector2D::from(100.0, 100.0));
+        assert_eq!(cam.screen_to_world(window, Vector2D::from(0.0, 0.0)), Vector2D::from(-100.0, -50.0));
+    }
+
+    #[test]
+    fn camera_world_to_screen() {
+        let window = Window::new().size(crate::Size2D::from(800, 600));
+        let cam = Camera2D::from(Vector2D::from(100.0, 100.0), 2.0);
+        let screen = Vector2D::from(123.0, 456.0);
+        assert_eq!(cam.world_to_screen(window, cam.screen_to_world(window, screen)), screen);
+    }
+
+    #[test]
+    fn camera_projection_matrix() {
+        let window = Window::new().size(crate::Size2D::from(800, 600));
+        let cam = Camera2D::from(Vector2D::from(100.0, 100.0), 2.0);
+        let m = cam.get_projection_matrix(window).identity;
+        let project = |p: Vector2D| (m[0][0] * p.x + m[1][0] * p.y + m[3][0], m[0][1] * p.x + m[1][1] * p.y + m[3][1]);
+        let (x, y) = project(cam.screen_to_world(window, Vector2D::from(0.0, 0.0)));
+        assert!((x + 1.0).abs() < 1e-6 && (y - 1.0).abs() < 1e-6);
+        let (x, y) = project(cam.screen_to_world(window, Vector2D::from(800.0, 600.0)));
+        assert!((x - 1.0).abs() < 1e-6 && (y + 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn camera_conversions_match_projection() {
+        let window = Window::new().size(crate::Size2D::from(640, 480));
+        for &(focus, zoom) in &[(Vector2D::from(0.0, 0.0), 1.0), (Vector2D::from(250.0, -40.0), 0.5), (Vector2D::from(-75.0, 310.0), 3.0)] {
+            let cam = Camera2D::from(focus, zoom);
+            let m = cam.get_projection_matrix(window).identity;
+            for &world in &[Vector2D::from(0.0, 0.0), Vector2D::from(100.0, 50.0), Vector2D::from(-320.0, 12.5)] {
+                let ndc_x = m[0][0] * world.x + m[1][0] * world.y + m[3][0];
+                let ndc_y = m[0][1] * world.x + m[1][1] * world.y + m[3][1];
+                let expected = Vector2D::from((ndc_x + 1.0) / 2.0 * 640.0, (1.0 - ndc_y) / 2.0 * 480.0);
+                let screen = cam.world_to_screen(window, world);
+                assert!((screen.x - expected.x).abs() < 1e-2 && (screen.y - expected.y).abs() < 1e-2);
+            }
+        }
+    }
+}
@@ -0,0 +1,351 @@
+use crate::Transform2D;
+use std::cell::Cell;
+
+/// Handle to a node of a `SceneTree`. Handles of removed nodes are never
+/// handed out again, so a stale handle cannot reach a newer node.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NodeId {
+	index: u32,
+	generation: u32,
+}
+
+#[derive(Clone, Debug)]
+struct Node {
+	local: Transform2D,
+	parent: Option<NodeId>,
+	children: Vec<NodeId>,
+	/// World transform, `None` until computed or after an ancestor changed.
+	world: Cell<Option<Transform2D>>,
+}
+
+/// A hierarchy of transforms where every node is placed relative to its parent.
+///
+/// World transforms are computed on demand and cached until the node or
+/// one of its ancestors moves.
+///
+/// ```rust
+/// use Engine::{SceneTree, Transform2D, Position2D, Rotation2D, Scale2D};
+///
+/// let mut scene = SceneTree::new();
+/// let player = scene.insert(None, Transform2D::from(Position2D::from(100, 50), Rotation2D::new(), Scale2D::new()));
+/// let hand = scene.insert(Some(player), Transform2D::from(Position2D::from(10, 0), Rotation2D::new(), Scale2D::new()));
+///
+/// let mut moved = scene.local(player).unwrap();
+/// moved.position.x += 20.0;
+/// scene.set_local(player, moved);
+/// assert_eq!(scene.world(hand).unwrap().position, Position2D::from(130, 50));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SceneTree {
+	slots: Vec<(u32, Option<Node>)>,
+	free: Vec<u32>,
+	roots: Vec<NodeId>,
+}
+
+impl SceneTree {
+	pub const fn new() -> SceneTree {
+		SceneTree { slots: Vec::new(), free: Vec::new(), roots: Vec::new() }
+	}
+
+	/// Adds a node placed by `local` under `parent`, or as a root without one.
+	pub fn insert(&mut self, parent: Option<NodeId>, local: Transform2D) -> NodeId {
+		if let Some(parent) = parent {
+			assert!(self.contains(parent), "parent node does not exist");
+		}
+		let node = Node { local, parent, children: Vec::new(), world: Cell::new(None) };
+		let id = match self.free.pop() {
+			Some(index) => {
+				let slot = &mut self.slots[index as usize];
+				slot.1 = Some(node);
+				NodeId { index, generation: slot.0 }
+			},
+			None => {
+				self.slots.push((0, Some(node)));
+				NodeId { index: self.slots.len() as u32 - 1, generation: 0 }
+			},
+		};
+		self.siblings_mut(parent).push(id);
+		id
+	}
+
+	/// Removes a node together with all of its descendants.
+	/// Returns `false` if the node did not exist.
+	pub fn remove(&mut self, id: NodeId) -> bool {
+		let parent = match self.node(id) {
+			Some(node) => node.parent,
+			None => return false,
+		};
+		self.siblings_mut(parent).retain(|&sibling| sibling != id);
+
+		for node in self.descendants(id) {
+			let slot = &mut self.slots[node.index as usize];
+			slot.0 += 1;
+			slot.1 = None;
+			self.free.push(node.index);
+		}
+		true
+	}
+
+	pub fn contains(&self, id: NodeId) -> bool {
+		self.node(id).is_some()
+	}
+
+	pub fn len(&self) -> usize {
+		self.slots.len() - self.free.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Nodes without a parent, in insertion order.
+	pub fn roots(&self) -> &[NodeId] {
+		&self.roots
+	}
+
+	pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+		self.node(id).and_then(|node| node.parent)
+	}
+
+	pub fn children(&self, id: NodeId) -> &[NodeId] {
+		self.node(id).map_or(&[], |node| &node.children)
+	}
+
+	/// The node followed by all of its descendants, parents before children.
+	pub fn descendants(&self, id: NodeId) -> Vec<NodeId> {
+		let mut nodes = Vec::new();
+		if self.contains(id) {
+			nodes.push(id);
+			let mut next = 0;
+			while next < nodes.len() {
+				nodes.extend_from_slice(self.children(nodes[next]));
+				next += 1;
+			}
+		}
+		nodes
+	}
+
+	/// The transform of a node relative to its parent.
+	pub fn local(&self, id: NodeId) -> Option<Transform2D> {
+		self.node(id).map(|node| node.local)
+	}
+
+	/// Moves a node relative to its parent; its children follow. Returns
+	/// `false` if the node does not exist.
+	pub fn set_local(&mut self, id: NodeId, local: Transform2D) -> bool {
+		match self.node_mut(id) {
+			Some(node) => node.local = local,
+			None => return false,
+		}
+		self.invalidate(id);
+		true
+	}
+
+	/// The transform of a node in world space, cached until it or one of
+	/// its ancestors moves.
+	pub fn world(&self, id: NodeId) -> Option<Transform2D> {
+		let node = self.node(id)?;
+		if let Some(world) = node.world.get() {
+			return Some(world);
+		}
+		let world = match node.parent {
+			Some(parent) => self.world(parent)?.combine(node.local),
+			None => node.local,
+		};
+		node.world.set(Some(world));
+		Some(world)
+	}
+
+	/// Moves a node so it ends up at `world` in world space. Returns
+	/// `false` if the node does not exist.
+	pub fn set_world(&mut self, id: NodeId, world: Transform2D) -> bool {
+		let local = match self.parent(id) {
+			Some(parent) => world.relative_to(self.world(parent).unwrap()),
+			None => world,
+		};
+		self.set_local(id, local)
+	}
+
+	/// Moves a node and its descendants under `parent`, or makes it a root.
+	///
+	/// With `keep_world` the node stays where it is on screen, otherwise it
+	/// keeps its local transform and jumps along with the new parent.
+	pub fn reparent(&mut self, id: NodeId, parent: Option<NodeId>, keep_world: bool) -> Result<(), String> {
+		let old = self.node(id).ok_or_else(|| String::from("node does not exist"))?.parent;
+		if let Some(parent) = parent {
+			if !self.contains(parent) {
+				return Err(String::from("parent node does not exist"));
+			}
+			if self.is_ancestor(id, parent) {
+				return Err(String::from("a node cannot be moved under itself or its descendants"));
+			}
+		}
+
+		let world = self.world(id).unwrap();
+		self.siblings_mut(old).retain(|&sibling| sibling != id);
+		self.siblings_mut(parent).push(id);
+		self.node_mut(id).unwrap().parent = parent;
+
+		if keep_world {
+			self.set_world(id, world);
+		} else {
+			self.invalidate(id);
+		}
+		Ok(())
+	}
+
+	/// Whether `ancestor` is `id` or one of the nodes above it.
+	pub fn is_ancestor(&self, ancestor: NodeId, id: NodeId) -> bool {
+		let mut current = Some(id);
+		while let Some(node) = current {
+			if node == ancestor {
+				return true;
+			}
+			current = self.parent(node);
+		}
+		false
+	}
+
+	/// Drops the cached world transforms of a node and its descendants.
+	fn invalidate(&self, id: NodeId) {
+		let mut stack = vec![id];
+		while let Some(id) = stack.pop() {
+			let node = self.node(id).unwrap();
+			// a child is only ever cached after its parent, so an uncached
+			// node has no cached descendants left to drop
+			if node.world.take().is_some() {
+				stack.extend_from_slice(&node.children);
+			}
+		}
+	}
+
+	fn node(&self, id: NodeId) -> Option<&Node> {
+		match self.slots.get(id.index as usize) {
+			Some((generation, node)) if *generation == id.generation => node.as_ref(),
+			_ => None,
+		}
+	}
+
+	fn node_mut(&mut self, id: NodeId) -> Option<&mut Node> {
+		match self.slots.get_mut(id.index as usize) {
+			Some((generation, node)) if *generation == id.generation => node.as_mut(),
+			_ => None,
+		}
+	}
+
+	fn siblings_mut(&mut self, parent: Option<NodeId>) -> &mut Vec<NodeId> {
+		match parent {
+			Some(parent) => &mut self.node_mut(parent).unwrap().children,
+			None => &mut self.roots,
+		}
+	}
+}
+
+impl Default for SceneTree {
+	fn default() -> SceneTree {
+		SceneTree::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Position2D, Rotation2D, Scale2D, test_util::{at, close_position}};
+
+	#[test]
+	fn scene_tree_insert() {
+		let mut scene = SceneTree::new();
+		let root = scene.insert(None, at(1.0, 2.0));
+		let child = scene.insert(Some(root), at(3.0, 4.0));
+		assert_eq!(scene.len(), 2);
+		assert_eq!(scene.roots(), &[root]);
+		assert_eq!(scene.children(root), &[child]);
+		assert_eq!(scene.parent(child), Some(root));
+		assert_eq!(scene.world(child).unwrap().position, Position2D::from(4, 6));
+	}
+
+	#[test]
+	fn scene_tree_world_inherits() {
+		let mut scene = SceneTree::new();
+		let body = scene.insert(None, Transform2D::from(Position2D::from(100, 0), Rotation2D::from_deg(90), Scale2D::from(2, 2)));
+		let hand = scene.insert(Some(body), at(10.0, 0.0));
+		let weapon = scene.insert(Some(hand), Transform2D::from(Position2D::from(5, 0), Rotation2D::from_deg(45), Scale2D::new()));
+		let world = scene.world(weapon).unwrap();
+		assert!(close_position(world.position, Position2D::from(100, 30)));
+		assert_eq!((world.rotation.deg(), world.scale), (135.0, Scale2D::from(2, 2)));
+	}
+
+	#[test]
+	fn scene_tree_invalidate() {
+		let mut scene = SceneTree::new();
+		let root = scene.insert(None, at(0.0, 0.0));
+		let child = scene.insert(Some(root), at(1.0, 0.0));
+		let grandchild = scene.insert(Some(child), at(1.0, 0.0));
+		assert_eq!(scene.world(grandchild).unwrap().position, Position2D::from(2, 0));
+
+		scene.set_local(root, at(10.0, 10.0));
+		assert_eq!(scene.world(grandchild).unwrap().position, Position2D::from(12, 10));
+		scene.set_local(child, at(-1.0, 0.0));
+		assert_eq!(scene.world(grandchild).unwrap().position, Position2D::from(10, 10));
+		assert_eq!(scene.world(root).unwrap().position, Position2D::from(10, 10));
+	}
+
+	#[test]
+	fn scene_tree_set_world() {
+		let mut scene = SceneTree::new();
+		let root = scene.insert(None, Transform2D::from(Position2D::from(50, 50), Rotation2D::from_deg(90), Scale2D::from(2, 2)));
+		let child = scene.insert(Some(root), at(0.0, 0.0));
+		assert!(scene.set_world(child, at(50.0, 70.0)));
+		assert!(close_position(scene.world(child).unwrap().position, Position2D::from(50, 70)));
+		assert!(close_position(scene.local(child).unwrap().position, Position2D::from(10, 0)));
+	}
+
+	#[test]
+	fn scene_tree_reparent() {
+		let mut scene = SceneTree::new();
+		let player = scene.insert(None, at(100.0, 0.0));
+		let chest = scene.insert(None, at(0.0, 100.0));
+		let sword = scene.insert(Some(chest), at(5.0, 5.0));
+
+		scene.reparent(sword, Some(player), true).unwrap();
+		assert_eq!(scene.children(chest), &[]);
+		assert_eq!(scene.children(player), &[sword]);
+		assert!(close_position(scene.world(sword).unwrap().position, Position2D::from(5, 105)));
+
+		scene.reparent(sword, None, false).unwrap();
+		assert_eq!(scene.roots(), &[player, chest, sword]);
+		assert!(close_position(scene.world(sword).unwrap().position, Position2D::from(-95, 105)));
+	}
+
+	#[test]
+	fn scene_tree_reparent_cycle() {
+		let mut scene = SceneTree::new();
+		let root = scene.insert(None, at(0.0, 0.0));
+		let child = scene.insert(Some(root), at(0.0, 0.0));
+		assert!(scene.reparent(root, Some(child), true).is_err());
+		assert!(scene.reparent(root, Some(root), true).is_err());
+		assert_eq!(scene.parent(child), Some(root));
+	}
+
+	#[test]
+	fn scene_tree_remove() {
+		let mut scene = SceneTree::new();
+		let root = scene.insert(None, at(0.0, 0.0));
+		let child = scene.insert(Some(root), at(0.0, 0.0));
+		let grandchild = scene.insert(Some(child), at(0.0, 0.0));
+		assert_eq!(scene.descendants(root), vec![root, child, grandchild]);
+
+		assert!(scene.remove(child));
+		assert!(!scene.remove(child));
+		assert!(!scene.contains(grandchild));
+		assert_eq!(scene.children(root), &[]);
+		assert_eq!(scene.len(), 1);
+
+		let reused = scene.insert(None, at(0.0, 0.0));
+		assert_ne!(reused, child);
+		assert_ne!(reused, grandchild);
+		assert_eq!(scene.world(child), None);
+		assert!(!scene.set_local(grandchild, at(1.0, 1.0)));
+		assert!(!scene.set_world(child, at(1.0, 1.0)));
+	}
+}
@@ -0,0 +1,371 @@
+use std::{any::{Any, TypeId}, collections::HashMap};
+
+/// Handle to an entity of a `World`. Handles of despawned entities are
+/// never handed out again, so a stale handle cannot reach a newer entity.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Entity {
+	index: u32,
+	generation: u32,
+}
+
+impl Entity {
+	pub fn index(self) -> u32 {
+		self.index
+	}
+}
+
+/// All components of one type, packed densely with a lookup by entity index.
+struct Storage<T> {
+	sparse: Vec<Option<usize>>,
+	entities: Vec<Entity>,
+	components: Vec<T>,
+}
+
+impl<T> Storage<T> {
+	fn new() -> Storage<T> {
+		Storage { sparse: Vec::new(), entities: Vec::new(), components: Vec::new() }
+	}
+
+	fn slot(&self, entity: Entity) -> Option<usize> {
+		self.sparse.get(entity.index as usize).copied().flatten()
+	}
+
+	fn insert(&mut self, entity: Entity, component: T) -> Option<T> {
+		if let Some(slot) = self.slot(entity) {
+			return Some(std::mem::replace(&mut self.components[slot], component));
+		}
+		let index = entity.index as usize;
+		if self.sparse.len() <= index {
+			self.sparse.resize(index + 1, None);
+		}
+		self.sparse[index] = Some(self.components.len());
+		self.entities.push(entity);
+		self.components.push(component);
+		None
+	}
+
+	fn remove(&mut self, entity: Entity) -> Option<T> {
+		let slot = self.slot(entity)?;
+		self.sparse[entity.index as usize] = None;
+		self.entities.swap_remove(slot);
+		if let Some(moved) = self.entities.get(slot) {
+			self.sparse[moved.index as usize] = Some(slot);
+		}
+		Some(self.components.swap_remove(slot))
+	}
+}
+
+/// Type erased `Storage` so storages of every component type fit in one map.
+trait AnyStorage {
+	fn remove_entity(&mut self, entity: Entity);
+	fn as_any(&self) -> &dyn Any;
+	fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: 'static> AnyStorage for Storage<T> {
+	fn remove_entity(&mut self, entity: Entity) {
+		self.remove(entity);
+	}
+
+	fn as_any(&self) -> &dyn Any {
+		self
+	}
+
+	fn as_any_mut(&mut self) -> &mut dyn Any {
+		self
+	}
+}
+
+/// Entities and their components.
+///
+/// Any `'static` type can be a component, e.g. `Transform2D`, `Sprite2D`,
+/// `Animation2D` or `Collision::Collision2D`. An entity holds at most one
+/// component of each type. Behaviour lives in systems, see `Systems`.
+///
+/// ```rust
+/// use Engine::{World, Transform2D, Position2D};
+///
+/// struct Velocity(f32, f32);
+///
+/// let mut world = World::new();
+/// let player = world.spawn();
+/// world.insert(player, Transform2D::new());
+/// world.insert(player, Velocity(10.0, 0.0));
+///
+/// for (_, transform, velocity) in world.query2_mut::<Transform2D, Velocity>() {
+///     transform.position.x += velocity.0;
+///     transform.position.y += velocity.1;
+/// }
+/// assert_eq!(world.get::<Transform2D>(player).unwrap().position, Position2D::from(10, 0));
+/// ```
+pub struct World {
+	generations: Vec<u32>,
+	alive: Vec<bool>,
+	free: Vec<u32>,
+	storages: HashMap<TypeId, Box<dyn AnyStorage>>,
+}
+
+impl World {
+	pub fn new() -> World {
+		World { generations: Vec::new(), alive: Vec::new(), free: Vec::new(), storages: HashMap::new() }
+	}
+
+	/// Creates an entity without components.
+	pub fn spawn(&mut self) -> Entity {
+		match self.free.pop() {
+			Some(index) => {
+				self.alive[index as usize] = true;
+				Entity { index, generation: self.generations[index as usize] }
+			},
+			None => {
+				self.generations.push(0);
+				self.alive.push(true);
+				Entity { index: self.generations.len() as u32 - 1, generation: 0 }
+			},
+		}
+	}
+
+	/// Removes an entity and all of its components.
+	/// Returns `false` if the entity was already despawned.
+	pub fn despawn(&mut self, entity: Entity) -> bool {
+		if !self.is_alive(entity) {
+			return false;
+		}
+		for storage in self.storages.values_mut() {
+			storage.remove_entity(entity);
+		}
+		let index = entity.index as usize;
+		self.alive[index] = false;
+		self.generations[index] += 1;
+		self.free.push(entity.index);
+		true
+	}
+
+	pub fn is_alive(&self, entity: Entity) -> bool {
+		let index = entity.index as usize;
+		index < self.alive.len() && self.alive[index] && self.generations[index] == entity.generation
+	}
+
+	/// Number of living entities.
+	pub fn len(&self) -> usize {
+		self.alive.len() - self.free.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Every living entity, in index order.
+	pub fn entities(&self) -> Vec<Entity> {
+		(0..self.alive.len())
+			.filter(|&index| self.alive[index])
+			.map(|index| Entity { index: index as u32, generation: self.generations[index] })
+			.collect()
+	}
+
+	/// Adds a component to an entity, returning the component of the same
+	/// type it replaced.
+	pub fn insert<T: 'static>(&mut self, entity: Entity, component: T) -> Option<T> {
+		assert!(self.is_alive(entity), "entity does not exist");
+		self.storages.entry(TypeId::of::<T>())
+			.or_insert_with(|| Box::new(Storage::<T>::new()))
+			.as_any_mut()
+			.downcast_mut::<Storage<T>>()
+			.unwrap()
+			.insert(entity, component)
+	}
+
+	pub fn remove<T: 'static>(&mut self, entity: Entity) -> Option<T> {
+		if !self.is_alive(entity) {
+			return None;
+		}
+		self.storage_mut::<T>()?.remove(entity)
+	}
+
+	pub fn get<T: 'static>(&self, entity: Entity) -> Option<&T> {
+		if !self.is_alive(entity) {
+			return None;
+		}
+		let storage = self.storage::<T>()?;
+		storage.slot(entity).map(|slot| &storage.components[slot])
+	}
+
+	pub fn get_mut<T: 'static>(&mut self, entity: Entity) -> Option<&mut T> {
+		if !self.is_alive(entity) {
+			return None;
+		}
+		let storage = self.storage_mut::<T>()?;
+		storage.slot(entity).map(move |slot| &mut storage.components[slot])
+	}
+
+	pub fn has<T: 'static>(&self, entity: Entity) -> bool {
+		self.get::<T>(entity).is_some()
+	}
+
+	/// Every entity with a `T`, with its component.
+	pub fn query<T: 'static>(&self) -> impl Iterator<Item = (Entity, &T)> {
+		self.storage::<T>().into_iter()
+			.flat_map(|storage| storage.entities.iter().copied().zip(storage.components.iter()))
+	}
+
+	pub fn query_mut<T: 'static>(&mut self) -> impl Iterator<Item = (Entity, &mut T)> {
+		self.storage_mut::<T>().into_iter()
+			.flat_map(|storage| storage.entities.iter().copied().zip(storage.components.iter_mut()))
+	}
+
+	/// Every entity with both an `A` and a `B`, with its components.
+	pub fn query2<A: 'static, B: 'static>(&self) -> impl Iterator<Item = (Entity, &A, &B)> {
+		let b = self.storage::<B>();
+		self.query::<A>().filter_map(move |(entity, a)| {
+			let b = b?;
+			b.slot(entity).map(|slot| (entity, a, &b.components[slot]))
+		})
+	}
+
+	/// Like `query2`, with both components mutable. `A` and `B` must be
+	/// different types.
+	pub fn query2_mut<A: 'static, B: 'static>(&mut self) -> Vec<(Entity, &mut A, &mut B)> {
+		assert!(TypeId::of::<A>() != TypeId::of::<B>(), "query2_mut needs two different component types");
+		let (mut a, mut b) = (None, None);
+		for (id, storage) in self.storages.iter_mut() {
+			if *id == TypeId::of::<A>() {
+				a = Some(storage);
+			} else if *id == TypeId::of::<B>() {
+				b = Some(storage);
+			}
+		}
+		let (a, b) = match (a, b) {
+			(Some(a), Some(b)) => (a, b),
+			_ => return Vec::new(),
+		};
+		let a = a.as_any_mut().downcast_mut::<Storage<A>>().unwrap();
+		let b = b.as_any_mut().downcast_mut::<Storage<B>>().unwrap();
+
+		let mut by_index: Vec<Option<&mut B>> = Vec::new();
+		by_index.resize_with(b.sparse.len(), || None);
+		for (entity, component) in b.entities.iter().zip(b.components.iter_mut()) {
+			by_index[entity.index as usize] = Some(component);
+		}
+		a.entities.iter().copied().zip(a.components.iter_mut())
+			.filter_map(|(entity, a)| {
+				let b = by_index.get_mut(entity.index as usize)?.take()?;
+				Some((entity, a, b))
+			})
+			.collect()
+	}
+
+	fn storage<T: 'static>(&self) -> Option<&Storage<T>> {
+		self.storages.get(&TypeId::of::<T>()).map(|storage| storage.as_any().downcast_ref::<Storage<T>>().unwrap())
+	}
+
+	fn storage_mut<T: 'static>(&mut self) -> Option<&mut Storage<T>> {
+		self.storages.get_mut(&TypeId::of::<T>()).map(|storage| storage.as_any_mut().downcast_mut::<Storage<T>>().unwrap())
+	}
+}
+
+impl Default for World {
+	fn default() -> World {
+		World::new()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Position2D, Transform2D};
+
+	#[derive(Debug, PartialEq)]
+	struct Health(i32);
+
+	#[derive(Debug, PartialEq)]
+	struct Velocity(f32, f32);
+
+	#[test]
+	fn world_spawn_despawn() {
+		let mut world = World::new();
+		let a = world.spawn();
+		let b = world.spawn();
+		assert_eq!(world.len(), 2);
+		assert!(world.despawn(a));
+		assert!(!world.despawn(a));
+		assert!(!world.is_alive(a) && world.is_alive(b));
+
+		let c = world.spawn();
+		assert_eq!(c.index(), a.index());
+		assert_ne!(c, a);
+		assert_eq!(world.entities(), vec![c, b]);
+	}
+
+	#[test]
+	fn world_components() {
+		let mut world = World::new();
+		let entity = world.spawn();
+		assert_eq!(world.insert(entity, Health(10)), None);
+		assert_eq!(world.insert(entity, Health(5)), Some(Health(10)));
+		world.get_mut::<Health>(entity).unwrap().0 -= 1;
+		assert_eq!(world.get::<Health>(entity), Some(&Health(4)));
+		assert!(!world.has::<Velocity>(entity));
+		assert_eq!(world.remove::<Health>(entity), Some(Health(4)));
+		assert_eq!(world.get::<Health>(entity), None);
+	}
+
+	#[test]
+	fn world_despawn_components() {
+		let mut world = World::new();
+		let a = world.spawn();
+		let b = world.spawn();
+		world.insert(a, Health(1));
+		world.insert(b, Health(2));
+		world.despawn(a);
+		let reused = world.spawn();
+		assert_eq!(world.get::<Health>(reused), None);
+		assert_eq!(world.get::<Health>(a), None);
+		assert_eq!(world.query::<Health>().collect::<Vec<_>>(), vec![(b, &Health(2))]);
+	}
+
+	#[test]
+	fn world_query() {
+		let mut world = World::new();
+		let entities: Vec<Entity> = (0..4).map(|_| world.spawn()).collect();
+		for (i, &entity) in entities.iter().enumerate() {
+			world.insert(entity, Health(i as i32));
+			if i % 2 == 0 {
+				world.insert(entity, Velocity(1.0, 0.0));
+			}
+		}
+		for (_, health) in world.query_mut::<Health>() {
+			health.0 *= 10;
+		}
+		let moving: Vec<(Entity, i32)> = world.query2::<Health, Velocity>().map(|(entity, health, _)| (entity, health.0)).collect();
+		assert_eq!(moving, vec![(entities[0], 0), (entities[2], 20)]);
+		assert_eq!(world.query::<Transform2D>().count(), 0);
+	}
+
+	#[test]
+	fn world_query2_mut() {
+		let mut world = World::new();
+		let still = world.spawn();
+		world.insert(still, Transform2D::new());
+		let moving = world.spawn();
+		world.insert(moving, Velocity(2.0, 3.0));
+		world.insert(moving, Transform2D::new());
+
+		for (_, transform, velocity) in world.query2_mut::<Transform2D, Velocity>() {
+			transform.position.x += velocity.0;
+			transform.position.y += velocity.1;
+			velocity.0 = 0.0;
+		}
+		assert_eq!(world.get::<Transform2D>(moving).unwrap().position, Position2D::from(2, 3));
+		assert_eq!(world.get::<Transform2D>(still).unwrap().position, Position2D::new());
+		assert_eq!(world.get::<Velocity>(moving), Some(&Velocity(0.0, 3.0)));
+	}
+
+	#[test]
+	#[should_panic]
+	fn world_insert_despawned() {
+		let mut world = World::new();
+		let entity = world.spawn();
+		world.despawn(entity);
+		world.insert(entity, Health(1));
+	}
+}
@@ -0,0 +1,235 @@
+use crate::{Transform2D, Math::Vector2D};
+
+/// How a body takes part in the simulation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BodyType {
+	/// Moved by gravity, forces and contacts.
+	Dynamic,
+	/// Moved only by its velocity, as if infinitely heavy. Pushes dynamic
+	/// bodies but is never pushed back, e.g. moving platforms.
+	Kinematic,
+	/// Never moves, e.g. level geometry.
+	Static,
+}
+
+/// A body simulated by a `PhysicsWorld2D`.
+///
+/// Lengths are in world units (pixels at zoom 1), velocities in units per
+/// second and angles in radians.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RigidBody2D {
+	/// Written back by the world every step.
+	pub transform: Transform2D,
+	pub body_type: BodyType,
+	pub velocity: Vector2D,
+	pub angular_velocity: f32,
+	pub mass: f32,
+	/// Fraction of the velocity lost per second.
+	pub drag: f32,
+	pub angular_drag: f32,
+	/// Combined with the friction of the other body as their geometric mean.
+	pub friction: f32,
+	/// Bounciness from 0 (none) to 1 (no energy lost). The larger of the two
+	/// bodies' values is used.
+	pub restitution: f32,
+	pub gravity_scale: f32,
+	/// Keeps the body from rotating, e.g. for characters.
+	pub fixed_rotation: bool,
+	pub can_sleep: bool,
+	pub(crate) inertia: f32,
+	pub(crate) force: Vector2D,
+	pub(crate) torque: f32,
+	pub(crate) sleeping: bool,
+	pub(crate) idle: f32,
+}
+
+impl RigidBody2D {
+	pub const fn new(transform: Transform2D) -> RigidBody2D {
+		RigidBody2D {
+			transform,
+			body_type: BodyType::Dynamic,
+			velocity: Vector2D::new(),
+			angular_velocity: 0.0,
+			mass: 1.0,
+			drag: 0.0,
+			angular_drag: 0.05,
+			friction: 0.5,
+			restitution: 0.0,
+			gravity_scale: 1.0,
+			fixed_rotation: false,
+			can_sleep: true,
+			inertia: 0.0,
+			force: Vector2D::new(),
+			torque: 0.0,
+			sleeping: false,
+			idle: 0.0,
+		}
+	}
+
+	pub const fn body_type(mut self, body_type: BodyType) -> RigidBody2D {
+		self.body_type = body_type;
+		self
+	}
+
+	pub const fn mass(mut self, mass: f32) -> RigidBody2D {
+		self.mass = mass;
+		self
+	}
+
+	pub const fn velocity(mut self, velocity: Vector2D) -> RigidBody2D {
+		self.velocity = velocity;
+		self
+	}
+
+	pub const fn drag(mut self, drag: f32, angular_drag: f32) -> RigidBody2D {
+		self.drag = drag;
+		self.angular_drag = angular_drag;
+		self
+	}
+
+	pub const fn friction(mut self, friction: f32) -> RigidBody2D {
+		self.friction = friction;
+		self
+	}
+
+	pub const fn restitution(mut self, restitution: f32) -> RigidBody2D {
+		self.restitution = restitution;
+		self
+	}
+
+	pub const fn gravity_scale(mut self, gravity_scale: f32) -> RigidBody2D {
+		self.gravity_scale = gravity_scale;
+		self
+	}
+
+	pub const fn fixed_rotation(mut self, fixed_rotation: bool) -> RigidBody2D {
+		self.fixed_rotation = fixed_rotation;
+		self
+	}
+
+	pub const fn can_sleep(mut self, can_sleep: bool) -> RigidBody2D {
+		self.can_sleep = can_sleep;
+		self
+	}
+
+	pub fn is_dynamic(&self) -> bool {
+		self.body_type == BodyType::Dynamic
+	}
+
+	/// 1 / mass, or 0 for bodies that contacts cannot move.
+	pub fn inverse_mass(&self) -> f32 {
+		if self.is_dynamic() && self.mass > 0.0 { 1.0 / self.mass } else { 0.0 }
+	}
+
+	/// 1 / moment of inertia around the body origin, or 0 for bodies that
+	/// contacts cannot rotate. The inertia is computed from the collider
+	/// when the body is added to a world.
+	pub fn inverse_inertia(&self) -> f32 {
+		if self.is_dynamic() && !self.fixed_rotation && self.inertia > 0.0 { 1.0 / self.inertia } else { 0.0 }
+	}
+
+	/// Pushes the body at its origin for the next step.
+	pub fn apply_force(&mut self, force: Vector2D) {
+		self.force += force;
+		self.wake();
+	}
+
+	/// Pushes the body at `point` in world space, which also turns it.
+	pub fn apply_force_at(&mut self, force: Vector2D, point: Vector2D) {
+		self.apply_force(force);
+		self.torque += cross(point - self.origin(), force);
+	}
+
+	pub fn apply_torque(&mut self, torque: f32) {
+		self.torque += torque;
+		self.wake();
+	}
+
+	/// Changes the velocity at once, e.g. for a jump or an explosion.
+	pub fn apply_impulse(&mut self, impulse: Vector2D) {
+		self.velocity += impulse * self.inverse_mass();
+		self.wake();
+	}
+
+	/// Like `apply_impulse`, at `point` in world space.
+	pub fn apply_impulse_at(&mut self, impulse: Vector2D, point: Vector2D) {
+		self.apply_impulse(impulse);
+		self.angular_velocity += cross(point - self.origin(), impulse) * self.inverse_inertia();
+	}
+
+	/// Velocity of the point of the body at `point` in world space.
+	pub fn velocity_at(&self, point: Vector2D) -> Vector2D {
+		self.velocity + cross_scalar(self.angular_velocity, point - self.origin())
+	}
+
+	/// Sleeping bodies are skipped by the simulation until something
+	/// touches or pushes them.
+	pub fn is_sleeping(&self) -> bool {
+		self.sleeping
+	}
+
+	pub fn wake(&mut self) {
+		self.sleeping = false;
+		self.idle = 0.0;
+	}
+
+	pub fn sleep(&mut self) {
+		self.sleeping = true;
+		self.velocity = Vector2D::new();
+		self.angular_velocity = 0.0;
+	}
+
+	pub(crate) fn origin(&self) -> Vector2D {
+		Vector2D::from(self.transform.position.x, self.transform.position.y)
+	}
+}
+
+/// Z component of the cross product of two vectors in the plane.
+pub(crate) fn cross(a: Vector2D, b: Vector2D) -> f32 {
+	a.x * b.y - a.y * b.x
+}
+
+/// Cross product of an angular velocity with a vector: the velocity of a
+/// point at `v` from the center of rotation.
+pub(crate) fn cross_scalar(s: f32, v: Vector2D) -> Vector2D {
+	Vector2D::from(-s * v.y, s * v.x)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn rigid_body_2d_new() {
+		let body = RigidBody2D::new(Transform2D::new()).mass(2.0).friction(0.1).restitution(0.5).body_type(BodyType::Kinematic);
+		assert_eq!((body.mass, body.friction, body.restitution, body.body_type), (2.0, 0.1, 0.5, BodyType::Kinematic));
+		assert_eq!(body.inverse_mass(), 0.0);
+		assert_eq!(RigidBody2D::new(Transform2D::new()).mass(4.0).inverse_mass(), 0.25);
+	}
+
+	#[test]
+	fn rigid_body_2d_impulse() {
+		let mut body = RigidBody2D::new(Transform2D::new()).mass(2.0);
+		body.inertia = 4.0;
+		body.sleep();
+		body.apply_impulse(Vector2D::from(4.0, 0.0));
+		assert_eq!(body.velocity, Vector2D::from(2.0, 0.0));
+		assert!(!body.is_sleeping());
+
+		body.apply_impulse_at(Vector2D::from(0.0, 2.0), Vector2D::from(2.0, 0.0));
+		assert_eq!(body.velocity, Vector2D::from(2.0, 1.0));
+		assert_eq!(body.angular_velocity, 1.0);
+		assert_eq!(body.velocity_at(Vector2D::from(0.0, 1.0)), Vector2D::from(1.0, 1.0));
+
+		let mut fixed = body.fixed_rotation(true);
+		fixed.apply_impulse_at(Vector2D::from(0.0, 2.0), Vector2D::from(2.0, 0.0));
+		assert_eq!(fixed.angular_velocity, 1.0);
+	}
+
+	#[test]
+	fn rigid_body_2d_force() {
+		let mut body = RigidBody2D::new(Transform2D::new());
+		body.apply_force_at(Vector2D::from(0.0, 3.0), Vector2D::from(2.0, 0.0));
+		assert_eq!((body.force, body.torque), (Vector2D::from(0.0, 3.0), 6.0));
+	}
+}
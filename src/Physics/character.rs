@@ -0,0 +1,594 @@
+use super::{BodyId, PhysicsWorld2D};
+use crate::{Position2D, Rect2D, Size2D, Transform2D, Math::Vector2D};
+use crate::Collision::{Collision2D, Contact2D, narrow::{Compound, collide_compound, perpendicular}};
+
+/// Bisection steps when searching the time of impact of a sweep.
+const SWEEP_ITERATIONS: u32 = 16;
+/// One-way colliders overlapped deeper than this at the start of a move are
+/// being passed through and are ignored until the move ends.
+const ONE_WAY_MARGIN: f32 = 1.0;
+
+/// Which kind of surface a character ran into, decided by the angle between
+/// the surface normal and `CharacterBody2D::up`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Surface {
+	Floor,
+	Wall,
+	Ceiling,
+}
+
+/// A surface a `CharacterBody2D` ran into during `move_and_slide`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SlideCollision2D {
+	pub body: BodyId,
+	pub surface: Surface,
+	/// Points away from the surface, towards the character.
+	pub normal: Vector2D,
+	/// Where the character touched the surface.
+	pub position: Position2D,
+}
+
+/// A body moved by code rather than by forces, e.g. a platformer character.
+///
+/// `move_and_slide` sweeps the collider along `velocity` against the bodies
+/// of a `PhysicsWorld2D` and slides along whatever it hits, remembering
+/// whether it ended on a floor, against a wall or under a ceiling. The
+/// character does not push the bodies it hits; `collisions` lists them so
+/// the game can.
+///
+/// The character is not a body of the world: dynamic bodies do not collide
+/// with it and queries such as `raycast` and `query_point` do not find it.
+/// Pair it with a kinematic body moved to `transform` after each move when
+/// other bodies need to see it.
+///
+/// ```rust
+/// use Engine::{Transform2D, Position2D, Rotation2D, Scale2D, Math::Vector2D};
+/// use Engine::Physics::{PhysicsWorld2D, RigidBody2D, BodyType, CharacterBody2D};
+/// use Engine::Collision::{Collision2D, Shape2D, Rectangle};
+///
+/// let mut world = PhysicsWorld2D::new();
+/// let ground = Transform2D::from(Position2D::from(0, 100), Rotation2D::new(), Scale2D::new());
+/// world.insert(RigidBody2D::new(ground).body_type(BodyType::Static), Collision2D::new(Shape2D::Rectangle(Rectangle::new(400.0, 20.0))));
+///
+/// let mut player = CharacterBody2D::new(Transform2D::new(), Collision2D::new(Shape2D::Rectangle(Rectangle::new(16.0, 32.0))));
+/// for _ in 0..60 {
+///     player.velocity.x = 100.0;
+///     player.velocity += world.gravity * (1.0 / 60.0);
+///     player.move_and_slide(&world, 1.0 / 60.0);
+/// }
+/// assert!(player.is_on_floor());
+/// assert!((player.transform.position.y - 74.0).abs() < 0.5);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CharacterBody2D {
+	pub transform: Transform2D,
+	pub collider: Collision2D,
+	/// Units per second. Movement into surfaces is removed by `move_and_slide`.
+	pub velocity: Vector2D,
+	/// Unit vector pointing away from floors.
+	pub up: Vector2D,
+	/// Steepest slope in radians still counted as floor. Steeper surfaces are walls.
+	pub floor_max_angle: f32,
+	/// Keeps the character from sliding down floor slopes, and moves it along
+	/// them at its horizontal speed.
+	pub stop_on_slope: bool,
+	/// How far the character is pulled down to stay on a floor it walks off
+	/// the top of, e.g. going down a slope or steps. 0 disables snapping.
+	pub snap_length: f32,
+	/// Most surfaces slid along per move.
+	pub max_slides: u32,
+	/// Gap kept between the character and what it hits.
+	pub safe_margin: f32,
+	floor: Option<(BodyId, Vector2D)>,
+	/// Where the floor body was at the end of the last move.
+	platform: Option<Position2D>,
+	wall: Option<Vector2D>,
+	ceiling: Option<Vector2D>,
+	collisions: Vec<SlideCollision2D>,
+}
+
+/// A body the character may hit during one move.
+#[derive(Clone)]
+struct Obstacle {
+	id: BodyId,
+	shape: Compound,
+	/// The blocking direction of one-way colliders.
+	one_way: Option<Vector2D>,
+}
+
+impl CharacterBody2D {
+	pub fn new(transform: Transform2D, collider: Collision2D) -> CharacterBody2D {
+		CharacterBody2D {
+			transform,
+			collider,
+			velocity: Vector2D::new(),
+			up: Vector2D::from(0.0, -1.0),
+			floor_max_angle: std::f32::consts::FRAC_PI_4,
+			stop_on_slope: true,
+			snap_length: 0.0,
+			max_slides: 4,
+			safe_margin: 0.08,
+			floor: None,
+			platform: None,
+			wall: None,
+			ceiling: None,
+			collisions: Vec::new(),
+		}
+	}
+
+	pub fn up(mut self, up: Vector2D) -> CharacterBody2D {
+		self.up = up;
+		self
+	}
+
+	pub fn floor_max_angle(mut self, angle: f32) -> CharacterBody2D {
+		self.floor_max_angle = angle;
+		self
+	}
+
+	pub fn stop_on_slope(mut self, stop_on_slope: bool) -> CharacterBody2D {
+		self.stop_on_slope = stop_on_slope;
+		self
+	}
+
+	pub fn snap_length(mut self, snap_length: f32) -> CharacterBody2D {
+		self.snap_length = snap_length;
+		self
+	}
+
+	pub fn is_on_floor(&self) -> bool {
+		self.floor.is_some()
+	}
+
+	pub fn is_on_wall(&self) -> bool {
+		self.wall.is_some()
+	}
+
+	pub fn is_on_ceiling(&self) -> bool {
+		self.ceiling.is_some()
+	}
+
+	/// Normal of the floor the last move ended on.
+	pub fn floor_normal(&self) -> Option<Vector2D> {
+		self.floor.map(|(_, normal)| normal)
+	}
+
+	/// The body the character stands on. When it moves, the character is
+	/// carried along by the next move.
+	pub fn floor_body(&self) -> Option<BodyId> {
+		self.floor.map(|(body, _)| body)
+	}
+
+	pub fn wall_normal(&self) -> Option<Vector2D> {
+		self.wall
+	}
+
+	pub fn ceiling_normal(&self) -> Option<Vector2D> {
+		self.ceiling
+	}
+
+	/// Surfaces hit during the last move, in order.
+	pub fn collisions(&self) -> &[SlideCollision2D] {
+		&self.collisions
+	}
+
+	/// Moves the character by `velocity` for `delta` seconds, sliding along
+	/// the surfaces of `world` it runs into. Returns whether it hit anything.
+	pub fn move_and_slide(&mut self, world: &PhysicsWorld2D, delta: f32) -> bool {
+		let floor_body = self.floor_body();
+		let carry = match (floor_body.and_then(|id| world.body(id)), self.platform) {
+			(Some(body), Some(last)) => Vector2D::from(body.transform.position.x - last.x, body.transform.position.y - last.y),
+			_ => Vector2D::new(),
+		};
+		let was_on_floor = self.is_on_floor();
+		self.floor = None;
+		self.wall = None;
+		self.ceiling = None;
+		self.collisions.clear();
+
+		let motion = self.velocity * delta;
+		let snap = if was_on_floor { self.up * -self.snap_length } else { Vector2D::new() };
+		let mut obstacles = self.obstacles(world, &[carry, motion, snap]);
+
+		// ride along with the floor, stopping at anything else in the way
+		if !carry > 0.0 {
+			let others: Vec<Obstacle> = obstacles.iter().filter(|obstacle| Some(obstacle.id) != floor_body).map(Obstacle::clone).collect();
+			let time = self.sweep(&others, carry).map_or(1.0, |(time, ..)| time);
+			self.translate(carry * time);
+		}
+		self.depenetrate(&mut obstacles);
+
+		let mut remaining = motion;
+		for _ in 0..self.max_slides {
+			if !remaining <= f32::EPSILON {
+				break;
+			}
+			let (time, obstacle, contact) = match self.sweep(&obstacles, remaining) {
+				Some(hit) => hit,
+				None => {
+					self.translate(remaining);
+					break;
+				},
+			};
+			self.translate(remaining * time);
+			let surface = self.surface(contact.normal);
+			self.touch(obstacles[obstacle].id, surface, &contact);
+			remaining = self.slide(remaining * (1.0 - time), contact.normal, surface);
+		}
+
+		if was_on_floor && !self.is_on_floor() && self.snap_length > 0.0 && self.velocity * self.up <= 0.0 {
+			if let Some((time, obstacle, contact)) = self.sweep(&obstacles, snap) {
+				if self.surface(contact.normal) == Surface::Floor {
+					self.translate(snap * time);
+					self.floor = Some((obstacles[obstacle].id, contact.normal));
+				}
+			}
+		}
+		self.platform = self.floor_body().and_then(|id| world.body(id)).map(|body| body.transform.position);
+		!self.collisions.is_empty()
+	}
+
+	/// Solid bodies near the path of a move whose layers collide with the character.
+	fn obstacles(&self, world: &PhysicsWorld2D, moves: &[Vector2D]) -> Vec<Obstacle> {
+		let bounds = self.collider.bounds(self.transform);
+		let (left, right) = (moves.iter().map(|motion| motion.x.min(0.0)).sum::<f32>(), moves.iter().map(|motion| motion.x.max(0.0)).sum::<f32>());
+		let (top, bottom) = (moves.iter().map(|motion| motion.y.min(0.0)).sum::<f32>(), moves.iter().map(|motion| motion.y.max(0.0)).sum::<f32>());
+		let margin = self.safe_margin + ONE_WAY_MARGIN;
+		let swept = Rect2D {
+			position: Position2D { x: bounds.left() + left - margin, y: bounds.top() + top - margin },
+			size: Size2D { x: bounds.size.x + right - left + 2.0 * margin, y: bounds.size.y + bottom - top + 2.0 * margin },
+		};
+
+		world.near(swept, u32::MAX).into_iter()
+			.filter_map(|id| world.entry(id).map(|(body, collider)| (id, body, collider)))
+			.filter(|(_, _, collider)| !collider.sensor && collider.interacts(&self.collider))
+			.map(|(id, body, collider)| (id, body, collider, Compound::from(&collider.shape, body.transform)))
+			.filter(|(.., shape)| shape.bounds().intersects(swept))
+			.map(|(id, body, collider, shape)| {
+				let angle = body.transform.rotation.rad();
+				let one_way = if collider.one_way { Some(Vector2D::from(angle.sin(), -angle.cos())) } else { None };
+				Obstacle { id, shape, one_way }
+			})
+			.collect()
+	}
+
+	/// Pushes the character out of whatever it starts overlapping, e.g. a
+	/// platform that moved into it. One-way colliders it is passing through
+	/// are dropped from `obstacles`.
+	fn depenetrate(&mut self, obstacles: &mut Vec<Obstacle>) {
+		let cos = self.floor_max_angle.cos();
+		obstacles.retain(|obstacle| match (obstacle.one_way, self.contact(obstacle, Vector2D::new())) {
+			(Some(up), Some(contact)) => contact.normal * up >= cos && contact.depth <= ONE_WAY_MARGIN,
+			_ => true,
+		});
+		for _ in 0..self.max_slides {
+			let deepest = obstacles.iter()
+				.filter_map(|obstacle| self.contact(obstacle, Vector2D::new()))
+				.max_by(|a, b| a.depth.total_cmp(&b.depth));
+			match deepest {
+				Some(contact) => self.translate(contact.normal * (contact.depth + self.safe_margin)),
+				None => break,
+			}
+		}
+	}
+
+	/// The fraction of `motion` the character can move before hitting an
+	/// obstacle, less the safe margin, with the obstacle and the contact.
+	fn sweep(&self, obstacles: &[Obstacle], motion: Vector2D) -> Option<(f32, usize, Contact2D)> {
+		let length = !motion;
+		if length <= 0.0 || !length.is_finite() || obstacles.is_empty() {
+			return None;
+		}
+		let hit = |time: f32| {
+			obstacles.iter().enumerate()
+				.filter(|(_, obstacle)| obstacle.one_way.is_none_or(|up| motion * up < 0.0))
+				.filter_map(|(i, obstacle)| self.contact(obstacle, motion * time).map(|contact| (i, contact)))
+				.filter(|(i, contact)| obstacles[*i].one_way.is_none_or(|up| contact.normal * up >= self.floor_max_angle.cos()))
+				.max_by(|a, b| a.1.depth.total_cmp(&b.1.depth))
+		};
+
+		// steps of half the character's size so thin obstacles are not skipped
+		let bounds = self.collider.bounds(self.transform);
+		let step = (bounds.size.x.min(bounds.size.y) / 2.0).max(self.safe_margin);
+		let steps = (length / step).ceil().max(1.0) as u32;
+		let (mut low, mut high) = (0.0, 0.0);
+		let mut found = None;
+		for i in 1..=steps {
+			let time = i as f32 / steps as f32;
+			if let Some(contact) = hit(time) {
+				high = time;
+				found = Some(contact);
+				break;
+			}
+			low = time;
+		}
+		let mut found = found?;
+		for _ in 0..SWEEP_ITERATIONS {
+			let middle = (low + high) / 2.0;
+			match hit(middle) {
+				Some(contact) => {
+					high = middle;
+					found = contact;
+				},
+				None => low = middle,
+			}
+		}
+		Some(((low - self.safe_margin / length).max(0.0), found.0, found.1))
+	}
+
+	/// The contact of the character moved by `offset` with `obstacle`,
+	/// with the normal pointing towards the character.
+	fn contact(&self, obstacle: &Obstacle, offset: Vector2D) -> Option<Contact2D> {
+		let mut transform = self.transform;
+		transform.position.x += offset.x;
+		transform.position.y += offset.y;
+		collide_compound(&obstacle.shape, &Compound::from(&self.collider.shape, transform))
+	}
+
+	fn surface(&self, normal: Vector2D) -> Surface {
+		let cos = self.floor_max_angle.cos();
+		if normal * self.up >= cos {
+			Surface::Floor
+		} else if -(normal * self.up) >= cos {
+			Surface::Ceiling
+		} else {
+			Surface::Wall
+		}
+	}
+
+	/// Records a hit and removes the velocity going into the surface.
+	fn touch(&mut self, body: BodyId, surface: Surface, contact: &Contact2D) {
+		let normal = contact.normal;
+		match surface {
+			Surface::Floor => self.floor = Some((body, normal)),
+			Surface::Wall => self.wall = Some(normal),
+			Surface::Ceiling => self.ceiling = Some(normal),
+		}
+		self.collisions.push(SlideCollision2D {
+			body,
+			surface,
+			normal,
+			position: contact.points.first().copied().unwrap_or(self.transform.position),
+		});
+
+		self.velocity = if surface == Surface::Floor && self.stop_on_slope {
+			let speed = self.velocity * self.up;
+			if speed < 0.0 { self.velocity - self.up * speed } else { self.velocity }
+		} else {
+			self.slide(self.velocity, normal, surface)
+		};
+	}
+
+	/// What is left of `remaining` after running into a surface.
+	fn slide(&self, remaining: Vector2D, normal: Vector2D, surface: Surface) -> Vector2D {
+		if surface == Surface::Floor && self.stop_on_slope {
+			// keep the horizontal part and follow the slope with it
+			let side = perpendicular(self.up);
+			let (horizontal, along) = (remaining * side, perpendicular(normal) * side);
+			if horizontal.abs() <= f32::EPSILON || along.abs() <= f32::EPSILON {
+				return Vector2D::new();
+			}
+			return perpendicular(normal) * (horizontal / along);
+		}
+		let project = |motion: Vector2D| {
+			let into = motion * normal;
+			if into < 0.0 { motion - normal * into } else { motion }
+		};
+		let slid = project(remaining);
+		// walls too steep to stand on are slid down but never climbed
+		if surface == Surface::Wall && slid * self.up > 0.0 && remaining * self.up <= 0.0 {
+			return project(self.up * (remaining * self.up));
+		}
+		slid
+	}
+
+	fn translate(&mut self, offset: Vector2D) {
+		self.transform.position.x += offset.x;
+		self.transform.position.y += offset.y;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{test_util::{STEP, at, boxed, solid, with_ground}, Physics::{BodyType, RigidBody2D}, Collision::{Polygon2D, Shape2D}};
+
+	/// A 20 by 20 character with its feet at (x, y).
+	fn character(x: f32, y: f32) -> CharacterBody2D {
+		CharacterBody2D::new(at(x, y - 10.0), boxed(20.0, 20.0))
+	}
+
+	/// Runs `seconds` of frames applying gravity and the horizontal `speed`.
+	fn walk(player: &mut CharacterBody2D, world: &PhysicsWorld2D, speed: f32, seconds: f32) {
+		for _ in 0..(seconds / STEP).round() as u32 {
+			player.velocity.x = speed;
+			player.velocity += world.gravity * STEP;
+			player.move_and_slide(world, STEP);
+		}
+	}
+
+	fn feet(player: &CharacterBody2D) -> f32 {
+		player.transform.position.y + 10.0
+	}
+
+	#[test]
+	fn character_lands() {
+		let (world, ground) = with_ground();
+		let mut player = character(0.0, -100.0);
+		walk(&mut player, &world, 0.0, 1.0);
+		assert!(player.is_on_floor() && !player.is_on_wall() && !player.is_on_ceiling());
+		assert_eq!(player.floor_body(), Some(ground));
+		assert!((feet(&player) + player.safe_margin).abs() < 0.05, "{}", feet(&player));
+		assert!(player.velocity.y.abs() < 1e-3);
+		let normal = player.floor_normal().unwrap();
+		assert!((normal.y + 1.0).abs() < 1e-4);
+		assert_eq!(player.collisions()[0].surface, Surface::Floor);
+	}
+
+	#[test]
+	fn character_wall() {
+		let (mut world, _) = with_ground();
+		let wall = solid(&mut world, at(100.0, -50.0), boxed(20.0, 100.0));
+		let mut player = character(0.0, 0.0);
+		walk(&mut player, &world, 200.0, 1.0);
+		assert!(player.is_on_floor() && player.is_on_wall());
+		assert!((player.transform.position.x - 80.0).abs() < 0.1, "{:?}", player.transform.position);
+		assert!((player.wall_normal().unwrap().x + 1.0).abs() < 1e-4);
+		assert!(player.collisions().iter().any(|collision| collision.body == wall && collision.surface == Surface::Wall));
+		// still walking along the floor while pressed against the wall
+		assert!(feet(&player).abs() < 0.2);
+	}
+
+	#[test]
+	fn character_ceiling() {
+		let (mut world, _) = with_ground();
+		solid(&mut world, at(0.0, -60.0), boxed(200.0, 20.0));
+		let mut player = character(0.0, 0.0);
+		player.velocity.y = -600.0;
+		let mut bumped = false;
+		for _ in 0..30 {
+			player.velocity += world.gravity * STEP;
+			player.move_and_slide(&world, STEP);
+			if player.is_on_ceiling() {
+				bumped = true;
+				assert!(player.velocity.y >= 0.0);
+				assert!(player.transform.position.y - 10.0 > -50.5);
+			}
+		}
+		assert!(bumped);
+		assert!(player.is_on_floor());
+	}
+
+	#[test]
+	fn character_slopes() {
+		let (mut world, _) = with_ground();
+		// a 30 degree ramp rising to the right from x = 0 to x = 200
+		let rise = 200.0 * (30.0f32).to_radians().tan();
+		let ramp = Polygon2D::new(vec![Position2D::from(0.0, 0.0), Position2D::from(200.0, -rise), Position2D::from(200.0, 0.0)]);
+		solid(&mut world, Transform2D::new(), Collision2D::new(Shape2D::Polygon(ramp)));
+
+		let mut player = character(-30.0, 0.0);
+		walk(&mut player, &world, 100.0, 1.5);
+		assert!(player.is_on_floor());
+		let x = player.transform.position.x;
+		assert!(x > 80.0 && x < 130.0, "{}", x);
+		assert!(feet(&player) < -30.0, "{}", feet(&player));
+
+		// standing still on the ramp does not slide down it
+		let before = player.transform.position;
+		walk(&mut player, &world, 0.0, 1.0);
+		assert!((player.transform.position.x - before.x).abs() < 0.5);
+		assert!(player.is_on_floor());
+
+		let mut sliding = player.clone().stop_on_slope(false);
+		walk(&mut sliding, &world, 0.0, 1.0);
+		assert!(sliding.transform.position.x < before.x - 5.0);
+	}
+
+	#[test]
+	fn character_steep_slope() {
+		let (mut world, _) = with_ground();
+		let rise = 100.0 * (60.0f32).to_radians().tan();
+		let cliff = Polygon2D::new(vec![Position2D::from(0.0, 0.0), Position2D::from(100.0, -rise), Position2D::from(100.0, 0.0)]);
+		solid(&mut world, Transform2D::new(), Collision2D::new(Shape2D::Polygon(cliff)));
+
+		let mut player = character(-30.0, 0.0);
+		walk(&mut player, &world, 100.0, 1.0);
+		assert!(player.is_on_wall());
+		assert!(feet(&player) > -30.0, "{}", feet(&player));
+	}
+
+	#[test]
+	fn character_snap() {
+		let (mut world, _) = with_ground();
+		// a 30 degree ramp going down to the right onto the ground
+		let drop = 200.0 * (30.0f32).to_radians().tan();
+		let ramp = Polygon2D::new(vec![Position2D::from(-50.0, -drop), Position2D::from(0.0, -drop), Position2D::from(200.0, 0.0), Position2D::from(-50.0, 0.0)]);
+		solid(&mut world, Transform2D::new(), Collision2D::new(Shape2D::Polygon(ramp)));
+
+		let run_down = |snap: f32| {
+			let mut player = character(-20.0, -drop).snap_length(snap);
+			walk(&mut player, &world, 0.0, 0.2);
+			let mut airborne = false;
+			for _ in 0..60 {
+				walk(&mut player, &world, 300.0, STEP);
+				airborne |= !player.is_on_floor();
+			}
+			airborne
+		};
+		assert!(!run_down(8.0));
+		assert!(run_down(0.0));
+	}
+
+	#[test]
+	fn character_one_way() {
+		let (mut world, _) = with_ground();
+		let mut platform = boxed(200.0, 10.0);
+		platform.one_way = true;
+		let platform = solid(&mut world, at(0.0, -55.0), platform);
+
+		let mut player = character(0.0, 0.0);
+		player.velocity.y = -700.0;
+		let mut highest = 0.0f32;
+		for _ in 0..120 {
+			player.velocity += world.gravity * STEP;
+			player.move_and_slide(&world, STEP);
+			assert!(!player.is_on_ceiling());
+			highest = highest.min(feet(&player));
+		}
+		assert!(highest < -60.0);
+		assert_eq!(player.floor_body(), Some(platform));
+		assert!((feet(&player) + 60.0).abs() < 0.2, "{}", feet(&player));
+
+		// walls of one-way colliders do not block either
+		let mut side = character(-150.0, -50.0);
+		walk(&mut side, &world, 300.0, 0.1);
+		assert!(!side.is_on_wall());
+		assert!(side.transform.position.x > -150.0 + 25.0);
+	}
+
+	#[test]
+	fn character_moving_platform() {
+		let mut world = PhysicsWorld2D::new();
+		let platform = world.insert(RigidBody2D::new(at(0.0, 5.0)).body_type(BodyType::Kinematic).velocity(Vector2D::from(60.0, -30.0)), boxed(100.0, 10.0));
+		let mut player = character(0.0, 0.0);
+		for _ in 0..60 {
+			world.step(STEP);
+			walk(&mut player, &world, 0.0, STEP);
+		}
+		let top = world.body(platform).unwrap().transform.position.y - 5.0;
+		assert_eq!(player.floor_body(), Some(platform));
+		assert!((player.transform.position.x - 60.0).abs() < 2.0, "{:?}", player.transform.position);
+		assert!((feet(&player) - top).abs() < 1.0, "{} {}", feet(&player), top);
+	}
+
+	#[test]
+	fn character_layers() {
+		let (mut world, _) = with_ground();
+		solid(&mut world, at(50.0, -50.0), boxed(20.0, 100.0).layer(0b1000).mask(0b1000));
+		let mut player = character(0.0, 0.0);
+		walk(&mut player, &world, 200.0, 0.5);
+		assert!(!player.is_on_wall());
+		assert!(player.transform.position.x > 90.0);
+	}
+
+	#[test]
+	fn character_not_in_world() {
+		let (mut world, _) = with_ground();
+		let bodies = world.len();
+		let mut player = character(0.0, 0.0);
+		walk(&mut player, &world, 100.0, 0.5);
+		world.step(STEP);
+		assert_eq!(world.len(), bodies);
+		assert!(world.query_point(player.transform.position, u32::MAX).is_empty());
+	}
+
+	#[test]
+	fn character_infinite_velocity() {
+		let (world, _) = with_ground();
+		let mut player = character(0.0, 0.0);
+		player.velocity = Vector2D::from(f32::INFINITY, 0.0);
+		player.move_and_slide(&world, STEP);
+		assert!(!player.is_on_wall());
+	}
+}
@@ -0,0 +1,601 @@
+use std::f32::consts::{PI, TAU};
+
+use super::{BodyId, RigidBody2D, body::cross};
+use crate::{Position2D, Rotation2D, Math::Vector2D};
+use crate::Collision::{Collision2D, narrow::{perpendicular, unit, vector}};
+
+/// Fraction of the drift of a joint fed back into the velocities every step.
+const BIAS: f32 = 0.2;
+/// Fraction of the drift of a joint moved back after the bodies moved.
+const CORRECTION: f32 = 0.8;
+/// Most rows a joint solves: two for the anchors, one for the angle, two
+/// limits and a motor. Each has its own slot in the impulse cache.
+pub(crate) const ROWS: usize = 6;
+const X: usize = 0;
+const Y: usize = 1;
+const ANGLE: usize = 2;
+const LOWER: usize = 3;
+const UPPER: usize = 4;
+const MOTOR: usize = 5;
+
+/// Handle to a joint of a `PhysicsWorld2D`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct JointId {
+	pub(crate) index: u32,
+	pub(crate) generation: u32,
+}
+
+/// What a joint allows the two bodies to do.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum JointKind2D {
+	/// Keeps the anchors between `min` and `max` apart: a rod when they are
+	/// equal, a rope when `min` is 0.
+	Distance { min: f32, max: f32 },
+	/// Pins the anchors together and lets the bodies turn around them, e.g.
+	/// doors and wheels. Takes limits and a motor.
+	Revolute,
+	/// Lets the anchor of `b` slide along `axis` through the anchor of `a`
+	/// without turning, e.g. elevators and pistons. `axis` turns with `a`.
+	/// Takes limits and a motor.
+	Prismatic { axis: Vector2D },
+	/// Holds the bodies together as if they were one.
+	Weld,
+	/// Pulls the anchors towards `length` apart with a force of `stiffness`
+	/// per unit of stretch, slowed down by `damping`.
+	Spring { length: f32, stiffness: f32, damping: f32 },
+}
+
+/// A constraint between two bodies of a `PhysicsWorld2D`, solved together
+/// with the contacts every step.
+///
+/// ```rust
+/// use Engine::{Transform2D, Position2D, Rotation2D, Scale2D};
+/// use Engine::Physics::{PhysicsWorld2D, RigidBody2D, BodyType, Joint2D, JointKind2D};
+/// use Engine::Collision::{Collision2D, Shape2D, Rectangle};
+///
+/// let mut world = PhysicsWorld2D::new();
+/// let at = |x| Transform2D::from(Position2D::from(x, 0.0), Rotation2D::new(), Scale2D::new());
+/// let frame = world.insert(RigidBody2D::new(at(0.0)).body_type(BodyType::Static), Collision2D::new(Shape2D::Rectangle(Rectangle::new(10.0, 10.0))));
+/// let door = world.insert(RigidBody2D::new(at(55.0)), Collision2D::new(Shape2D::Rectangle(Rectangle::new(100.0, 10.0))));
+///
+/// // hinged at the right edge of the frame, swinging down at most a quarter turn
+/// let hinge = Joint2D::new(JointKind2D::Revolute, frame, door).anchors(Position2D::from(5, 0), Position2D::from(-50, 0));
+/// world.add_joint(hinge.limits(0.0, std::f32::consts::FRAC_PI_2)).unwrap();
+/// for _ in 0..120 {
+///     world.step(1.0 / 60.0);
+/// }
+/// let door = world.body(door).unwrap().transform;
+/// assert!((door.rotation.rad() - std::f32::consts::FRAC_PI_2).abs() < 0.05);
+/// assert!((door.position.x - 5.0).abs() < 1.0 && (door.position.y - 50.0).abs() < 1.0);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Joint2D {
+	pub a: BodyId,
+	pub b: BodyId,
+	pub kind: JointKind2D,
+	/// Where the joint holds each body, in the local space of the body like
+	/// collider offsets.
+	pub anchor_a: Position2D,
+	pub anchor_b: Position2D,
+	/// Lowest and highest angle of `b` relative to `a` in radians, within
+	/// -π and π, for revolute joints. Lowest and highest translation along
+	/// the axis for prismatic joints.
+	pub limits: Option<(f32, f32)>,
+	/// Speed to drive a revolute joint at in radians per second, or a
+	/// prismatic joint at in units per second, with the largest torque or
+	/// force the motor may use.
+	pub motor: Option<(f32, f32)>,
+	/// Whether the two bodies still collide with each other.
+	pub collide_connected: bool,
+	/// Angle of `b` relative to `a` when the joint was added.
+	pub(crate) reference: f32,
+	/// Accumulated impulse of every row of the last step, to start the
+	/// solver from.
+	pub(crate) impulses: [f32; ROWS],
+}
+
+impl Joint2D {
+	pub const fn new(kind: JointKind2D, a: BodyId, b: BodyId) -> Joint2D {
+		Joint2D {
+			a,
+			b,
+			kind,
+			anchor_a: Position2D::new(),
+			anchor_b: Position2D::new(),
+			limits: None,
+			motor: None,
+			collide_connected: false,
+			reference: 0.0,
+			impulses: [0.0; ROWS],
+		}
+	}
+
+	pub const fn anchors(mut self, anchor_a: Position2D, anchor_b: Position2D) -> Joint2D {
+		self.anchor_a = anchor_a;
+		self.anchor_b = anchor_b;
+		self
+	}
+
+	pub const fn limits(mut self, lower: f32, upper: f32) -> Joint2D {
+		self.limits = Some((lower, upper));
+		self
+	}
+
+	pub const fn motor(mut self, speed: f32, max: f32) -> Joint2D {
+		self.motor = Some((speed, max));
+		self
+	}
+
+	pub const fn collide_connected(mut self, collide_connected: bool) -> Joint2D {
+		self.collide_connected = collide_connected;
+		self
+	}
+}
+
+/// Linear part for `b`, negated for `a`, and angular parts for `a` and `b`
+/// of what a row constrains.
+type Jacobian = (Vector2D, f32, f32);
+
+/// One constraint on the relative motion of the bodies: the velocity along
+/// `jacobian` is driven to `-bias`, keeping the accumulated impulse within
+/// `lower` and `upper`.
+struct Row {
+	slot: usize,
+	jacobian: Jacobian,
+	mass: f32,
+	bias: f32,
+	/// How far the joint has drifted along `jacobian`, moved back after the
+	/// bodies moved.
+	error: f32,
+	/// Lets a soft row give way in proportion to its impulse, like a spring.
+	softness: f32,
+	lower: f32,
+	upper: f32,
+	impulse: f32,
+}
+
+impl Row {
+	fn new(slot: usize, jacobian: Jacobian) -> Row {
+		Row { slot, jacobian, mass: 0.0, bias: 0.0, error: 0.0, softness: 0.0, lower: f32::MIN, upper: f32::MAX, impulse: 0.0 }
+	}
+
+	/// Keeps `error`, the position along `jacobian`, at 0.
+	fn fixed(slot: usize, jacobian: Jacobian, error: f32, delta: f32) -> Row {
+		Row { bias: error * BIAS / delta, error, ..Row::new(slot, jacobian) }
+	}
+
+	/// Keeps `error`, the position along `jacobian`, from going below 0.
+	/// While it is above, the bodies may close the gap within one step.
+	fn limit(slot: usize, jacobian: Jacobian, error: f32, delta: f32) -> Row {
+		Row { bias: error.max(0.0) / delta, error: error.min(0.0), lower: 0.0, ..Row::new(slot, jacobian) }
+	}
+
+	/// Drives the velocity along `jacobian` to `speed` with impulses up to `max`.
+	fn motor(slot: usize, jacobian: Jacobian, speed: f32, max: f32) -> Row {
+		Row { bias: -speed, lower: -max, upper: max, ..Row::new(slot, jacobian) }
+	}
+
+	/// Pulls `stretch`, the position along `jacobian`, towards 0 as a soft
+	/// constraint, which stays stable however stiff the spring is.
+	fn spring(slot: usize, jacobian: Jacobian, stretch: f32, stiffness: f32, damping: f32, delta: f32) -> Option<Row> {
+		let softness = delta * (damping + delta * stiffness);
+		if softness <= 0.0 {
+			return None;
+		}
+		let softness = 1.0 / softness;
+		Some(Row { bias: stretch * delta * stiffness * softness, softness, ..Row::new(slot, jacobian) })
+	}
+}
+
+/// A joint between the bodies at two indices of a step, prepared for the
+/// solver.
+pub(crate) struct JointConstraint {
+	joint: Joint2D,
+	a: usize,
+	b: usize,
+	/// Inverse mass and inertia of both bodies. Sleeping bodies do not move.
+	inverse: [(f32, f32); 2],
+	rows: Vec<Row>,
+}
+
+impl JointConstraint {
+	/// Turns `joint` into rows for the current positions of its bodies,
+	/// starting from the impulses of the last step.
+	pub(crate) fn new(joint: &Joint2D, a: usize, b: usize, entries: &[(RigidBody2D, Collision2D)], delta: f32) -> JointConstraint {
+		let (body_a, body_b) = (&entries[a].0, &entries[b].0);
+		let inverse = |body: &RigidBody2D| if body.sleeping { (0.0, 0.0) } else { (body.inverse_mass(), body.inverse_inertia()) };
+		let mut constraint = JointConstraint { joint: *joint, a, b, inverse: [inverse(body_a), inverse(body_b)], rows: Vec::new() };
+
+		let ra = vector(body_a.transform.apply(joint.anchor_a)) - body_a.origin();
+		let rb = vector(body_b.transform.apply(joint.anchor_b)) - body_b.origin();
+		let separation = body_b.origin() + rb - body_a.origin() - ra;
+		let angle = wrap(body_b.transform.rotation.rad() - body_a.transform.rotation.rad() - joint.reference);
+		let along = |axis: Vector2D| (axis, -cross(ra, axis), cross(rb, axis));
+		let turn = (Vector2D::new(), -1.0, 1.0);
+
+		match joint.kind {
+			JointKind2D::Distance { min, max } => {
+				if let Some(normal) = unit(separation) {
+					let length = !separation;
+					if min > 0.0 {
+						constraint.push(Row::limit(LOWER, along(normal), length - min, delta));
+					}
+					constraint.push(Row::limit(UPPER, flip(along(normal)), max - length, delta));
+				}
+			},
+			JointKind2D::Revolute => {
+				constraint.pin(along, separation, delta);
+				// limits after the motor, so they win against it
+				if let Some((speed, max)) = joint.motor {
+					constraint.push(Row::motor(MOTOR, turn, speed, max * delta));
+				}
+				if let Some((lower, upper)) = joint.limits {
+					constraint.push(Row::limit(LOWER, turn, angle - lower, delta));
+					constraint.push(Row::limit(UPPER, flip(turn), upper - angle, delta));
+				}
+			},
+			JointKind2D::Prismatic { axis } => {
+				let axis = unit(rotate(axis, body_a.transform.rotation.rad())).unwrap_or(Vector2D::from(1.0, 0.0));
+				// the axis turns with `a`, so the lever of `a` reaches the anchor of `b`
+				let slide = |axis: Vector2D| (axis, -cross(ra + separation, axis), cross(rb, axis));
+				let normal = perpendicular(axis);
+				constraint.push(Row::fixed(X, slide(normal), separation * normal, delta));
+				constraint.push(Row::fixed(ANGLE, turn, angle, delta));
+				let translation = separation * axis;
+				if let Some((speed, max)) = joint.motor {
+					constraint.push(Row::motor(MOTOR, slide(axis), speed, max * delta));
+				}
+				if let Some((lower, upper)) = joint.limits {
+					constraint.push(Row::limit(LOWER, slide(axis), translation - lower, delta));
+					constraint.push(Row::limit(UPPER, flip(slide(axis)), upper - translation, delta));
+				}
+			},
+			JointKind2D::Weld => {
+				constraint.pin(along, separation, delta);
+				constraint.push(Row::fixed(ANGLE, turn, angle, delta));
+			},
+			JointKind2D::Spring { length, stiffness, damping } => {
+				if let Some(normal) = unit(separation) {
+					if let Some(row) = Row::spring(LOWER, along(normal), !separation - length, stiffness, damping, delta) {
+						constraint.push(row);
+					}
+				}
+			},
+		}
+
+		for row in constraint.rows.iter_mut() {
+			row.impulse = joint.impulses[row.slot].clamp(row.lower, row.upper);
+		}
+		constraint
+	}
+
+	/// Applies the impulses the rows start from.
+	pub(crate) fn warm_start(&self, entries: &mut [(RigidBody2D, Collision2D)]) {
+		for row in &self.rows {
+			self.apply(entries, row.jacobian, row.impulse);
+		}
+	}
+
+	/// One solver pass over every row.
+	pub(crate) fn solve(&mut self, entries: &mut [(RigidBody2D, Collision2D)]) {
+		let mut i = 0;
+		while i < self.rows.len() {
+			if self.is_pin(i) {
+				// both axes at once, or light bodies on long anchors barely converge
+				let velocity = [self.velocity(i, entries) + self.rows[i].bias, self.velocity(i + 1, entries) + self.rows[i + 1].bias];
+				let impulse = self.block(i, velocity);
+				for (k, impulse) in impulse.iter().copied().enumerate() {
+					self.rows[i + k].impulse += impulse;
+					self.apply(entries, self.rows[i + k].jacobian, impulse);
+				}
+				i += 2;
+				continue;
+			}
+			let row = &self.rows[i];
+			let impulse = -row.mass * (self.velocity(i, entries) + row.bias + row.softness * row.impulse);
+			let total = (row.impulse + impulse).clamp(row.lower, row.upper);
+			let impulse = total - row.impulse;
+			let jacobian = row.jacobian;
+			self.rows[i].impulse = total;
+			self.apply(entries, jacobian, impulse);
+			i += 1;
+		}
+	}
+
+	/// Moves the bodies back where the joint drifted after they moved, like
+	/// contacts pushing overlapping bodies apart. Fast turning bodies drift
+	/// more than the velocities alone can hold.
+	pub(crate) fn correct(&self, entries: &mut [(RigidBody2D, Collision2D)], delta: f32) {
+		let drifted = JointConstraint::new(&self.joint, self.a, self.b, entries, delta);
+		let mut i = 0;
+		while i < drifted.rows.len() {
+			if drifted.is_pin(i) {
+				let impulse = drifted.block(i, [drifted.rows[i].error, drifted.rows[i + 1].error]);
+				drifted.displace(entries, i, impulse[0] * CORRECTION);
+				drifted.displace(entries, i + 1, impulse[1] * CORRECTION);
+				i += 2;
+				continue;
+			}
+			let row = &drifted.rows[i];
+			if row.error != 0.0 {
+				drifted.displace(entries, i, -row.mass * row.error * CORRECTION);
+			}
+			i += 1;
+		}
+	}
+
+	/// Accumulated impulse of every row slot, 0 for rows not in use.
+	pub(crate) fn impulses(&self) -> [f32; ROWS] {
+		let mut impulses = [0.0; ROWS];
+		for row in &self.rows {
+			impulses[row.slot] = row.impulse;
+		}
+		impulses
+	}
+
+	/// Adds rows keeping the anchors together.
+	fn pin(&mut self, along: impl Fn(Vector2D) -> Jacobian, separation: Vector2D, delta: f32) {
+		for (slot, axis) in [(X, Vector2D::from(1.0, 0.0)), (Y, Vector2D::from(0.0, 1.0))] {
+			self.push(Row::fixed(slot, along(axis), separation * axis, delta));
+		}
+	}
+
+	fn push(&mut self, mut row: Row) {
+		let [(ma, ia), (mb, ib)] = self.inverse;
+		let (linear, angular_a, angular_b) = row.jacobian;
+		let k = (ma + mb) * (linear * linear) + ia * angular_a * angular_a + ib * angular_b * angular_b + row.softness;
+		row.mass = if k > 0.0 { 1.0 / k } else { 0.0 };
+		self.rows.push(row);
+	}
+
+	/// Whether rows `i` and `i + 1` hold anchors together.
+	fn is_pin(&self, i: usize) -> bool {
+		self.rows[i].slot == X && self.rows.get(i + 1).is_some_and(|row| row.slot == Y)
+	}
+
+	/// Impulses along rows `i` and `i + 1` that bring `value` along them to 0
+	/// together.
+	fn block(&self, i: usize, value: [f32; 2]) -> [f32; 2] {
+		let [(ma, ia), (mb, ib)] = self.inverse;
+		let ((l1, a1, b1), (l2, a2, b2)) = (self.rows[i].jacobian, self.rows[i + 1].jacobian);
+		let k11 = (ma + mb) * (l1 * l1) + ia * a1 * a1 + ib * b1 * b1;
+		let k12 = (ma + mb) * (l1 * l2) + ia * a1 * a2 + ib * b1 * b2;
+		let k22 = (ma + mb) * (l2 * l2) + ia * a2 * a2 + ib * b2 * b2;
+		let determinant = k11 * k22 - k12 * k12;
+		if determinant.abs() <= f32::EPSILON {
+			return [0.0, 0.0];
+		}
+		[
+			-(k22 * value[0] - k12 * value[1]) / determinant,
+			-(k11 * value[1] - k12 * value[0]) / determinant,
+		]
+	}
+
+	/// Velocity of the bodies along row `i`.
+	fn velocity(&self, i: usize, entries: &[(RigidBody2D, Collision2D)]) -> f32 {
+		let (body_a, body_b) = (&entries[self.a].0, &entries[self.b].0);
+		let (linear, angular_a, angular_b) = self.rows[i].jacobian;
+		linear * (body_b.velocity - body_a.velocity) + angular_a * body_a.angular_velocity + angular_b * body_b.angular_velocity
+	}
+
+	/// Like `apply` for positions, moving the bodies along row `i`.
+	fn displace(&self, entries: &mut [(RigidBody2D, Collision2D)], i: usize, impulse: f32) {
+		let [(ma, ia), (mb, ib)] = self.inverse;
+		let (linear, angular_a, angular_b) = self.rows[i].jacobian;
+		for (body, linear, angular) in [(self.a, -linear * ma, angular_a * ia), (self.b, linear * mb, angular_b * ib)] {
+			let transform = &mut entries[body].0.transform;
+			transform.position.x += linear.x * impulse;
+			transform.position.y += linear.y * impulse;
+			if angular != 0.0 {
+				transform.rotation = Rotation2D::from_rad(transform.rotation.rad() + angular * impulse);
+			}
+		}
+	}
+
+	fn apply(&self, entries: &mut [(RigidBody2D, Collision2D)], (linear, angular_a, angular_b): Jacobian, impulse: f32) {
+		let [(ma, ia), (mb, ib)] = self.inverse;
+		let body_a = &mut entries[self.a].0;
+		body_a.velocity -= linear * (impulse * ma);
+		body_a.angular_velocity += angular_a * impulse * ia;
+		let body_b = &mut entries[self.b].0;
+		body_b.velocity += linear * (impulse * mb);
+		body_b.angular_velocity += angular_b * impulse * ib;
+	}
+}
+
+fn flip((linear, angular_a, angular_b): Jacobian) -> Jacobian {
+	(-linear, -angular_a, -angular_b)
+}
+
+/// `angle` in radians brought within -π and π.
+fn wrap(angle: f32) -> f32 {
+	(angle + PI).rem_euclid(TAU) - PI
+}
+
+fn rotate(v: Vector2D, angle: f32) -> Vector2D {
+	let (sin, cos) = angle.sin_cos();
+	Vector2D::from(v.x * cos - v.y * sin, v.x * sin + v.y * cos)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Scale2D, Transform2D, test_util::{STEP, at, ball, run}, Physics::{BodyType, PhysicsWorld2D}, Collision::{Rectangle, Shape2D}};
+
+	fn plank() -> Collision2D {
+		Collision2D::new(Shape2D::Rectangle(Rectangle::new(100.0, 10.0)))
+	}
+
+	/// A world with a static body at the origin to hang things from.
+	fn with_pivot() -> (PhysicsWorld2D, BodyId) {
+		let mut world = PhysicsWorld2D::new();
+		let pivot = world.insert(RigidBody2D::new(Transform2D::new()).body_type(BodyType::Static), ball(5.0));
+		(world, pivot)
+	}
+
+	fn position(world: &PhysicsWorld2D, id: BodyId) -> Vector2D {
+		world.body(id).unwrap().origin()
+	}
+
+	#[test]
+	fn joint_distance() {
+		let (mut world, pivot) = with_pivot();
+		let bob = world.insert(RigidBody2D::new(at(100.0, 0.0)), ball(5.0));
+		world.add_joint(Joint2D::new(JointKind2D::Distance { min: 100.0, max: 100.0 }, pivot, bob)).unwrap();
+		let mut lowest = f32::MIN;
+		for _ in 0..120 {
+			world.step(STEP);
+			let length = !position(&world, bob);
+			assert!((length - 100.0).abs() < 2.0, "{}", length);
+			lowest = lowest.max(position(&world, bob).y);
+		}
+		// swung through the bottom
+		assert!(lowest > 98.0, "{}", lowest);
+	}
+
+	#[test]
+	fn joint_rope() {
+		let (mut world, pivot) = with_pivot();
+		let slack = world.insert(RigidBody2D::new(at(0.0, 50.0)), ball(5.0));
+		world.add_joint(Joint2D::new(JointKind2D::Distance { min: 0.0, max: 100.0 }, pivot, slack).anchors(Position2D::new(), Position2D::from(0, -10))).unwrap();
+		world.step(STEP);
+		// falls freely while slack
+		assert!(world.body(slack).unwrap().velocity.y > 10.0);
+		run(&mut world, 3.0);
+		let hanging = position(&world, slack);
+		assert!(hanging.x.abs() < 1e-3 && (hanging.y - 110.0).abs() < 1.0, "{:?}", hanging);
+
+		// a rope does not push
+		let body = world.body_mut(slack).unwrap();
+		body.transform.position = Position2D::from(0, 30);
+		body.wake();
+		world.step(STEP);
+		assert!(world.body(slack).unwrap().velocity.y > 0.0 && position(&world, slack).y < 31.0);
+	}
+
+	#[test]
+	fn joint_chain() {
+		let (mut world, pivot) = with_pivot();
+		let mut previous = pivot;
+		let links: Vec<BodyId> = (1..=10).map(|i| {
+			let link = world.insert(RigidBody2D::new(at(10.0 * i as f32, 0.0)).drag(1.0, 1.0), Collision2D::new(Shape2D::Rectangle(Rectangle::new(10.0, 3.0))));
+			world.add_joint(Joint2D::new(JointKind2D::Revolute, previous, link).anchors(Position2D::from(5, 0), Position2D::from(-5, 0))).unwrap();
+			previous = link;
+			link
+		}).collect();
+		for _ in 0..600 {
+			world.step(STEP);
+			for pair in links.windows(2) {
+				let gap = !(position(&world, pair[1]) - position(&world, pair[0]));
+				assert!(gap < 11.0, "{}", gap);
+			}
+		}
+		// settles hanging straight down from the edge of the pivot
+		let end = position(&world, links[9]);
+		assert!((end.x - 5.0).abs() < 2.0 && (end.y - 95.0).abs() < 1.0, "{:?}", end);
+	}
+
+	#[test]
+	fn joint_revolute() {
+		let (mut world, frame) = with_pivot();
+		world.gravity = Vector2D::new();
+		let door = world.insert(RigidBody2D::new(at(50.0, 0.0)), plank());
+		let hinge = Joint2D::new(JointKind2D::Revolute, frame, door).anchors(Position2D::new(), Position2D::from(-50, 0));
+		let hinge = world.add_joint(hinge.limits(-1.0, 1.0).motor(2.0, 1e8)).unwrap();
+		run(&mut world, 0.25);
+		let body = world.body(door).unwrap();
+		assert!((body.angular_velocity - 2.0).abs() < 0.1, "{}", body.angular_velocity);
+
+		run(&mut world, 1.0);
+		let body = world.body(door).unwrap();
+		assert!((body.transform.rotation.rad() - 1.0).abs() < 0.05, "{}", body.transform.rotation.rad());
+		// the hinge end stays on the frame
+		let end = vector(body.transform.apply(Position2D::from(-50, 0)));
+		assert!(!end < 1.0, "{:?}", end);
+
+		world.joint_mut(hinge).unwrap().motor = Some((-2.0, 1e8));
+		run(&mut world, 2.0);
+		assert!((world.body(door).unwrap().transform.rotation.rad() + 1.0).abs() < 0.05);
+	}
+
+	#[test]
+	fn joint_prismatic() {
+		let (mut world, rail) = with_pivot();
+		let cart = world.insert(RigidBody2D::new(Transform2D::new()), ball(5.0));
+		let slider = Joint2D::new(JointKind2D::Prismatic { axis: Vector2D::from(1.0, 0.0) }, rail, cart);
+		world.add_joint(slider.limits(-10.0, 80.0).motor(100.0, 1e6)).unwrap();
+		run(&mut world, 2.0);
+		// held on the axis against gravity, stopped by the upper limit
+		let body = world.body(cart).unwrap();
+		assert!((body.transform.position.x - 80.0).abs() < 1.0, "{:?}", body.transform.position);
+		assert!(body.transform.position.y.abs() < 1.0 && body.transform.rotation.rad().abs() < 1e-3);
+	}
+
+	#[test]
+	fn joint_weld() {
+		let mut world = PhysicsWorld2D::new();
+		let ground = world.insert(RigidBody2D::new(at(0.0, 100.0)).body_type(BodyType::Static), Collision2D::new(Shape2D::Rectangle(Rectangle::new(400.0, 20.0))));
+		let left = world.insert(RigidBody2D::new(at(0.0, 0.0)), plank());
+		let right = world.insert(RigidBody2D::new(Transform2D::from(Position2D::from(90, 0), Rotation2D::from_deg(90), Scale2D::new())), plank());
+		world.add_joint(Joint2D::new(JointKind2D::Weld, left, right).anchors(Position2D::from(45, 0), Position2D::from(0, 45))).unwrap();
+		run(&mut world, 3.0);
+
+		// an L shape resting on its foot, the overlapping corner not pushed apart
+		let (left, right) = (world.body(left).unwrap().transform, world.body(right).unwrap().transform);
+		assert!((right.rotation.rad() - left.rotation.rad() - std::f32::consts::FRAC_PI_2).abs() < 0.02);
+		assert!((!(vector(left.position) - vector(right.position)) - !Vector2D::from(90.0, 0.0)).abs() < 1.0);
+		assert!(world.contacts().iter().all(|&(a, b, _)| a == ground || b == ground));
+	}
+
+	#[test]
+	fn joint_spring() {
+		let (mut world, anchor) = with_pivot();
+		world.gravity = Vector2D::new();
+		let weight = world.insert(RigidBody2D::new(at(150.0, 0.0)), ball(5.0));
+		world.add_joint(Joint2D::new(JointKind2D::Spring { length: 100.0, stiffness: 50.0, damping: 1.0 }, anchor, weight)).unwrap();
+		let mut shortest = f32::MAX;
+		for _ in 0..120 {
+			world.step(STEP);
+			shortest = shortest.min(position(&world, weight).x);
+		}
+		// overshoots the rest length, then settles on it
+		assert!(shortest < 90.0, "{}", shortest);
+		run(&mut world, 10.0);
+		assert!((position(&world, weight).x - 100.0).abs() < 1.0);
+	}
+
+	#[test]
+	fn joint_wake() {
+		let mut world = PhysicsWorld2D::new();
+		world.gravity = Vector2D::new();
+		let a = world.insert(RigidBody2D::new(Transform2D::new()), ball(5.0));
+		let b = world.insert(RigidBody2D::new(at(50.0, 0.0)), ball(5.0));
+		world.add_joint(Joint2D::new(JointKind2D::Distance { min: 50.0, max: 50.0 }, a, b)).unwrap();
+		run(&mut world, 1.0);
+		assert!(world.body(a).unwrap().is_sleeping() && world.body(b).unwrap().is_sleeping());
+
+		world.body_mut(a).unwrap().apply_impulse(Vector2D::from(-100.0, 0.0));
+		world.step(STEP);
+		assert!(!world.body(b).unwrap().is_sleeping());
+		assert!(world.body(b).unwrap().velocity.x < -10.0);
+	}
+
+	#[test]
+	fn joint_remove() {
+		let (mut world, pivot) = with_pivot();
+		let a = world.insert(RigidBody2D::new(at(50.0, 0.0)), ball(5.0));
+		let b = world.insert(RigidBody2D::new(at(100.0, 0.0)), ball(5.0));
+		assert!(world.add_joint(Joint2D::new(JointKind2D::Weld, a, a)).is_none());
+		let first = world.add_joint(Joint2D::new(JointKind2D::Revolute, pivot, a)).unwrap();
+		let second = world.add_joint(Joint2D::new(JointKind2D::Revolute, a, b)).unwrap();
+		assert_eq!(world.joints(), vec![first, second]);
+
+		assert_eq!(world.remove_joint(first).map(|joint| joint.b), Some(a));
+		assert!(world.remove_joint(first).is_none());
+		world.remove(b);
+		assert!(world.joint(second).is_none() && world.joints().is_empty());
+		assert!(world.add_joint(Joint2D::new(JointKind2D::Weld, a, b)).is_none());
+		let third = world.add_joint(Joint2D::new(JointKind2D::Weld, pivot, a)).unwrap();
+		assert_ne!(third, first);
+	}
+}
@@ -0,0 +1,12 @@
+//! Rigid body simulation on top of the `Collision` shapes.
+
+pub(crate) mod body;
+pub(crate) mod character;
+pub(crate) mod joint;
+pub(crate) mod query;
+pub(crate) mod world;
+
+pub use body::*;
+pub use character::*;
+pub use joint::*;
+pub use world::*;
@@ -0,0 +1,181 @@
+use super::{BodyId, PhysicsWorld2D};
+use crate::{Position2D, Rect2D, Size2D, Transform2D, Math::Vector2D};
+use crate::Collision::{CastHit2D, Contact2D, Shape2D, narrow::{Compound, collide_compound, position, vector}};
+use crate::Collision::query::{raycast_compound, shape_cast_compound};
+
+/// Queries against every collider of a world, for line of sight, hitscan
+/// and finding what is under the mouse.
+///
+/// Every query only sees colliders on the layers of `mask`, `u32::MAX` for
+/// all. Raycasts and shape casts pass through sensors while point and shape
+/// queries include them.
+///
+/// ```rust
+/// use Engine::{Transform2D, Position2D, Rotation2D, Scale2D};
+/// use Engine::Physics::{PhysicsWorld2D, RigidBody2D, BodyType};
+/// use Engine::Collision::{Collision2D, Shape2D, Rectangle};
+///
+/// let mut world = PhysicsWorld2D::new();
+/// let at = |x| Transform2D::from(Position2D::from(x, 0.0), Rotation2D::new(), Scale2D::new());
+/// let wall = world.insert(RigidBody2D::new(at(100.0)).body_type(BodyType::Static), Collision2D::new(Shape2D::Rectangle(Rectangle::new(20.0, 100.0))));
+///
+/// let (hit, details) = world.raycast(Position2D::new(), Position2D::from(200, 0), u32::MAX).unwrap();
+/// assert_eq!(hit, wall);
+/// assert_eq!((details.point.x, details.fraction), (90.0, 0.45));
+/// assert_eq!(world.query_point(Position2D::from(95, 0), u32::MAX), vec![wall]);
+/// ```
+impl PhysicsWorld2D {
+	/// The first solid collider on the segment from `from` to `to`.
+	pub fn raycast(&self, from: Position2D, to: Position2D, mask: u32) -> Option<(BodyId, CastHit2D)> {
+		self.raycast_all(from, to, mask).into_iter().next()
+	}
+
+	/// Every solid collider on the segment from `from` to `to`, nearest first.
+	pub fn raycast_all(&self, from: Position2D, to: Position2D, mask: u32) -> Vec<(BodyId, CastHit2D)> {
+		let (start, direction) = (vector(from), vector(to) - vector(from));
+		let region = swept(Rect2D { position: from, size: Size2D::new() }, direction);
+		let mut hits: Vec<(BodyId, CastHit2D)> = self.candidates(region, mask, true)
+			.filter_map(|(id, shape)| {
+				let (fraction, normal) = raycast_compound(&shape, start, direction)?;
+				Some((id, CastHit2D { point: position(start + direction * fraction), normal, fraction }))
+			})
+			.collect();
+		hits.sort_by(|a, b| a.1.fraction.total_cmp(&b.1.fraction).then(a.0.cmp(&b.0)));
+		hits
+	}
+
+	/// The first solid collider `shape` placed by `transform` touches when
+	/// moved along `motion`.
+	pub fn shape_cast(&self, shape: &Shape2D, transform: Transform2D, motion: Vector2D, mask: u32) -> Option<(BodyId, CastHit2D)> {
+		let moving = Compound::from(shape, transform);
+		self.candidates(swept(moving.bounds(), motion), mask, true)
+			.filter_map(|(id, target)| shape_cast_compound(&moving, motion, &target).map(|hit| (id, hit)))
+			.min_by(|a, b| a.1.fraction.total_cmp(&b.1.fraction).then(a.0.cmp(&b.0)))
+	}
+
+	/// Every collider containing `point`, sorted by id.
+	pub fn query_point(&self, point: Position2D, mask: u32) -> Vec<BodyId> {
+		let region = Rect2D { position: point, size: Size2D::new() };
+		self.candidates(region, mask, false)
+			.filter(|(_, shape)| shape.distance(vector(point)) <= 0.0)
+			.map(|(id, _)| id)
+			.collect()
+	}
+
+	/// Every collider overlapping `shape` placed by `transform`, with the
+	/// contact pointing from the collider into the shape. Sorted by id.
+	pub fn query_shape(&self, shape: &Shape2D, transform: Transform2D, mask: u32) -> Vec<(BodyId, Contact2D)> {
+		let shape = Compound::from(shape, transform);
+		self.candidates(shape.bounds(), mask, false)
+			.filter_map(|(id, other)| collide_compound(&other, &shape).map(|contact| (id, contact)))
+			.collect()
+	}
+
+	/// Colliders on `mask` whose bounds touch `region`, placed in the world.
+	fn candidates(&self, region: Rect2D, mask: u32, solid: bool) -> impl Iterator<Item = (BodyId, Compound)> + '_ {
+		self.near(region, mask).into_iter()
+			.filter_map(move |id| self.entry(id).map(|(body, collider)| (id, body, collider)))
+			.filter(move |(_, _, collider)| collider.layer & mask != 0 && !(solid && collider.sensor))
+			.map(|(id, body, collider)| (id, Compound::from(&collider.shape, body.transform)))
+			.filter(move |(_, shape)| touches(shape.bounds(), region))
+	}
+}
+
+/// `bounds` grown to cover being moved by `motion`.
+fn swept(bounds: Rect2D, motion: Vector2D) -> Rect2D {
+	Rect2D {
+		position: Position2D { x: bounds.left() + motion.x.min(0.0), y: bounds.top() + motion.y.min(0.0) },
+		size: Size2D { x: bounds.size.x + motion.x.abs(), y: bounds.size.y + motion.y.abs() },
+	}
+}
+
+/// Like `Rect2D::intersects`, but boxes that only share an edge touch, so
+/// rays along an axis and points find what they lie on.
+fn touches(a: Rect2D, b: Rect2D) -> bool {
+	a.left() <= b.right() && b.left() <= a.right() && a.top() <= b.bottom() && b.top() <= a.bottom()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{test_util::{STEP, at, ball, boxed, run, solid, with_ground}, Physics::RigidBody2D, Collision::{Circle, Collision2D, Rectangle}};
+
+	#[test]
+	fn world_raycast() {
+		let mut world = PhysicsWorld2D::new();
+		let near = solid(&mut world, at(50.0, 0.0), boxed(10.0, 10.0));
+		let far = solid(&mut world, at(100.0, 0.0), Collision2D::new(Shape2D::Circle(Circle::new(10.0))));
+		let hidden = solid(&mut world, at(75.0, 0.0), boxed(10.0, 10.0).layer(0b10));
+		let zone = solid(&mut world, at(20.0, 0.0), boxed(10.0, 10.0).sensor(true));
+		solid(&mut world, at(50.0, 100.0), boxed(10.0, 10.0));
+
+		let (id, hit) = world.raycast(Position2D::new(), Position2D::from(200, 0), u32::MAX).unwrap();
+		assert_eq!(id, near);
+		assert_eq!((hit.point, hit.fraction), (Position2D::from(45, 0), 0.225));
+		assert_eq!(hit.normal, Vector2D::from(-1.0, 0.0));
+
+		let all: Vec<BodyId> = world.raycast_all(Position2D::new(), Position2D::from(200, 0), u32::MAX).into_iter().map(|(id, _)| id).collect();
+		assert_eq!(all, vec![near, hidden, far]);
+		let masked: Vec<BodyId> = world.raycast_all(Position2D::new(), Position2D::from(200, 0), 0b01).into_iter().map(|(id, _)| id).collect();
+		assert_eq!(masked, vec![near, far]);
+		assert!(!all.contains(&zone));
+
+		// backwards from behind the circle
+		let (id, hit) = world.raycast(Position2D::from(200, 0), Position2D::new(), 0b01).unwrap();
+		assert_eq!((id, hit.point), (far, Position2D::from(110, 0)));
+		assert!(world.raycast(Position2D::from(0, 50), Position2D::from(200, 50), u32::MAX).is_none());
+	}
+
+	#[test]
+	fn world_shape_cast() {
+		let mut world = PhysicsWorld2D::new();
+		let floor = solid(&mut world, at(0.0, 100.0), Collision2D::new(Shape2D::Rectangle(Rectangle::new(400.0, 20.0))));
+		solid(&mut world, at(0.0, 50.0), boxed(20.0, 20.0).sensor(true));
+
+		let ball = Shape2D::Circle(Circle::new(10.0));
+		let (id, hit) = world.shape_cast(&ball, Transform2D::new(), Vector2D::from(0.0, 200.0), u32::MAX).unwrap();
+		assert_eq!(id, floor);
+		assert!((hit.fraction - 0.4).abs() < 1e-4 && (hit.point.y - 90.0).abs() < 1e-3);
+		assert!(world.shape_cast(&ball, Transform2D::new(), Vector2D::from(0.0, 200.0), 0).is_none());
+		assert!(world.shape_cast(&ball, Transform2D::new(), Vector2D::from(0.0, -200.0), u32::MAX).is_none());
+	}
+
+	#[test]
+	fn world_query_point_shape() {
+		let mut world = PhysicsWorld2D::new();
+		let a = solid(&mut world, at(0.0, 0.0), boxed(20.0, 20.0));
+		let b = solid(&mut world, at(15.0, 0.0), Collision2D::new(Shape2D::Circle(Circle::new(10.0))).sensor(true));
+		solid(&mut world, at(100.0, 0.0), boxed(20.0, 20.0));
+
+		assert_eq!(world.query_point(Position2D::from(8, 0), u32::MAX), vec![a, b]);
+		assert_eq!(world.query_point(Position2D::from(-10, 10), u32::MAX), vec![a]);
+		assert!(world.query_point(Position2D::from(50, 0), u32::MAX).is_empty());
+
+		let found = world.query_shape(&Shape2D::Rectangle(Rectangle::new(10.0, 10.0)), at(22.0, 0.0), u32::MAX);
+		assert_eq!(found.len(), 1);
+		assert_eq!(found[0].0, b);
+		assert!(found[0].1.normal.x > 0.9);
+	}
+
+	#[test]
+	fn world_query_moved_bodies() {
+		let (mut world, ground) = with_ground();
+		let a = solid(&mut world, at(0.0, -100.0), boxed(20.0, 20.0));
+		world.body_mut(a).unwrap().transform.position.x = 300.0;
+		assert_eq!(world.query_point(Position2D::from(300, -100), u32::MAX), vec![a]);
+		world.step(STEP);
+		assert_eq!(world.query_point(Position2D::from(300, -100), u32::MAX), vec![a]);
+		assert!(world.query_point(Position2D::from(0, -100), u32::MAX).is_empty());
+
+		// a falling ball is found where the steps moved it
+		let ball = world.insert(RigidBody2D::new(at(-300.0, -200.0)), ball(10.0));
+		run(&mut world, 2.0);
+		assert_eq!(world.query_point(Position2D::from(-300, -5), u32::MAX), vec![ball]);
+		assert_eq!(world.query_point(Position2D::from(-300, 5), u32::MAX), vec![ground]);
+
+		world.remove(a);
+		let b = solid(&mut world, at(0.0, -100.0), boxed(20.0, 20.0));
+		assert!(world.query_point(Position2D::from(300, -100), u32::MAX).is_empty());
+		assert_eq!(world.query_point(Position2D::from(0, -100), u32::MAX), vec![b]);
+	}
+}
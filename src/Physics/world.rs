@@ -0,0 +1,951 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{Joint2D, JointId, RigidBody2D, body::{cross, cross_scalar}, joint::JointConstraint};
+use crate::{Position2D, Rect2D, Rotation2D, Scale2D, Size2D, Transform2D, Math::Vector2D};
+use crate::Collision::{BroadPhase2D, Collision2D, CollisionEvent2D, CollisionTracker2D, Contact2D, ProxyId, narrow::{Compound, collide_compound, perpendicular, vector}};
+
+/// Penetration left alone so resting contacts do not jitter.
+const SLOP: f32 = 0.5;
+/// Fraction of the remaining penetration corrected every step.
+const CORRECTION: f32 = 0.8;
+/// Slower impacts do not bounce, so resting bodies settle.
+const RESTITUTION_THRESHOLD: f32 = 40.0;
+/// Cell size of the broad phase grid, around the size of a typical body.
+const CELL_SIZE: f32 = 64.0;
+/// Boxes that only share an edge do not overlap in the broad phase, so
+/// queries look this much further to find them.
+const QUERY_MARGIN: f32 = 0.01;
+
+/// Handle to a body of a `PhysicsWorld2D`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BodyId {
+	index: u32,
+	generation: u32,
+}
+
+/// A contact between the bodies at two indices of a step.
+type IndexedContact = (usize, usize, Contact2D);
+
+/// A contact point prepared for the solver.
+struct ContactPoint {
+	ra: Vector2D,
+	rb: Vector2D,
+	normal_mass: f32,
+	tangent_mass: f32,
+	bounce: f32,
+	normal_impulse: f32,
+	tangent_impulse: f32,
+}
+
+/// A contact between two bodies prepared for the solver.
+struct Constraint {
+	a: usize,
+	b: usize,
+	normal: Vector2D,
+	depth: f32,
+	friction: f32,
+	points: Vec<ContactPoint>,
+}
+
+/// Rigid bodies with colliders, stepped with an impulse based solver.
+///
+/// Every `step` applies gravity and forces, finds contacts through a
+/// `BroadPhase2D` and the narrow phase, resolves them with restitution and
+/// friction impulses over `iterations` passes, moves the bodies and pushes
+/// apart what still overlaps. Bodies that stay still for `sleep_time`
+/// seconds fall asleep until something touches them.
+///
+/// Colliders only collide when their layers and masks allow it. Sensor
+/// colliders are detected but never resolved. Every step reports a
+/// `CollisionEvent2D` for each pair of bodies that touches or stopped
+/// touching, if either collider is `monitoring`.
+///
+/// `Joint2D`s hold pairs of bodies together and are solved in the same
+/// passes as the contacts. Bodies joined by a joint do not collide with each
+/// other unless the joint allows it.
+///
+/// Entities with `RigidBody2D` and `Collision2D` components are added to
+/// `Context::physics` by the `sync_physics` system, which also writes the
+/// simulated transforms back to them.
+///
+/// ```rust
+/// use Engine::{Transform2D, Position2D, Rotation2D, Scale2D, Physics::{PhysicsWorld2D, RigidBody2D, BodyType}};
+/// use Engine::Collision::{Collision2D, Shape2D, Rectangle, Circle};
+///
+/// let mut world = PhysicsWorld2D::new();
+/// let ground = Transform2D::from(Position2D::from(0, 100), Rotation2D::new(), Scale2D::new());
+/// world.insert(RigidBody2D::new(ground).body_type(BodyType::Static), Collision2D::new(Shape2D::Rectangle(Rectangle::new(400.0, 20.0))));
+/// let ball = world.insert(RigidBody2D::new(Transform2D::new()), Collision2D::new(Shape2D::Circle(Circle::new(10.0))));
+///
+/// for _ in 0..120 {
+///     world.step(1.0 / 60.0);
+/// }
+/// // resting on top of the ground
+/// let y = world.body(ball).unwrap().transform.position.y;
+/// assert!((y - 80.0).abs() < 1.0);
+/// ```
+pub struct PhysicsWorld2D {
+	/// Acceleration applied to every dynamic body, y pointing down.
+	pub gravity: Vector2D,
+	/// Solver passes per step. More passes make stacks more stable.
+	pub iterations: u32,
+	/// Seconds a body must stay below the sleep speeds before it sleeps.
+	pub sleep_time: f32,
+	pub sleep_velocity: f32,
+	pub sleep_angular_velocity: f32,
+	slots: Vec<(u32, Option<(RigidBody2D, Collision2D)>)>,
+	free: Vec<u32>,
+	joints: Vec<(u32, Option<Joint2D>)>,
+	free_joints: Vec<u32>,
+	contacts: Vec<(BodyId, BodyId, Contact2D)>,
+	/// Normal and friction impulses of every contact point of the last step,
+	/// to start the solver from.
+	impulses: HashMap<(BodyId, BodyId), Vec<(f32, f32)>>,
+	tracker: CollisionTracker2D<BodyId>,
+	events: Vec<CollisionEvent2D<BodyId>>,
+	/// End events of pairs whose body was removed, reported by the next step.
+	removed: Vec<CollisionEvent2D<BodyId>>,
+	/// Bounds of every collider, moved along with the bodies.
+	broad: BroadPhase2D,
+	/// Proxy of the body in each slot.
+	proxies: Vec<ProxyId>,
+	/// Body of each proxy, by proxy index.
+	owners: Vec<BodyId>,
+	/// Bodies handed out mutably since the last step, whose proxies may no
+	/// longer match their collider.
+	touched: HashSet<BodyId>,
+}
+
+impl PhysicsWorld2D {
+	pub fn new() -> PhysicsWorld2D {
+		PhysicsWorld2D {
+			gravity: Vector2D::from(0.0, 980.0),
+			iterations: 8,
+			sleep_time: 0.5,
+			sleep_velocity: 5.0,
+			sleep_angular_velocity: 0.05,
+			slots: Vec::new(),
+			free: Vec::new(),
+			joints: Vec::new(),
+			free_joints: Vec::new(),
+			contacts: Vec::new(),
+			impulses: HashMap::new(),
+			tracker: CollisionTracker2D::new(),
+			events: Vec::new(),
+			removed: Vec::new(),
+			broad: BroadPhase2D::new(CELL_SIZE),
+			proxies: Vec::new(),
+			owners: Vec::new(),
+			touched: HashSet::new(),
+		}
+	}
+
+	/// Adds a body with its collider and computes its moment of inertia from
+	/// the collider shape.
+	pub fn insert(&mut self, mut body: RigidBody2D, collider: Collision2D) -> BodyId {
+		body.inertia = inertia(&collider, body.transform.scale, body.mass);
+		let proxy = self.broad.insert(collider.bounds(body.transform), collider.layer, collider.mask);
+		let id = match self.free.pop() {
+			Some(index) => {
+				let slot = &mut self.slots[index as usize];
+				slot.1 = Some((body, collider));
+				self.proxies[index as usize] = proxy;
+				BodyId { index, generation: slot.0 }
+			},
+			None => {
+				self.slots.push((0, Some((body, collider))));
+				self.proxies.push(proxy);
+				BodyId { index: self.slots.len() as u32 - 1, generation: 0 }
+			},
+		};
+		if proxy.index() < self.owners.len() {
+			self.owners[proxy.index()] = id;
+		} else {
+			self.owners.push(id);
+		}
+		id
+	}
+
+	/// Removes a body and its joints, returning it with its collider.
+	pub fn remove(&mut self, id: BodyId) -> Option<(RigidBody2D, Collision2D)> {
+		let slot = self.slots.get_mut(id.index as usize).filter(|slot| slot.0 == id.generation)?;
+		let removed = slot.1.take()?;
+		slot.0 += 1;
+		self.free.push(id.index);
+		self.broad.remove(self.proxies[id.index as usize]);
+		self.touched.remove(&id);
+		for (index, (generation, joint)) in self.joints.iter_mut().enumerate() {
+			if joint.is_some_and(|joint| joint.a == id || joint.b == id) {
+				*joint = None;
+				*generation += 1;
+				self.free_joints.push(index as u32);
+			}
+		}
+		self.contacts.retain(|(a, b, _)| *a != id && *b != id);
+		self.impulses.retain(|(a, b), _| *a != id && *b != id);
+		for event in self.tracker.remove(id) {
+			let other = event.other(id).and_then(|other| self.collider(other));
+			if removed.1.monitoring || other.is_some_and(|other| other.monitoring) {
+				self.removed.push(event);
+			}
+		}
+		Some(removed)
+	}
+
+	pub fn contains(&self, id: BodyId) -> bool {
+		self.get(id).is_some()
+	}
+
+	pub fn len(&self) -> usize {
+		self.slots.len() - self.free.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// Every body in the world.
+	pub fn bodies(&self) -> Vec<BodyId> {
+		self.slots.iter().enumerate()
+			.filter(|(_, slot)| slot.1.is_some())
+			.map(|(index, slot)| BodyId { index: index as u32, generation: slot.0 })
+			.collect()
+	}
+
+	pub fn body(&self, id: BodyId) -> Option<&RigidBody2D> {
+		self.get(id).map(|(body, _)| body)
+	}
+
+	pub fn body_mut(&mut self, id: BodyId) -> Option<&mut RigidBody2D> {
+		self.get_mut(id).map(|(body, _)| body)
+	}
+
+	pub fn collider(&self, id: BodyId) -> Option<&Collision2D> {
+		self.get(id).map(|(_, collider)| collider)
+	}
+
+	/// The collider of a body. Call `refresh_mass` after changing its shape.
+	pub fn collider_mut(&mut self, id: BodyId) -> Option<&mut Collision2D> {
+		self.get_mut(id).map(|(_, collider)| collider)
+	}
+
+	/// Recomputes the moment of inertia of a body after its mass, scale or
+	/// collider shape changed.
+	pub fn refresh_mass(&mut self, id: BodyId) {
+		if let Some((body, collider)) = self.get_mut(id) {
+			body.inertia = inertia(collider, body.transform.scale, body.mass);
+		}
+	}
+
+	/// Connects two bodies, keeping their current angle to each other as the
+	/// reference for limits and welds. Returns `None` when a body is not in
+	/// the world or both are the same body.
+	pub fn add_joint(&mut self, mut joint: Joint2D) -> Option<JointId> {
+		if joint.a == joint.b {
+			return None;
+		}
+		let (a, b) = (self.body(joint.a)?, self.body(joint.b)?);
+		joint.reference = b.transform.rotation.rad() - a.transform.rotation.rad();
+		joint.impulses = Default::default();
+		match self.free_joints.pop() {
+			Some(index) => {
+				let slot = &mut self.joints[index as usize];
+				slot.1 = Some(joint);
+				Some(JointId { index, generation: slot.0 })
+			},
+			None => {
+				self.joints.push((0, Some(joint)));
+				Some(JointId { index: self.joints.len() as u32 - 1, generation: 0 })
+			},
+		}
+	}
+
+	pub fn remove_joint(&mut self, id: JointId) -> Option<Joint2D> {
+		let slot = self.joints.get_mut(id.index as usize).filter(|slot| slot.0 == id.generation)?;
+		let removed = slot.1.take()?;
+		slot.0 += 1;
+		self.free_joints.push(id.index);
+		Some(removed)
+	}
+
+	/// Every joint in the world.
+	pub fn joints(&self) -> Vec<JointId> {
+		self.joints.iter().enumerate()
+			.filter(|(_, slot)| slot.1.is_some())
+			.map(|(index, slot)| JointId { index: index as u32, generation: slot.0 })
+			.collect()
+	}
+
+	pub fn joint(&self, id: JointId) -> Option<&Joint2D> {
+		match self.joints.get(id.index as usize) {
+			Some((generation, joint)) if *generation == id.generation => joint.as_ref(),
+			_ => None,
+		}
+	}
+
+	/// A joint to change its limits, motor or anchors. Changing its bodies
+	/// keeps the reference angle of the old ones.
+	pub fn joint_mut(&mut self, id: JointId) -> Option<&mut Joint2D> {
+		match self.joints.get_mut(id.index as usize) {
+			Some((generation, joint)) if *generation == id.generation => joint.as_mut(),
+			_ => None,
+		}
+	}
+
+	/// A body with its collider.
+	pub(crate) fn entry(&self, id: BodyId) -> Option<(&RigidBody2D, &Collision2D)> {
+		self.get(id).map(|(body, collider)| (body, collider))
+	}
+
+	/// Contacts found during the last step, with the normal pointing from
+	/// the first body into the second. Sensor contacts are included.
+	pub fn contacts(&self) -> &[(BodyId, BodyId, Contact2D)] {
+		&self.contacts
+	}
+
+	/// Collision events of the last step: begin or persist for every pair
+	/// touching, then end for every pair that stopped touching.
+	pub fn events(&self) -> &[CollisionEvent2D<BodyId>] {
+		&self.events
+	}
+
+	/// Whether two bodies touched during the last step.
+	pub fn is_touching(&self, a: BodyId, b: BodyId) -> bool {
+		self.tracker.is_touching(a, b)
+	}
+
+	/// Advances the simulation by `delta` seconds.
+	pub fn step(&mut self, delta: f32) {
+		if delta <= 0.0 {
+			return;
+		}
+		let ids = self.bodies();
+		let mut entries: Vec<(RigidBody2D, Collision2D)> = ids.iter().map(|&id| self.slots[id.index as usize].1.take().unwrap()).collect();
+		for id in std::mem::take(&mut self.touched) {
+			if let Ok(i) = ids.binary_search(&id) {
+				self.update_proxy(id, &entries[i]);
+			}
+		}
+		let before: Vec<Transform2D> = entries.iter().map(|(body, _)| body.transform).collect();
+
+		let mut joints = self.prepare_joints(&ids, &mut entries, delta);
+		for (body, _) in entries.iter_mut() {
+			integrate_velocity(body, self.gravity, delta);
+		}
+
+		let (mut constraints, contacts, touching) = self.find_contacts(&ids, &mut entries);
+
+		// contacts that persist start from last step's impulses, which keeps stacks steady
+		for constraint in constraints.iter_mut() {
+			let cached = match self.impulses.get(&(ids[constraint.a], ids[constraint.b])) {
+				Some(cached) if cached.len() == constraint.points.len() => cached,
+				_ => continue,
+			};
+			let tangent = perpendicular(constraint.normal);
+			for (point, &(normal_impulse, tangent_impulse)) in constraint.points.iter_mut().zip(cached) {
+				point.normal_impulse = normal_impulse;
+				point.tangent_impulse = tangent_impulse;
+				apply(&mut entries, constraint.a, constraint.b, point, constraint.normal * normal_impulse + tangent * tangent_impulse);
+			}
+		}
+		for (_, joint) in &joints {
+			joint.warm_start(&mut entries);
+		}
+
+		for _ in 0..self.iterations {
+			for constraint in constraints.iter_mut() {
+				solve(constraint, &mut entries);
+			}
+			for (_, joint) in joints.iter_mut() {
+				joint.solve(&mut entries);
+			}
+		}
+		for (index, joint) in &joints {
+			if let Some(slot) = self.joints[*index].1.as_mut() {
+				slot.impulses = joint.impulses();
+			}
+		}
+
+		for (body, _) in entries.iter_mut() {
+			integrate_position(body, delta);
+		}
+		for constraint in &constraints {
+			correct_position(constraint, &mut entries);
+		}
+		for (_, joint) in &joints {
+			joint.correct(&mut entries, delta);
+		}
+		for (body, _) in entries.iter_mut() {
+			self.update_sleep(body, delta);
+		}
+		for (i, entry) in entries.iter().enumerate() {
+			if entry.0.transform != before[i] {
+				self.update_proxy(ids[i], entry);
+			}
+		}
+
+		for (i, (_, collider)) in entries.iter_mut().enumerate() {
+			collider.colliding = touching.iter().any(|&(a, b)| a == i || b == i);
+		}
+		self.impulses = constraints.iter()
+			.map(|constraint| ((ids[constraint.a], ids[constraint.b]), constraint.points.iter().map(|point| (point.normal_impulse, point.tangent_impulse)).collect()))
+			.collect();
+		self.contacts = contacts.into_iter().map(|(a, b, contact)| (ids[a], ids[b], contact)).collect();
+		for (id, entry) in ids.iter().zip(entries) {
+			self.slots[id.index as usize].1 = Some(entry);
+		}
+
+		let events = self.tracker.update(touching.into_iter().map(|(a, b)| (ids[a], ids[b])));
+		let monitored = |id: BodyId| self.collider(id).is_some_and(|collider| collider.monitoring);
+		let events: Vec<CollisionEvent2D<BodyId>> = events.into_iter().filter(|event| monitored(event.a) || monitored(event.b)).collect();
+		self.events = self.removed.drain(..).chain(events).collect();
+	}
+
+	/// Prepares the solver for every joint, waking bodies joined to one that
+	/// moves and bodies driven by a motor.
+	fn prepare_joints(&self, ids: &[BodyId], entries: &mut [(RigidBody2D, Collision2D)], delta: f32) -> Vec<(usize, JointConstraint)> {
+		let disturbs = |body: &RigidBody2D| self.disturbs(body);
+		let mut prepared = Vec::new();
+		for (index, (_, joint)) in self.joints.iter().enumerate() {
+			let joint = match joint {
+				Some(joint) => joint,
+				None => continue,
+			};
+			let (a, b) = match (ids.binary_search(&joint.a), ids.binary_search(&joint.b)) {
+				(Ok(a), Ok(b)) => (a, b),
+				_ => continue,
+			};
+			let driven = joint.motor.is_some_and(|(speed, _)| speed != 0.0);
+			if driven || disturbs(&entries[a].0) {
+				entries[b].0.wake();
+			}
+			if driven || disturbs(&entries[b].0) {
+				entries[a].0.wake();
+			}
+			prepared.push((index, JointConstraint::new(joint, a, b, entries, delta)));
+		}
+		prepared
+	}
+
+	/// Runs the broad and narrow phase and prepares the solver, waking
+	/// sleeping bodies that something moving touches. Also returns every
+	/// pair touching, including sensors and resting pairs that were not
+	/// tested because nothing in them moves.
+	fn find_contacts(&self, ids: &[BodyId], entries: &mut [(RigidBody2D, Collision2D)]) -> (Vec<Constraint>, Vec<IndexedContact>, Vec<(usize, usize)>) {
+		// proxies are sorted by id, which is not the order of the bodies once slots are reused
+		let mut pairs: Vec<(usize, usize)> = self.broad.pairs().into_iter()
+			.filter_map(|(a, b)| Some((ids.binary_search(&self.owners[a.index()]).ok()?, ids.binary_search(&self.owners[b.index()]).ok()?)))
+			.map(|(a, b)| (a.min(b), a.max(b)))
+			.collect();
+		pairs.sort();
+		// shapes are only placed for the bodies that get tested
+		let mut shapes: Vec<Option<Compound>> = vec![None; entries.len()];
+
+		let connected: HashSet<(BodyId, BodyId)> = self.joints.iter()
+			.filter_map(|(_, joint)| joint.filter(|joint| !joint.collide_connected))
+			.map(|joint| (joint.a.min(joint.b), joint.a.max(joint.b)))
+			.collect();
+
+		let mut constraints = Vec::new();
+		let mut contacts = Vec::new();
+		let mut touching = Vec::new();
+		for (a, b) in pairs {
+			if connected.contains(&(ids[a].min(ids[b]), ids[a].max(ids[b]))) {
+				continue;
+			}
+			let sensor = entries[a].1.sensor || entries[b].1.sensor;
+			if !sensor && !entries[a].0.is_dynamic() && !entries[b].0.is_dynamic() {
+				continue;
+			}
+			let (awake_a, awake_b) = (self.is_moving(&entries[a].0), self.is_moving(&entries[b].0));
+			if !awake_a && !awake_b {
+				if self.tracker.is_touching(ids[a], ids[b]) {
+					touching.push((a, b));
+				}
+				continue;
+			}
+			for &i in &[a, b] {
+				if shapes[i].is_none() {
+					shapes[i] = Some(Compound::from(&entries[i].1.shape, entries[i].0.transform));
+				}
+			}
+			let contact = match (&shapes[a], &shapes[b]) {
+				(Some(shape_a), Some(shape_b)) => collide_compound(shape_a, shape_b),
+				_ => None,
+			};
+			let contact = match contact {
+				Some(contact) => contact,
+				None => continue,
+			};
+			touching.push((a, b));
+			if sensor {
+				contacts.push((a, b, contact));
+				continue;
+			}
+			if entries[b].0.sleeping && self.disturbs(&entries[a].0) {
+				entries[b].0.wake();
+			}
+			if entries[a].0.sleeping && self.disturbs(&entries[b].0) {
+				entries[a].0.wake();
+			}
+			constraints.push(prepare(a, b, &contact, entries));
+			contacts.push((a, b, contact));
+		}
+		(constraints, contacts, touching)
+	}
+
+	/// Moves the proxy of a body to the bounds and layers of its collider.
+	fn update_proxy(&mut self, id: BodyId, (body, collider): &(RigidBody2D, Collision2D)) {
+		let proxy = self.proxies[id.index as usize];
+		self.broad.update(proxy, collider.bounds(body.transform));
+		self.broad.set_layers(proxy, collider.layer, collider.mask);
+	}
+
+	/// Bodies on `mask` whose bounds may touch `region`, sorted by id. Bodies
+	/// changed since the last step are always included.
+	pub(crate) fn near(&self, region: Rect2D, mask: u32) -> Vec<BodyId> {
+		let region = Rect2D {
+			position: Position2D { x: region.left() - QUERY_MARGIN, y: region.top() - QUERY_MARGIN },
+			size: Size2D { x: region.size.x + 2.0 * QUERY_MARGIN, y: region.size.y + 2.0 * QUERY_MARGIN },
+		};
+		let mut ids: Vec<BodyId> = self.broad.query_region(region, mask).into_iter()
+			.map(|proxy| self.owners[proxy.index()])
+			.chain(self.touched.iter().copied())
+			.collect();
+		ids.sort();
+		ids.dedup();
+		ids
+	}
+
+	/// Whether a body can disturb the bodies it touches.
+	fn is_moving(&self, body: &RigidBody2D) -> bool {
+		match body.body_type {
+			super::BodyType::Static => false,
+			super::BodyType::Kinematic => !body.velocity > 0.0 || body.angular_velocity != 0.0,
+			super::BodyType::Dynamic => !body.sleeping,
+		}
+	}
+
+	/// Whether a body wakes the sleeping bodies it touches or is joined to.
+	/// Bodies that are settling do not, or neighbours falling asleep one
+	/// after the other would keep waking each other.
+	fn disturbs(&self, body: &RigidBody2D) -> bool {
+		self.is_moving(body) && body.idle == 0.0
+	}
+
+	fn update_sleep(&self, body: &mut RigidBody2D, delta: f32) {
+		if !body.is_dynamic() || body.sleeping {
+			return;
+		}
+		let slow = !body.velocity < self.sleep_velocity && body.angular_velocity.abs() < self.sleep_angular_velocity;
+		if body.can_sleep && slow {
+			body.idle += delta;
+			if body.idle >= self.sleep_time {
+				body.sleep();
+			}
+		} else {
+			body.idle = 0.0;
+		}
+	}
+
+	fn get(&self, id: BodyId) -> Option<&(RigidBody2D, Collision2D)> {
+		match self.slots.get(id.index as usize) {
+			Some((generation, entry)) if *generation == id.generation => entry.as_ref(),
+			_ => None,
+		}
+	}
+
+	/// An entry that may be changed, so its proxy is refreshed by the next step.
+	fn get_mut(&mut self, id: BodyId) -> Option<&mut (RigidBody2D, Collision2D)> {
+		let entry = match self.slots.get_mut(id.index as usize) {
+			Some((generation, entry)) if *generation == id.generation => entry.as_mut()?,
+			_ => return None,
+		};
+		self.touched.insert(id);
+		Some(entry)
+	}
+}
+
+impl Default for PhysicsWorld2D {
+	fn default() -> PhysicsWorld2D {
+		PhysicsWorld2D::new()
+	}
+}
+
+/// Applies gravity, forces and drag to an awake dynamic body and clears its forces.
+fn integrate_velocity(body: &mut RigidBody2D, gravity: Vector2D, delta: f32) {
+	if body.is_dynamic() && !body.sleeping {
+		body.velocity += (gravity * body.gravity_scale + body.force * body.inverse_mass()) * delta;
+		body.angular_velocity += body.torque * body.inverse_inertia() * delta;
+		body.velocity *= 1.0 / (1.0 + body.drag * delta);
+		body.angular_velocity *= 1.0 / (1.0 + body.angular_drag * delta);
+		if body.fixed_rotation {
+			body.angular_velocity = 0.0;
+		}
+	}
+	body.force = Vector2D::new();
+	body.torque = 0.0;
+}
+
+/// Moves an awake body by its velocity and writes the result to its transform.
+fn integrate_position(body: &mut RigidBody2D, delta: f32) {
+	if body.body_type == super::BodyType::Static || body.sleeping {
+		return;
+	}
+	body.transform.position.x += body.velocity.x * delta;
+	body.transform.position.y += body.velocity.y * delta;
+	if body.angular_velocity != 0.0 {
+		body.transform.rotation = Rotation2D::from_rad(body.transform.rotation.rad() + body.angular_velocity * delta);
+	}
+}
+
+fn prepare(a: usize, b: usize, contact: &Contact2D, entries: &[(RigidBody2D, Collision2D)]) -> Constraint {
+	let (body_a, body_b) = (&entries[a].0, &entries[b].0);
+	let normal = contact.normal;
+	let tangent = perpendicular(normal);
+	let (ma, mb) = (body_a.inverse_mass(), body_b.inverse_mass());
+	let (ia, ib) = (body_a.inverse_inertia(), body_b.inverse_inertia());
+	let restitution = body_a.restitution.max(body_b.restitution);
+
+	let points = contact.points.iter().map(|&point| {
+		let point = vector(point);
+		let (ra, rb) = (point - body_a.origin(), point - body_b.origin());
+		let effective_mass = |axis: Vector2D| {
+			let (rna, rnb) = (cross(ra, axis), cross(rb, axis));
+			let k = ma + mb + ia * rna * rna + ib * rnb * rnb;
+			if k > 0.0 { 1.0 / k } else { 0.0 }
+		};
+		let approach = (body_b.velocity_at(point) - body_a.velocity_at(point)) * normal;
+		ContactPoint {
+			ra,
+			rb,
+			normal_mass: effective_mass(normal),
+			tangent_mass: effective_mass(tangent),
+			bounce: if approach < -RESTITUTION_THRESHOLD { -restitution * approach } else { 0.0 },
+			normal_impulse: 0.0,
+			tangent_impulse: 0.0,
+		}
+	}).collect();
+
+	Constraint {
+		a,
+		b,
+		normal,
+		depth: contact.depth,
+		friction: (body_a.friction * body_b.friction).max(0.0).sqrt(),
+		points,
+	}
+}
+
+/// One solver pass over a contact: normal impulses keep the bodies from
+/// moving into each other, friction impulses oppose sliding.
+fn solve(constraint: &mut Constraint, entries: &mut [(RigidBody2D, Collision2D)]) {
+	let (a, b) = (constraint.a, constraint.b);
+	let normal = constraint.normal;
+	let tangent = perpendicular(normal);
+
+	for point in constraint.points.iter_mut() {
+		let (ra, rb) = (point.ra, point.rb);
+		let relative = |entries: &[(RigidBody2D, Collision2D)]| {
+			let (body_a, body_b) = (&entries[a].0, &entries[b].0);
+			body_b.velocity + cross_scalar(body_b.angular_velocity, rb) - body_a.velocity - cross_scalar(body_a.angular_velocity, ra)
+		};
+
+		let approach = relative(entries) * normal;
+		let impulse = point.normal_mass * (point.bounce - approach);
+		let total = (point.normal_impulse + impulse).max(0.0);
+		let impulse = total - point.normal_impulse;
+		point.normal_impulse = total;
+		apply(entries, a, b, point, normal * impulse);
+
+		let slide = relative(entries) * tangent;
+		let limit = constraint.friction * point.normal_impulse;
+		let total = (point.tangent_impulse - point.tangent_mass * slide).clamp(-limit, limit);
+		let impulse = total - point.tangent_impulse;
+		point.tangent_impulse = total;
+		apply(entries, a, b, point, tangent * impulse);
+	}
+}
+
+fn apply(entries: &mut [(RigidBody2D, Collision2D)], a: usize, b: usize, point: &ContactPoint, impulse: Vector2D) {
+	let body_a = &mut entries[a].0;
+	body_a.velocity -= impulse * body_a.inverse_mass();
+	body_a.angular_velocity -= cross(point.ra, impulse) * body_a.inverse_inertia();
+	let body_b = &mut entries[b].0;
+	body_b.velocity += impulse * body_b.inverse_mass();
+	body_b.angular_velocity += cross(point.rb, impulse) * body_b.inverse_inertia();
+}
+
+/// Pushes overlapping bodies apart along the contact normal, in proportion
+/// to their inverse masses.
+fn correct_position(constraint: &Constraint, entries: &mut [(RigidBody2D, Collision2D)]) {
+	let (ma, mb) = (entries[constraint.a].0.inverse_mass(), entries[constraint.b].0.inverse_mass());
+	if ma + mb <= 0.0 {
+		return;
+	}
+	let correction = constraint.normal * ((constraint.depth - SLOP).max(0.0) * CORRECTION / (ma + mb));
+	let position = &mut entries[constraint.a].0.transform.position;
+	position.x -= correction.x * ma;
+	position.y -= correction.y * ma;
+	let position = &mut entries[constraint.b].0.transform.position;
+	position.x += correction.x * mb;
+	position.y += correction.y * mb;
+}
+
+/// Moment of inertia of a collider around the body origin for `mass`,
+/// treating the shape as a uniformly dense solid.
+fn inertia(collider: &Collision2D, scale: Scale2D, mass: f32) -> f32 {
+	let local = Transform2D::from(Position2D::new(), Rotation2D::new(), scale);
+	let shape = Compound::from(&collider.shape, local);
+	if let [piece] = shape.pieces.as_slice() {
+		let radius = piece.radius;
+		match piece.points.as_slice() {
+			[] => return 0.0,
+			[center] => return mass * (radius * radius / 2.0 + *center * *center),
+			[start, end] => {
+				// a capsule as the box around its segment
+				let center = (*start + *end) * 0.5;
+				let (length, width) = (!(*end - *start) + 2.0 * radius, 2.0 * radius);
+				return mass * ((length * length + width * width) / 12.0 + center * center);
+			},
+			_ => {},
+		}
+	}
+
+	// polygons, summed over their convex pieces, which all wind the same way
+	let (mut area, mut moment) = (0.0, 0.0);
+	for points in shape.pieces.iter().map(|piece| &piece.points) {
+		for i in 0..points.len() {
+			let (p, q) = (points[i], points[(i + 1) % points.len()]);
+			let twice_area = cross(p, q);
+			area += twice_area;
+			moment += twice_area * (p * p + p * q + q * q);
+		}
+	}
+	if area.abs() > f32::EPSILON { mass * moment / (6.0 * area) } else { 0.0 }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{test_util::{STEP, at, ball, boxed, run, with_ground}, Physics::BodyType, Collision::{Capsule2D, CollisionPhase, Shape2D}};
+
+	#[test]
+	fn physics_gravity() {
+		let mut world = PhysicsWorld2D::new();
+		let body = world.insert(RigidBody2D::new(Transform2D::new()), ball(1.0));
+		let floating = world.insert(RigidBody2D::new(at(100.0, 0.0)).gravity_scale(0.0), ball(1.0));
+		run(&mut world, 1.0);
+		let y = world.body(body).unwrap().transform.position.y;
+		// semi-implicit Euler lands slightly past 0.5 * g * t^2
+		assert!((y - 490.0).abs() < 10.0, "{}", y);
+		assert!((world.body(body).unwrap().velocity.y - 980.0).abs() < 1e-2);
+		assert_eq!(world.body(floating).unwrap().transform.position, Position2D::from(100, 0));
+	}
+
+	#[test]
+	fn physics_force_and_drag() {
+		let mut world = PhysicsWorld2D::new();
+		world.gravity = Vector2D::new();
+		let pushed = world.insert(RigidBody2D::new(Transform2D::new()).mass(2.0), ball(1.0));
+		world.body_mut(pushed).unwrap().apply_force(Vector2D::from(120.0, 0.0));
+		world.step(STEP);
+		assert!((world.body(pushed).unwrap().velocity.x - 1.0).abs() < 1e-4);
+		world.step(STEP);
+		assert!((world.body(pushed).unwrap().velocity.x - 1.0).abs() < 1e-4);
+
+		let dragged = world.insert(RigidBody2D::new(Transform2D::new()).velocity(Vector2D::from(100.0, 0.0)).drag(2.0, 0.0), ball(1.0));
+		run(&mut world, 1.0);
+		assert!(world.body(dragged).unwrap().velocity.x < 20.0);
+	}
+
+	#[test]
+	fn physics_rest_on_ground() {
+		let (mut world, _) = with_ground();
+		let crate_ = world.insert(RigidBody2D::new(at(0.0, -100.0)), boxed(20.0, 20.0));
+		run(&mut world, 3.0);
+		let body = world.body(crate_).unwrap();
+		assert!((body.transform.position.y + 10.0).abs() < 1.0, "{:?}", body.transform.position);
+		assert!(body.transform.rotation.deg().abs() < 1.0);
+		assert!(body.is_sleeping());
+		assert!(world.collider(crate_).unwrap().colliding);
+		assert_eq!(world.contacts().len(), 0);
+	}
+
+	#[test]
+	fn physics_stack() {
+		let (mut world, _) = with_ground();
+		let boxes: Vec<BodyId> = (0..4).map(|i| world.insert(RigidBody2D::new(at(0.0, -10.0 - 21.0 * i as f32)), boxed(20.0, 20.0))).collect();
+		run(&mut world, 4.0);
+		for (i, &id) in boxes.iter().enumerate() {
+			let position = world.body(id).unwrap().transform.position;
+			assert!(position.x.abs() < 1.0, "{} {:?}", i, position);
+			assert!((position.y + 10.0 + 20.0 * i as f32).abs() < 2.0, "{} {:?}", i, position);
+			assert!(world.body(id).unwrap().is_sleeping(), "{}", i);
+		}
+	}
+
+	#[test]
+	fn physics_restitution() {
+		let (mut world, _) = with_ground();
+		let bouncy = world.insert(RigidBody2D::new(at(0.0, -200.0)).restitution(1.0), ball(10.0));
+		let dull = world.insert(RigidBody2D::new(at(100.0, -200.0)), ball(10.0));
+
+		let mut highest = f32::MAX;
+		let mut bounced = false;
+		for _ in 0..120 {
+			world.step(STEP);
+			let body = world.body(bouncy).unwrap();
+			bounced |= body.velocity.y < 0.0;
+			if bounced {
+				highest = highest.min(body.transform.position.y);
+			}
+		}
+		assert!(bounced);
+		assert!(highest < -150.0, "{}", highest);
+		assert!((world.body(dull).unwrap().transform.position.y + 10.0).abs() < 1.0);
+	}
+
+	#[test]
+	fn physics_friction() {
+		let (mut world, _) = with_ground();
+		let rough = world.insert(RigidBody2D::new(at(0.0, -10.0)).velocity(Vector2D::from(200.0, 0.0)).fixed_rotation(true), boxed(20.0, 20.0));
+		let smooth = world.insert(RigidBody2D::new(at(-500.0, -10.0)).velocity(Vector2D::from(200.0, 0.0)).friction(0.0), boxed(20.0, 20.0));
+		run(&mut world, 1.0);
+		assert!(world.body(rough).unwrap().velocity.x.abs() < 1.0);
+		assert!((world.body(smooth).unwrap().velocity.x - 200.0).abs() < 1.0);
+	}
+
+	#[test]
+	fn physics_rolling() {
+		let (mut world, _) = with_ground();
+		let wheel = world.insert(RigidBody2D::new(at(0.0, -10.0)).velocity(Vector2D::from(100.0, 0.0)), ball(10.0));
+		run(&mut world, 1.0);
+		let body = world.body(wheel).unwrap();
+		// friction turns sliding into rolling: v = w * r
+		assert!(body.angular_velocity > 0.0);
+		assert!((body.velocity.x - body.angular_velocity * 10.0).abs() < 2.0);
+		assert!(body.transform.rotation.rad() != 0.0);
+	}
+
+	#[test]
+	fn physics_kinematic_pushes() {
+		let mut world = PhysicsWorld2D::new();
+		world.gravity = Vector2D::new();
+		let platform = world.insert(RigidBody2D::new(at(0.0, 0.0)).body_type(BodyType::Kinematic).velocity(Vector2D::from(100.0, 0.0)), boxed(20.0, 20.0));
+		let pushed = world.insert(RigidBody2D::new(at(30.0, 0.0)), boxed(20.0, 20.0));
+		let wall = world.insert(RigidBody2D::new(at(-100.0, 0.0)).body_type(BodyType::Static), boxed(20.0, 20.0));
+		run(&mut world, 1.0);
+		assert!((world.body(platform).unwrap().transform.position.x - 100.0).abs() < 1e-2);
+		assert!(world.body(pushed).unwrap().transform.position.x > 115.0);
+		assert_eq!(world.body(wall).unwrap().transform.position, Position2D::from(-100, 0));
+	}
+
+	#[test]
+	fn physics_wake() {
+		let (mut world, _) = with_ground();
+		let resting = world.insert(RigidBody2D::new(at(0.0, -10.0)), boxed(20.0, 20.0));
+		run(&mut world, 1.0);
+		assert!(world.body(resting).unwrap().is_sleeping());
+
+		let falling = world.insert(RigidBody2D::new(at(0.0, -100.0)), ball(5.0));
+		let mut woke = false;
+		for _ in 0..60 {
+			world.step(STEP);
+			woke |= !world.body(resting).unwrap().is_sleeping();
+		}
+		assert!(woke);
+		assert!(world.body(falling).unwrap().transform.position.y < -20.0);
+
+		run(&mut world, 2.0);
+		world.body_mut(resting).unwrap().apply_impulse(Vector2D::from(0.0, -500.0));
+		world.step(STEP);
+		assert!(world.body(resting).unwrap().transform.position.y < -10.0);
+	}
+
+	#[test]
+	fn physics_layers() {
+		let (mut world, _) = with_ground();
+		let ghost = boxed(20.0, 20.0).layer(2).mask(2);
+		let ghost = world.insert(RigidBody2D::new(at(0.0, -10.0)), ghost);
+		run(&mut world, 0.5);
+		assert!(world.body(ghost).unwrap().transform.position.y > 50.0);
+	}
+
+	#[test]
+	fn physics_events() {
+		let (mut world, _) = with_ground();
+		let ground = world.bodies()[0];
+		let ball = world.insert(RigidBody2D::new(at(0.0, -50.0)), ball(10.0));
+		let mut phases = Vec::new();
+		for _ in 0..120 {
+			world.step(STEP);
+			for event in world.events() {
+				assert_eq!((event.a, event.b), (ground, ball));
+				if phases.last() != Some(&event.phase) {
+					phases.push(event.phase);
+				}
+			}
+		}
+		// the pair keeps persisting after the ball falls asleep
+		assert!(world.body(ball).unwrap().is_sleeping());
+		assert_eq!(phases, vec![CollisionPhase::Begin, CollisionPhase::Persist]);
+		assert!(world.is_touching(ball, ground));
+
+		world.remove(ball);
+		world.step(STEP);
+		assert_eq!(world.events(), &[CollisionEvent2D { phase: CollisionPhase::End, a: ground, b: ball }]);
+		world.step(STEP);
+		assert!(world.events().is_empty());
+	}
+
+	#[test]
+	fn physics_sensor() {
+		let mut world = PhysicsWorld2D::new();
+		let zone = world.insert(RigidBody2D::new(at(0.0, 100.0)).body_type(BodyType::Static), boxed(100.0, 20.0).sensor(true));
+		let falling = world.insert(RigidBody2D::new(Transform2D::new()), ball(5.0));
+		let mut phases = Vec::new();
+		for _ in 0..60 {
+			world.step(STEP);
+			phases.extend(world.events().iter().filter(|event| event.phase != CollisionPhase::Persist).map(|event| event.phase));
+			if world.is_touching(zone, falling) {
+				assert!(world.collider(zone).unwrap().colliding);
+			}
+		}
+		assert_eq!(phases, vec![CollisionPhase::Begin, CollisionPhase::End]);
+		// passed straight through
+		assert!((world.body(falling).unwrap().velocity.y - 980.0).abs() < 1e-2);
+
+		let mut quiet = PhysicsWorld2D::new();
+		quiet.insert(RigidBody2D::new(at(0.0, 100.0)).body_type(BodyType::Static), boxed(100.0, 20.0).sensor(true).monitoring(false));
+		quiet.insert(RigidBody2D::new(Transform2D::new()), ball(5.0).monitoring(false));
+		for _ in 0..60 {
+			quiet.step(STEP);
+			assert!(quiet.events().is_empty());
+		}
+	}
+
+	#[test]
+	fn physics_remove() {
+		let mut world = PhysicsWorld2D::new();
+		let a = world.insert(RigidBody2D::new(Transform2D::new()), ball(1.0));
+		assert!(world.remove(a).is_some());
+		assert!(world.remove(a).is_none());
+		let b = world.insert(RigidBody2D::new(Transform2D::new()), ball(1.0));
+		assert_ne!(a, b);
+		assert!(world.body(a).is_none());
+		assert_eq!(world.bodies(), vec![b]);
+	}
+
+	#[test]
+	fn physics_inertia() {
+		let circle = inertia(&ball(2.0), Scale2D::new(), 3.0);
+		assert!((circle - 6.0).abs() < 1e-4);
+		let rectangle = inertia(&boxed(6.0, 12.0), Scale2D::new(), 2.0);
+		assert!((rectangle - 30.0).abs() < 1e-3);
+		let scaled = inertia(&boxed(3.0, 6.0), Scale2D::from(2, 2), 2.0);
+		assert!((scaled - 30.0).abs() < 1e-3);
+		let capsule = inertia(&Collision2D::new(Shape2D::Capsule(Capsule2D::new(1.0, 10.0))), Scale2D::new(), 12.0);
+		assert!((capsule - 104.0).abs() < 1e-3);
+	}
+}
@@ -0,0 +1,340 @@
+use super::{Renderer2D, Texture};
+use crate::{Rect2D, Sprite2D};
+use image::{ImageFormat, RgbaImage};
+
+use std::{fs, io, path::Path, str::FromStr};
+
+/// Packs many images into a single `Atlas`, so sprites using them can
+/// share one texture and one batch.
+///
+/// Every image gets `padding` transparent pixels of space around it, and
+/// its border pixels are repeated `extrude` times outward so filtering at
+/// the edges of a region never picks up its neighbours.
+///
+/// ```rust
+/// use Engine::Render::AtlasBuilder;
+/// use image::RgbaImage;
+///
+/// let mut builder = AtlasBuilder::new().padding(2).extrude(1);
+/// builder.insert("player", RgbaImage::new(16, 24));
+/// builder.insert("coin", RgbaImage::new(8, 8));
+/// let atlas = builder.build().unwrap();
+///
+/// let player = atlas.region("player").unwrap();
+/// assert_eq!((player.size.x, player.size.y), (16.0, 24.0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct AtlasBuilder {
+	pub padding: u32,
+	pub extrude: u32,
+	pub max_size: u32,
+	images: Vec<(String, RgbaImage)>,
+}
+
+impl AtlasBuilder {
+	pub const fn new() -> AtlasBuilder {
+		AtlasBuilder { padding: 1, extrude: 0, max_size: 4096, images: Vec::new() }
+	}
+
+	/// Collects every image file of `dir`, named after its file name
+	/// without the extension. Other files are skipped.
+	pub fn from_dir<P: AsRef<Path>>(dir: P) -> image::ImageResult<AtlasBuilder> {
+		let mut paths: Vec<_> = fs::read_dir(dir)?
+			.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+			.filter(|path| path.is_file() && ImageFormat::from_path(path).is_ok())
+			.collect();
+		paths.sort();
+
+		let mut builder = AtlasBuilder::new();
+		for path in paths {
+			let name = path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+			builder.insert(&name, image::open(&path)?.into_rgba8());
+		}
+		Ok(builder)
+	}
+
+	pub const fn padding(mut self, padding: u32) -> AtlasBuilder {
+		self.padding = padding;
+		self
+	}
+
+	pub const fn extrude(mut self, extrude: u32) -> AtlasBuilder {
+		self.extrude = extrude;
+		self
+	}
+
+	pub const fn max_size(mut self, max_size: u32) -> AtlasBuilder {
+		self.max_size = max_size;
+		self
+	}
+
+	/// Adds an image, replacing any earlier image of the same name.
+	pub fn insert(&mut self, name: &str, image: RgbaImage) {
+		self.images.retain(|(other, _)| other != name);
+		self.images.push((String::from(name), image));
+	}
+
+	/// Packs the images into the smallest power of two sized atlas found.
+	/// Fails if an image has no pixels or the images do not fit.
+	pub fn build(&self) -> Result<Atlas, String> {
+		if let Some((name, _)) = self.images.iter().find(|(_, image)| image.width() == 0 || image.height() == 0) {
+			return Err(format!("image '{}' is empty", name));
+		}
+		let cells: Vec<(u32, u32)> = self.images.iter()
+			.map(|(_, image)| (image.width() + 2 * self.extrude, image.height() + 2 * self.extrude))
+			.collect();
+
+		// tallest first packs shelves tightly; the name keeps the order stable
+		let mut order: Vec<usize> = (0..cells.len()).collect();
+		order.sort_by(|&a, &b| cells[b].1.cmp(&cells[a].1).then(cells[b].0.cmp(&cells[a].0)).then(self.images[a].0.cmp(&self.images[b].0)));
+		let sorted: Vec<(u32, u32)> = order.iter().map(|&i| cells[i]).collect();
+
+		let mut best: Option<Layout> = None;
+		let mut width = 1;
+		while width <= self.max_size {
+			if let Some((positions, used)) = pack_shelves(&sorted, width, self.padding) {
+				let height = used.next_power_of_two();
+				if height <= self.max_size && best.as_ref().is_none_or(|(w, h, _)| width * height < w * h) {
+					best = Some((width, height, positions));
+				}
+			}
+			width *= 2;
+		}
+		let (width, height, positions) = best.ok_or_else(|| format!("images do not fit in a {0}x{0} atlas", self.max_size))?;
+
+		let mut image = RgbaImage::new(width, height);
+		let mut regions = vec![(String::new(), Rect2D::new()); self.images.len()];
+		for (&index, &(x, y)) in order.iter().zip(positions.iter()) {
+			let (name, source) = &self.images[index];
+			let (cell_width, cell_height) = cells[index];
+			for cy in 0..cell_height {
+				for cx in 0..cell_width {
+					// pixels outside the source repeat its nearest border pixel
+					let sx = cx.saturating_sub(self.extrude).min(source.width() - 1);
+					let sy = cy.saturating_sub(self.extrude).min(source.height() - 1);
+					image.put_pixel(x + cx, y + cy, *source.get_pixel(sx, sy));
+				}
+			}
+			regions[index] = (name.clone(), Rect2D::from(x + self.extrude, y + self.extrude, source.width(), source.height()));
+		}
+
+		Ok(Atlas { image, regions })
+	}
+}
+
+impl Default for AtlasBuilder {
+	fn default() -> AtlasBuilder {
+		AtlasBuilder::new()
+	}
+}
+
+/// Atlas width and height, and the top left corner of every cell.
+type Layout = (u32, u32, Vec<(u32, u32)>);
+
+/// Places cells left to right on shelves as tall as their first cell.
+/// Returns the position of every cell and the height used.
+fn pack_shelves(cells: &[(u32, u32)], width: u32, padding: u32) -> Option<(Vec<(u32, u32)>, u32)> {
+	if cells.is_empty() {
+		return Some((Vec::new(), 0));
+	}
+	let mut positions = Vec::with_capacity(cells.len());
+	let (mut x, mut y, mut shelf) = (padding, padding, 0);
+	for &(cell_width, cell_height) in cells {
+		if cell_width + 2 * padding > width {
+			return None;
+		}
+		if x + cell_width + padding > width {
+			x = padding;
+			y += shelf + padding;
+			shelf = 0;
+		}
+		positions.push((x, y));
+		x += cell_width + padding;
+		shelf = shelf.max(cell_height);
+	}
+	Some((positions, y + shelf + padding))
+}
+
+/// One image holding many named regions, built by `AtlasBuilder`.
+///
+/// The region table is stored next to the image as text, one
+/// `name = x y width height` line per region.
+#[derive(Clone, Debug)]
+pub struct Atlas {
+	pub image: RgbaImage,
+	regions: Vec<(String, Rect2D)>,
+}
+
+impl Atlas {
+	/// The pixel rectangle of the image called `name`.
+	pub fn region(&self, name: &str) -> Option<Rect2D> {
+		self.regions.iter().find(|(other, _)| other == name).map(|(_, rect)| *rect)
+	}
+
+	pub fn names(&self) -> Vec<&str> {
+		self.regions.iter().map(|(name, _)| name.as_str()).collect()
+	}
+
+	/// Hands the atlas image to the renderer as a texture.
+	pub fn upload(&self, renderer: &mut Renderer2D) -> Texture {
+		renderer.create_texture(self.image.clone())
+	}
+
+	/// A sprite showing the region `name` of `texture`, the uploaded atlas image.
+	pub fn sprite(&self, texture: Texture, name: &str) -> Option<Sprite2D> {
+		self.region(name).map(|region| Sprite2D::new(texture).source(region))
+	}
+
+	pub fn to_metadata(&self) -> String {
+		let mut metadata = String::new();
+		for (name, rect) in self.regions.iter() {
+			metadata.push_str(&format!("{} = {} {} {} {}\n", name, rect.position.x, rect.position.y, rect.size.x, rect.size.y));
+		}
+		metadata
+	}
+
+	/// Loads an atlas written by `save`.
+	pub fn load<P: AsRef<Path>, M: AsRef<Path>>(image_path: P, metadata_path: M) -> io::Result<Atlas> {
+		let image = image::open(image_path)
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?
+			.into_rgba8();
+		let regions = parse_regions(&fs::read_to_string(metadata_path)?)
+			.map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+		Ok(Atlas { image, regions })
+	}
+
+	/// Writes the image as a PNG (or any format the extension names) and the region table as text.
+	pub fn save<P: AsRef<Path>, M: AsRef<Path>>(&self, image_path: P, metadata_path: M) -> io::Result<()> {
+		self.image.save(image_path).map_err(io::Error::other)?;
+		fs::write(metadata_path, self.to_metadata())
+	}
+}
+
+fn parse_regions(metadata: &str) -> Result<Vec<(String, Rect2D)>, String> {
+	let mut regions = Vec::new();
+	for (number, line) in metadata.lines().enumerate() {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') {
+			continue;
+		}
+		let (name, rect) = line.rsplit_once('=').ok_or_else(|| format!("line {}: expected 'name = x y width height'", number + 1))?;
+		let values = rect.split_whitespace().map(f32::from_str).collect::<Result<Vec<f32>, _>>()
+			.map_err(|error| format!("line {}: {}", number + 1, error))?;
+		if values.len() != 4 {
+			return Err(format!("line {}: expected 4 numbers, found {}", number + 1, values.len()));
+		}
+		regions.push((String::from(name.trim()), Rect2D::from(values[0], values[1], values[2], values[3])));
+	}
+	Ok(regions)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use image::Rgba;
+
+	fn filled(width: u32, height: u32, color: [u8; 4]) -> RgbaImage {
+		RgbaImage::from_pixel(width, height, Rgba(color))
+	}
+
+	fn overlaps(a: Rect2D, b: Rect2D, gap: f32) -> bool {
+		a.left() < b.right() + gap && b.left() < a.right() + gap && a.top() < b.bottom() + gap && b.top() < a.bottom() + gap
+	}
+
+	#[test]
+	fn atlas_builder_new() {
+		let builder = AtlasBuilder::new();
+		assert_eq!((builder.padding, builder.extrude, builder.max_size), (1, 0, 4096));
+		assert_eq!(builder.build().unwrap().image.dimensions(), (1, 1));
+	}
+
+	#[test]
+	fn atlas_build_packs() {
+		let mut builder = AtlasBuilder::new().padding(2);
+		for i in 0..10 {
+			builder.insert(&format!("image{}", i), filled(5 + i, 12 - i, [i as u8, 0, 0, 255]));
+		}
+		let atlas = builder.build().unwrap();
+		assert!(atlas.image.width().is_power_of_two() && atlas.image.height().is_power_of_two());
+		assert_eq!(atlas.names().len(), 10);
+
+		for i in 0..10 {
+			let a = atlas.region(&format!("image{}", i)).unwrap();
+			assert_eq!(a.size, crate::Size2D::from(5 + i, 12 - i));
+			assert_eq!(atlas.image.get_pixel(a.left() as u32, a.top() as u32).0, [i as u8, 0, 0, 255]);
+			for j in 0..i {
+				let b = atlas.region(&format!("image{}", j)).unwrap();
+				assert!(!overlaps(a, b, 2.0), "image{} and image{} are closer than the padding", i, j);
+			}
+		}
+	}
+
+	#[test]
+	fn atlas_build_extrude() {
+		let mut image = filled(2, 2, [255, 0, 0, 255]);
+		image.put_pixel(1, 1, Rgba([0, 0, 255, 255]));
+		let mut builder = AtlasBuilder::new().padding(0).extrude(2);
+		builder.insert("tile", image);
+		let atlas = builder.build().unwrap();
+		assert_eq!(atlas.region("tile"), Some(Rect2D::from(2, 2, 2, 2)));
+		assert_eq!(atlas.image.dimensions(), (8, 8));
+		assert_eq!(atlas.image.get_pixel(0, 0).0, [255, 0, 0, 255]);
+		assert_eq!(atlas.image.get_pixel(5, 5).0, [0, 0, 255, 255]);
+	}
+
+	#[test]
+	fn atlas_build_too_large() {
+		let mut builder = AtlasBuilder::new().max_size(16);
+		builder.insert("big", filled(20, 2, [0; 4]));
+		assert!(builder.build().is_err());
+	}
+
+	#[test]
+	fn atlas_build_empty_image() {
+		let mut builder = AtlasBuilder::new().extrude(1);
+		builder.insert("coin", filled(4, 4, [0; 4]));
+		builder.insert("nothing", RgbaImage::new(0, 0));
+		assert_eq!(builder.build().unwrap_err(), "image 'nothing' is empty");
+	}
+
+	#[test]
+	fn atlas_sprite() {
+		let mut builder = AtlasBuilder::new();
+		builder.insert("coin", filled(4, 4, [0; 4]));
+		let atlas = builder.build().unwrap();
+		let mut renderer = Renderer2D::new(crate::Window::new());
+		let texture = atlas.upload(&mut renderer);
+		let sprite = atlas.sprite(texture, "coin").unwrap();
+		assert_eq!(sprite.source, atlas.region("coin"));
+		assert!(atlas.sprite(texture, "missing").is_none());
+	}
+
+	#[test]
+	fn atlas_metadata() {
+		let regions = parse_regions("# atlas\nplayer one = 1 2 16 24\n\ncoin = 20 2 8 8\n").unwrap();
+		assert_eq!(regions, vec![(String::from("player one"), Rect2D::from(1, 2, 16, 24)), (String::from("coin"), Rect2D::from(20, 2, 8, 8))]);
+		assert!(parse_regions("coin = 1 2 3").is_err());
+		assert!(parse_regions("coin 1 2 3 4").is_err());
+
+		let atlas = Atlas { image: RgbaImage::new(1, 1), regions };
+		assert_eq!(parse_regions(&atlas.to_metadata()).unwrap(), atlas.regions);
+	}
+
+	#[test]
+	fn atlas_save_load() {
+		let dir = std::env::temp_dir().join(format!("engine_atlas_save_load_{}", std::process::id()));
+		fs::create_dir_all(&dir).unwrap();
+		filled(3, 5, [0, 255, 0, 255]).save(dir.join("leaf.png")).unwrap();
+		filled(6, 2, [0, 0, 255, 255]).save(dir.join("water.png")).unwrap();
+		fs::write(dir.join("notes.txt"), "not an image").unwrap();
+
+		let atlas = AtlasBuilder::from_dir(&dir).unwrap().build().unwrap();
+		atlas.save(dir.join("out.png"), dir.join("out.txt")).unwrap();
+		let loaded = Atlas::load(dir.join("out.png"), dir.join("out.txt")).unwrap();
+		fs::remove_dir_all(&dir).unwrap();
+
+		assert_eq!(atlas.names(), vec!["leaf", "water"]);
+		assert_eq!(loaded.image, atlas.image);
+		assert_eq!(loaded.region("water"), atlas.region("water"));
+	}
+}
@@ -0,0 +1,270 @@
+use super::{Renderer2D, Texture, Vertex};
+use crate::{Color, Window, Math::Matrix4x4};
+
+use std::collections::HashMap;
+
+/// Vertex shader used by every backend that runs GLSL.
+pub const VERTEX_SHADER: &str = include_str!("../../shaders/VertexShader.vert");
+/// Fragment shader used by every backend that runs GLSL.
+pub const FRAGMENT_SHADER: &str = include_str!("../../shaders/FragmentShader.frag");
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BufferId(pub u32);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct TextureId(pub u32);
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct ProgramId(pub u32);
+
+/// The graphics calls the engine needs, so drawing does not depend on a
+/// particular graphics API.
+///
+/// `GlBackend` talks to OpenGL 3.3 core, `SoftwareRenderer` rasterizes into
+/// an image and `RecordingBackend` only remembers what it was asked to do.
+pub trait Backend {
+	fn create_program(&mut self, vertex: &str, fragment: &str) -> ProgramId;
+	fn set_projection(&mut self, program: ProgramId, projection: Matrix4x4);
+
+	fn create_buffer(&mut self) -> BufferId;
+	/// Replaces the contents of `buffer`.
+	fn upload_vertices(&mut self, buffer: BufferId, vertices: &[Vertex]);
+
+	/// Creates a texture from tightly packed RGBA8 pixels, row by row from the top.
+	fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> TextureId;
+	fn delete_texture(&mut self, texture: TextureId);
+	/// Selects the texture sampled by the following draw calls, if any.
+	fn bind_texture(&mut self, texture: Option<TextureId>);
+
+	/// Sets the size in pixels of the area drawn into.
+	fn resize(&mut self, width: u32, height: u32);
+	fn clear(&mut self, color: Color);
+	/// Draws `count` vertices of `buffer` as triangles, starting at vertex `start`.
+	fn draw_triangles(&mut self, program: ProgramId, buffer: BufferId, start: usize, count: usize);
+}
+
+/// Draws the output of a `Renderer2D` through a `Backend`, holding on to
+/// the program, vertex buffer and textures it created there.
+pub struct Presenter<B: Backend> {
+	pub backend: B,
+	program: ProgramId,
+	buffer: BufferId,
+	white: TextureId,
+	textures: HashMap<Texture, TextureId>,
+}
+
+impl<B: Backend> Presenter<B> {
+	pub fn new(mut backend: B) -> Presenter<B> {
+		let program = backend.create_program(VERTEX_SHADER, FRAGMENT_SHADER);
+		let buffer = backend.create_buffer();
+		// untextured batches sample a single white pixel, so one shader serves both
+		let white = backend.create_texture(1, 1, &[255, 255, 255, 255]);
+		Presenter { backend, program, buffer, white, textures: HashMap::new() }
+	}
+
+	/// Uploads new textures, then clears the frame and draws everything
+	/// queued on `renderer`, one draw call per batch.
+	///
+	/// Textures unloaded since the last frame are deleted only after it is
+	/// drawn, as batches queued earlier in the frame may still use them.
+	pub fn present(&mut self, renderer: &mut Renderer2D, window: Window) {
+		let (uploads, unloads) = renderer.take_textures();
+		for (texture, image) in uploads {
+			let id = self.backend.create_texture(image.width(), image.height(), image.as_raw());
+			self.textures.insert(texture, id);
+		}
+
+		self.backend.clear(renderer.clear_color);
+		if !renderer.vertices().is_empty() {
+			self.backend.upload_vertices(self.buffer, renderer.vertices());
+			self.backend.set_projection(self.program, renderer.camera.get_projection_matrix(window));
+			for batch in renderer.batches() {
+				let texture = batch.texture.and_then(|texture| self.textures.get(&texture).copied());
+				self.backend.bind_texture(Some(texture.unwrap_or(self.white)));
+				self.backend.draw_triangles(self.program, self.buffer, batch.start, batch.count);
+			}
+		}
+
+		for texture in unloads {
+			if let Some(id) = self.textures.remove(&texture) {
+				self.backend.delete_texture(id);
+			}
+		}
+	}
+}
+
+/// A call made on a `RecordingBackend`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Command {
+	CreateProgram(ProgramId),
+	SetProjection(ProgramId, [f32; 16]),
+	CreateBuffer(BufferId),
+	UploadVertices(BufferId, Vec<Vertex>),
+	CreateTexture(TextureId, u32, u32),
+	DeleteTexture(TextureId),
+	BindTexture(Option<TextureId>),
+	Resize(u32, u32),
+	Clear(Color),
+	DrawTriangles(ProgramId, BufferId, usize, usize),
+}
+
+/// A backend that draws nothing and records every call, for tests.
+#[derive(Clone, Debug, Default)]
+pub struct RecordingBackend {
+	pub commands: Vec<Command>,
+	next_id: u32,
+}
+
+impl RecordingBackend {
+	pub fn new() -> RecordingBackend {
+		RecordingBackend { commands: Vec::new(), next_id: 0 }
+	}
+
+	/// Number of draw calls recorded so far.
+	pub fn draw_calls(&self) -> usize {
+		self.commands.iter().filter(|command| matches!(command, Command::DrawTriangles(..))).count()
+	}
+
+	fn id(&mut self) -> u32 {
+		self.next_id += 1;
+		self.next_id
+	}
+}
+
+impl Backend for RecordingBackend {
+	fn create_program(&mut self, _vertex: &str, _fragment: &str) -> ProgramId {
+		let program = ProgramId(self.id());
+		self.commands.push(Command::CreateProgram(program));
+		program
+	}
+
+	fn set_projection(&mut self, program: ProgramId, projection: Matrix4x4) {
+		self.commands.push(Command::SetProjection(program, projection.array()));
+	}
+
+	fn create_buffer(&mut self) -> BufferId {
+		let buffer = BufferId(self.id());
+		self.commands.push(Command::CreateBuffer(buffer));
+		buffer
+	}
+
+	fn upload_vertices(&mut self, buffer: BufferId, vertices: &[Vertex]) {
+		self.commands.push(Command::UploadVertices(buffer, vertices.to_vec()));
+	}
+
+	fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> TextureId {
+		assert_eq!(pixels.len(), (width * height * 4) as usize);
+		let texture = TextureId(self.id());
+		self.commands.push(Command::CreateTexture(texture, width, height));
+		texture
+	}
+
+	fn delete_texture(&mut self, texture: TextureId) {
+		self.commands.push(Command::DeleteTexture(texture));
+	}
+
+	fn bind_texture(&mut self, texture: Option<TextureId>) {
+		self.commands.push(Command::BindTexture(texture));
+	}
+
+	fn resize(&mut self, width: u32, height: u32) {
+		self.commands.push(Command::Resize(width, height));
+	}
+
+	fn clear(&mut self, color: Color) {
+		self.commands.push(Command::Clear(color));
+	}
+
+	fn draw_triangles(&mut self, program: ProgramId, buffer: BufferId, start: usize, count: usize) {
+		self.commands.push(Command::DrawTriangles(program, buffer, start, count));
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Sprite2D, Transform2D, Visual, Render::{Font, TextStyle, MAX_BATCH_VERTICES}};
+	use image::RgbaImage;
+
+	#[test]
+	fn presenter_new() {
+		let presenter = Presenter::new(RecordingBackend::new());
+		assert_eq!(presenter.backend.commands, vec![
+			Command::CreateProgram(ProgramId(1)),
+			Command::CreateBuffer(BufferId(2)),
+			Command::CreateTexture(TextureId(3), 1, 1),
+		]);
+	}
+
+	#[test]
+	fn presenter_present_empty() {
+		let mut presenter = Presenter::new(RecordingBackend::new());
+		presenter.backend.commands.clear();
+		let mut renderer = Renderer2D::new(Window::new());
+		presenter.present(&mut renderer, Window::new());
+		assert_eq!(presenter.backend.commands, vec![Command::Clear(Window::new().color)]);
+	}
+
+	#[test]
+	fn presenter_present_batches() {
+		let mut presenter = Presenter::new(RecordingBackend::new());
+		let mut renderer = Renderer2D::new(Window::new());
+		let rectangle = Visual::Rectangle::new(1.0, 1.0);
+		for _ in 0..(MAX_BATCH_VERTICES / 6 + 1) {
+			renderer.draw_rectangle(&rectangle, Transform2D::new());
+		}
+		presenter.present(&mut renderer, Window::new());
+		assert_eq!(presenter.backend.draw_calls(), 2);
+		assert_eq!(presenter.backend.commands.last(), Some(&Command::DrawTriangles(ProgramId(1), BufferId(2), MAX_BATCH_VERTICES, 6)));
+	}
+
+	#[test]
+	fn presenter_present_textures() {
+		let mut presenter = Presenter::new(RecordingBackend::new());
+		presenter.backend.commands.clear();
+		let mut renderer = Renderer2D::new(Window::new());
+		let texture = renderer.create_texture(RgbaImage::new(2, 2));
+		renderer.draw_sprite(&Sprite2D::new(texture), Transform2D::new());
+		renderer.draw_rectangle(&Visual::Rectangle::new(1.0, 1.0), Transform2D::new());
+		presenter.present(&mut renderer, Window::new());
+
+		let binds: Vec<&Command> = presenter.backend.commands.iter().filter(|command| matches!(command, Command::BindTexture(_))).collect();
+		assert_eq!(presenter.backend.commands[0], Command::CreateTexture(TextureId(4), 2, 2));
+		assert_eq!(binds, vec![&Command::BindTexture(Some(TextureId(4))), &Command::BindTexture(Some(TextureId(3)))]);
+
+		renderer.unload_texture(texture);
+		presenter.backend.commands.clear();
+		presenter.present(&mut renderer, Window::new());
+		assert_eq!(presenter.backend.commands.last(), Some(&Command::DeleteTexture(TextureId(4))));
+	}
+
+	#[test]
+	fn presenter_present_text_glyphs() {
+		// the second string adds glyphs, which replaces the atlas texture of the first
+		let mut presenter = Presenter::new(RecordingBackend::new());
+		presenter.backend.commands.clear();
+		let mut renderer = Renderer2D::new(Window::new());
+		let mut font = Font::load("resources/fonts/DejaVuSans.ttf").unwrap();
+		let style = TextStyle::new(64.0);
+		renderer.draw_text(&mut font, "AB", &style, Transform2D::new());
+		let first_uv = renderer.vertices()[0].uv;
+		renderer.draw_text(&mut font, &"CDEFGHIJKLMNOPQRSTUVWXYZ".repeat(2), &style, Transform2D::new());
+		assert_eq!(renderer.vertices()[0].uv, first_uv);
+		presenter.present(&mut renderer, Window::new());
+
+		let commands = &presenter.backend.commands;
+		let created: Vec<(TextureId, u32)> = commands.iter().filter_map(|command| match command {
+			Command::CreateTexture(id, _, height) => Some((*id, *height)),
+			_ => None,
+		}).collect();
+		assert_eq!(created.len(), 2);
+		assert!(created[1].1 > created[0].1, "the atlas should have grown");
+
+		let draws: Vec<usize> = commands.iter().enumerate().filter(|(_, command)| matches!(command, Command::DrawTriangles(..))).map(|(i, _)| i).collect();
+		assert_eq!(draws.len(), 2);
+		assert_eq!(commands[draws[0] - 1], Command::BindTexture(Some(created[0].0)));
+		assert_eq!(commands[draws[1] - 1], Command::BindTexture(Some(created[1].0)));
+		let deleted = commands.iter().position(|command| *command == Command::DeleteTexture(created[0].0)).unwrap();
+		assert!(deleted > draws[1]);
+	}
+}
@@ -0,0 +1,27 @@
+//! Batched 2D drawing.
+//!
+//! Games queue shapes on `Context::renderer` from `Game::draw`. The queued
+//! triangles are grouped into batches and handed once per frame to a
+//! `Backend` with as few draw calls as possible. Besides OpenGL there is
+//! `SoftwareRenderer`, which draws on the CPU for tests that cannot open a
+//! window, and `RecordingBackend`, which only records the calls.
+//!
+//! `AtlasBuilder` packs many images into one texture so sprites drawn
+//! from it stay in the same batch.
+//!
+//! `Font` rasterizes TrueType or bitmap glyphs into a glyph atlas on first
+//! use, and `Renderer2D::draw_text` lays out and draws strings with it.
+
+pub(crate) mod atlas;
+pub(crate) mod backend;
+pub(crate) mod opengl;
+pub(crate) mod renderer;
+pub(crate) mod software;
+pub(crate) mod text;
+
+pub use atlas::*;
+pub use backend::*;
+pub use opengl::*;
+pub use renderer::*;
+pub use software::*;
+pub use text::*;
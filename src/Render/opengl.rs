@@ -0,0 +1,151 @@
+use super::{Backend, BufferId, ProgramId, TextureId, Vertex};
+use crate::{Color, Math::Matrix4x4, gl::{self, types::*}, glfw, shader};
+
+use std::collections::HashMap;
+use std::mem;
+use std::os::raw::c_void;
+use std::ptr;
+
+/// OpenGL 3.3 core implementation of `Backend`. Every vertex buffer gets
+/// its own vertex array object laid out for `Vertex`.
+pub struct GlBackend {
+	programs: Vec<GLuint>,
+	vertex_arrays: HashMap<BufferId, GLuint>,
+	capacities: HashMap<BufferId, usize>,
+}
+
+impl GlBackend {
+	/// Loads the OpenGL functions of the context current on `window`.
+	pub fn new(window: &mut glfw::Window) -> GlBackend {
+		gl::load_with(|symbol| window.get_proc_address(symbol) as *const _);
+
+		unsafe {
+			gl::Enable(gl::BLEND);
+			// straight alpha for color, while destination alpha accumulates coverage
+			gl::BlendFuncSeparate(gl::SRC_ALPHA, gl::ONE_MINUS_SRC_ALPHA, gl::ONE, gl::ONE_MINUS_SRC_ALPHA);
+		}
+
+		GlBackend { programs: Vec::new(), vertex_arrays: HashMap::new(), capacities: HashMap::new() }
+	}
+}
+
+impl Backend for GlBackend {
+	fn create_program(&mut self, vertex: &str, fragment: &str) -> ProgramId {
+		let program = shader::Compile(vertex, fragment);
+		self.programs.push(program);
+		ProgramId(program)
+	}
+
+	fn set_projection(&mut self, program: ProgramId, projection: Matrix4x4) {
+		shader::Use(vec![program.0]);
+		shader::SetMatrix4x4(program.0, "projection", projection);
+	}
+
+	fn create_buffer(&mut self) -> BufferId {
+		unsafe {
+			let (mut vao, mut vbo) = (0, 0);
+			gl::GenVertexArrays(1, &mut vao);
+			gl::GenBuffers(1, &mut vbo);
+
+			gl::BindVertexArray(vao);
+			gl::BindBuffer(gl::ARRAY_BUFFER, vbo);
+
+			let stride = mem::size_of::<Vertex>() as GLsizei;
+			gl::VertexAttribPointer(0, 2, gl::FLOAT, gl::FALSE, stride, ptr::null());
+			gl::EnableVertexAttribArray(0);
+			gl::VertexAttribPointer(1, 4, gl::FLOAT, gl::FALSE, stride, mem::size_of::<[f32; 2]>() as *const c_void);
+			gl::EnableVertexAttribArray(1);
+			gl::VertexAttribPointer(2, 2, gl::FLOAT, gl::FALSE, stride, mem::size_of::<[f32; 6]>() as *const c_void);
+			gl::EnableVertexAttribArray(2);
+
+			gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+			gl::BindVertexArray(0);
+
+			self.vertex_arrays.insert(BufferId(vbo), vao);
+			self.capacities.insert(BufferId(vbo), 0);
+			BufferId(vbo)
+		}
+	}
+
+	fn upload_vertices(&mut self, buffer: BufferId, vertices: &[Vertex]) {
+		let capacity = self.capacities.entry(buffer).or_insert(0);
+		let size = mem::size_of_val(vertices) as GLsizeiptr;
+		unsafe {
+			gl::BindBuffer(gl::ARRAY_BUFFER, buffer.0);
+			if vertices.len() > *capacity {
+				// grow the buffer; it is reused as long as later frames fit
+				gl::BufferData(gl::ARRAY_BUFFER, size, vertices.as_ptr() as *const c_void, gl::DYNAMIC_DRAW);
+				*capacity = vertices.len();
+			} else {
+				gl::BufferSubData(gl::ARRAY_BUFFER, 0, size, vertices.as_ptr() as *const c_void);
+			}
+			gl::BindBuffer(gl::ARRAY_BUFFER, 0);
+		}
+	}
+
+	fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> TextureId {
+		assert_eq!(pixels.len(), (width * height * 4) as usize);
+		unsafe {
+			let mut texture = 0;
+			gl::GenTextures(1, &mut texture);
+			gl::BindTexture(gl::TEXTURE_2D, texture);
+			gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_S, gl::CLAMP_TO_EDGE as GLint);
+			gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_WRAP_T, gl::CLAMP_TO_EDGE as GLint);
+			gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::NEAREST as GLint);
+			gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as GLint);
+			gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+			gl::TexImage2D(
+				gl::TEXTURE_2D, 0, gl::RGBA8 as GLint, width as GLsizei, height as GLsizei, 0,
+				gl::RGBA, gl::UNSIGNED_BYTE, pixels.as_ptr() as *const c_void,
+			);
+			gl::BindTexture(gl::TEXTURE_2D, 0);
+			TextureId(texture)
+		}
+	}
+
+	fn delete_texture(&mut self, texture: TextureId) {
+		unsafe { gl::DeleteTextures(1, &texture.0) }
+	}
+
+	fn bind_texture(&mut self, texture: Option<TextureId>) {
+		unsafe {
+			gl::ActiveTexture(gl::TEXTURE0);
+			gl::BindTexture(gl::TEXTURE_2D, texture.map_or(0, |texture| texture.0));
+		}
+	}
+
+	fn resize(&mut self, width: u32, height: u32) {
+		unsafe { gl::Viewport(0, 0, width as GLsizei, height as GLsizei) }
+	}
+
+	fn clear(&mut self, color: Color) {
+		let [r, g, b, a] = color.unit_interval();
+		unsafe {
+			gl::ClearColor(r, g, b, a);
+			gl::Clear(gl::COLOR_BUFFER_BIT);
+		}
+	}
+
+	fn draw_triangles(&mut self, program: ProgramId, buffer: BufferId, start: usize, count: usize) {
+		shader::Use(vec![program.0]);
+		unsafe {
+			gl::BindVertexArray(self.vertex_arrays[&buffer]);
+			gl::DrawArrays(gl::TRIANGLES, start as GLint, count as GLsizei);
+			gl::BindVertexArray(0);
+		}
+	}
+}
+
+impl Drop for GlBackend {
+	fn drop(&mut self) {
+		unsafe {
+			for (buffer, vao) in self.vertex_arrays.iter() {
+				gl::DeleteBuffers(1, &buffer.0);
+				gl::DeleteVertexArrays(1, vao);
+			}
+			for program in self.programs.iter() {
+				gl::DeleteProgram(*program);
+			}
+		}
+	}
+}
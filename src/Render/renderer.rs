@@ -0,0 +1,341 @@
+use crate::{Camera2D, Color, Position2D, Size2D, Sprite2D, Transform2D, Window, Math::Vector2D, Visual};
+use super::{Font, TextLayout, TextStyle};
+use image::RgbaImage;
+
+use std::path::Path;
+
+/// Most vertices a single batch, and therefore a single draw call, holds.
+pub const MAX_BATCH_VERTICES: usize = 6 * 8192;
+
+/// A vertex as uploaded to the GPU: world position, RGBA color (0 to 1)
+/// and texture coordinates (0 to 1, origin at the top left of the image).
+#[repr(C)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Vertex {
+	pub position: [f32; 2],
+	pub color: [f32; 4],
+	pub uv: [f32; 2],
+}
+
+impl Vertex {
+	pub fn from(position: Position2D, color: Color) -> Vertex {
+		Vertex { position: [position.x, position.y], color: color.unit_interval(), uv: [0.0, 0.0] }
+	}
+
+	pub fn textured(position: Position2D, color: Color, uv: Position2D) -> Vertex {
+		Vertex { position: [position.x, position.y], color: color.unit_interval(), uv: [uv.x, uv.y] }
+	}
+}
+
+/// Handle to an image handed to the backend through `Renderer2D::create_texture`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Texture {
+	id: u32,
+	pub width: u32,
+	pub height: u32,
+}
+
+impl Texture {
+	pub fn id(self) -> u32 {
+		self.id
+	}
+
+	pub fn size(self) -> Size2D {
+		Size2D::from(self.width, self.height)
+	}
+}
+
+/// A run of vertices drawn with a single draw call. Batches without a
+/// texture are drawn in plain vertex colors.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Batch {
+	pub start: usize,
+	pub count: usize,
+	pub texture: Option<Texture>,
+}
+
+/// Collects the triangles drawn during a frame into as few batches as possible.
+///
+/// `Renderer2D` does not talk to the GPU itself: the engine hands the
+/// finished batches to a backend after `Game::draw`, which keeps the
+/// drawing API usable in headless runs.
+#[derive(Clone, Debug)]
+pub struct Renderer2D {
+	pub camera: Camera2D,
+	pub clear_color: Color,
+	vertices: Vec<Vertex>,
+	batches: Vec<Batch>,
+	next_texture: u32,
+	uploads: Vec<(Texture, RgbaImage)>,
+	unloads: Vec<Texture>,
+}
+
+impl Renderer2D {
+	/// A renderer whose camera shows the window with the world origin at its top left corner.
+	pub fn new(window: Window) -> Renderer2D {
+		Renderer2D {
+			camera: Camera2D::from(Vector2D::from(window.size.x / 2.0, window.size.y / 2.0), 1.0),
+			clear_color: window.color,
+			vertices: Vec::new(),
+			batches: Vec::new(),
+			next_texture: 0,
+			uploads: Vec::new(),
+			unloads: Vec::new(),
+		}
+	}
+
+	/// Loads an image file as a texture. The pixels reach the backend when
+	/// the frame is presented, so this can be called before the window exists.
+	pub fn load_texture<P: AsRef<Path>>(&mut self, path: P) -> image::ImageResult<Texture> {
+		Ok(self.create_texture(image::open(path)?.into_rgba8()))
+	}
+
+	pub fn create_texture(&mut self, image: RgbaImage) -> Texture {
+		self.next_texture += 1;
+		let texture = Texture { id: self.next_texture, width: image.width(), height: image.height() };
+		self.uploads.push((texture, image));
+		texture
+	}
+
+	/// Frees the backend copy of `texture` once the frame is presented.
+	pub fn unload_texture(&mut self, texture: Texture) {
+		self.unloads.push(texture);
+	}
+
+	/// Takes the textures created and unloaded since the last call.
+	pub(crate) fn take_textures(&mut self) -> (Vec<(Texture, RgbaImage)>, Vec<Texture>) {
+		(std::mem::take(&mut self.uploads), std::mem::take(&mut self.unloads))
+	}
+
+	/// Forgets everything drawn during the previous frame.
+	pub fn clear(&mut self) {
+		self.vertices.clear();
+		self.batches.clear();
+	}
+
+	pub fn vertices(&self) -> &[Vertex] {
+		&self.vertices
+	}
+
+	pub fn batches(&self) -> &[Batch] {
+		&self.batches
+	}
+
+	/// Number of draw calls a backend needs for this frame.
+	pub fn draw_calls(&self) -> usize {
+		self.batches.len()
+	}
+
+	/// Draws a list of triangles, three vertices each, optionally textured.
+	pub fn draw_vertices(&mut self, vertices: &[Vertex], texture: Option<Texture>) {
+		assert_eq!(vertices.len() % 3, 0, "vertices must form whole triangles");
+		for triangle in vertices.chunks(3) {
+			self.reserve(3, texture);
+			self.vertices.extend_from_slice(triangle);
+		}
+	}
+
+	/// Draws a sprite as a textured quad, placed by `transform`.
+	pub fn draw_sprite(&mut self, sprite: &Sprite2D, transform: Transform2D) {
+		let corners = sprite.corners();
+		let uvs = sprite.uvs();
+		let quad: Vec<Vertex> = [0, 1, 2, 0, 2, 3].iter()
+			.map(|&i| Vertex::textured(transform.apply(corners[i]), sprite.color, uvs[i]))
+			.collect();
+		self.draw_vertices(&quad, Some(sprite.texture));
+	}
+
+	/// Draws `text` in world space with the top left corner of its text box
+	/// at the origin of `transform`. One glyph is one textured quad, so a
+	/// string stays in one batch.
+	pub fn draw_text(&mut self, font: &mut Font, text: &str, style: &TextStyle, transform: Transform2D) {
+		let layout = font.layout(text, style);
+		self.draw_glyphs(font, &layout, style, |point| transform.apply(point));
+	}
+
+	/// Draws `text` at `position` in window pixels, with `style.size` in
+	/// pixels whatever the camera zoom, e.g. for a HUD or FPS counter.
+	pub fn draw_text_screen(&mut self, font: &mut Font, text: &str, style: &TextStyle, window: Window, position: Position2D) {
+		let layout = font.layout(text, style);
+		let camera = self.camera;
+		self.draw_glyphs(font, &layout, style, |point| {
+			let world = camera.screen_to_world(window, Vector2D::from(position.x + point.x, position.y + point.y));
+			Position2D { x: world.x, y: world.y }
+		});
+	}
+
+	fn draw_glyphs<F: Fn(Position2D) -> Position2D>(&mut self, font: &mut Font, layout: &TextLayout, style: &TextStyle, place: F) {
+		let (texture, atlas) = font.texture(style.size, self);
+		let mut quads = Vec::with_capacity(layout.glyphs.len() * 6);
+		for glyph in &layout.glyphs {
+			let (left, top) = (glyph.position.x, glyph.position.y);
+			let (right, bottom) = (left + glyph.size.x, top + glyph.size.y);
+			let corners = [(left, top), (right, top), (right, bottom), (left, bottom)];
+			let source = glyph.source;
+			let uvs = [
+				(source.left(), source.top()),
+				(source.right(), source.top()),
+				(source.right(), source.bottom()),
+				(source.left(), source.bottom()),
+			];
+			for &i in &[0, 1, 2, 0, 2, 3] {
+				let corner = place(Position2D { x: corners[i].0, y: corners[i].1 });
+				let uv = Position2D { x: uvs[i].0 / atlas.x, y: uvs[i].1 / atlas.y };
+				quads.push(Vertex::textured(corner, style.color, uv));
+			}
+		}
+		self.draw_vertices(&quads, Some(texture));
+	}
+
+	/// Draws any `Visual` shape, placed by `transform`.
+	pub fn draw(&mut self, shape: &Visual::Shape2D, transform: Transform2D) {
+		self.draw_triangles(&shape.tessellate(), shape.color(), transform);
+	}
+
+	pub fn draw_triangle(&mut self, triangle: &Visual::Triangle, transform: Transform2D) {
+		self.draw_triangles(&triangle.tessellate(), triangle.color, transform);
+	}
+
+	/// Draws a rectangle centered on its offset.
+	pub fn draw_rectangle(&mut self, rectangle: &Visual::Rectangle, transform: Transform2D) {
+		self.draw_triangles(&rectangle.tessellate(), rectangle.color, transform);
+	}
+
+	pub fn draw_circle(&mut self, circle: &Visual::Circle, transform: Transform2D) {
+		self.draw_triangles(&circle.tessellate(), circle.color, transform);
+	}
+
+	pub fn draw_capsule(&mut self, capsule: &Visual::Capsule2D, transform: Transform2D) {
+		self.draw_triangles(&capsule.tessellate(), capsule.color, transform);
+	}
+
+	pub fn draw_polygon(&mut self, polygon: &Visual::Polygon2D, transform: Transform2D) {
+		self.draw_triangles(&polygon.tessellate(), polygon.color, transform);
+	}
+
+	/// Draws a triangle list given in local space in a single color.
+	fn draw_triangles(&mut self, points: &[Position2D], color: Color, transform: Transform2D) {
+		for triangle in points.chunks_exact(3) {
+			self.reserve(3, None);
+			for point in triangle {
+				self.vertices.push(Vertex::from(transform.apply(*point), color));
+			}
+		}
+	}
+
+	/// Makes sure the next `count` vertices land in a batch that can hold
+	/// them and samples `texture`.
+	fn reserve(&mut self, count: usize, texture: Option<Texture>) {
+		match self.batches.last_mut() {
+			Some(batch) if batch.texture == texture && batch.count + count <= MAX_BATCH_VERTICES => batch.count += count,
+			_ => self.batches.push(Batch { start: self.vertices.len(), count, texture }),
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Rotation2D, Scale2D};
+
+	#[test]
+	fn renderer_new() {
+		let window = Window::new();
+		let renderer = Renderer2D::new(window);
+		assert_eq!(renderer.camera, Camera2D::from(Vector2D::from(428.0, 241.0), 1.0));
+		assert_eq!(renderer.clear_color, window.color);
+		assert_eq!(renderer.draw_calls(), 0);
+	}
+
+	#[test]
+	fn renderer_draw_rectangle() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let mut rectangle = Visual::Rectangle::new(2.0, 4.0);
+		rectangle.color = Color::RED;
+		renderer.draw_rectangle(&rectangle, Transform2D::from(Position2D::from(10, 10), Rotation2D::new(), Scale2D::new()));
+		assert_eq!(renderer.vertices().len(), 6);
+		assert_eq!(renderer.vertices()[0], Vertex { position: [9.0, 8.0], color: [1.0, 0.0, 0.0, 1.0], uv: [0.0, 0.0] });
+		assert_eq!(renderer.vertices()[2].position, [11.0, 12.0]);
+		assert_eq!(renderer.batches(), &[Batch { start: 0, count: 6, texture: None }]);
+	}
+
+	#[test]
+	fn renderer_draw_triangle() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let mut triangle = Visual::Triangle::new();
+		triangle.offset = Position2D::from(1, 1);
+		renderer.draw_triangle(&triangle, Transform2D::new());
+		let positions: Vec<[f32; 2]> = renderer.vertices().iter().map(|vertex| vertex.position).collect();
+		assert_eq!(positions, vec![[1.0, 1.0], [2.0, 1.0], [1.0, 0.0]]);
+	}
+
+	#[test]
+	fn renderer_batches() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let rectangle = Visual::Rectangle::new(1.0, 1.0);
+		for _ in 0..(MAX_BATCH_VERTICES / 6 + 1) {
+			renderer.draw_rectangle(&rectangle, Transform2D::new());
+		}
+		assert_eq!(renderer.draw_calls(), 2);
+		assert_eq!(renderer.batches()[1], Batch { start: MAX_BATCH_VERTICES, count: 6, texture: None });
+		renderer.clear();
+		assert_eq!(renderer.draw_calls(), 0);
+		assert!(renderer.vertices().is_empty());
+	}
+
+	#[test]
+	fn renderer_draw_shapes() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let mut circle = Visual::Circle::new(5.0);
+		circle.segments = 16;
+		circle.color = Color::BLUE;
+		let shape = Visual::Shape2D::Circle(circle);
+		renderer.draw(&shape, Transform2D::from(Position2D::from(10, 0), Rotation2D::new(), Scale2D::from(2, 2)));
+		assert_eq!(renderer.vertices().len(), 14 * 3);
+		assert!(renderer.vertices().iter().all(|vertex| vertex.color == [0.0, 0.0, 1.0, 1.0]));
+		assert_eq!(renderer.vertices()[0].position, [20.0, 0.0]);
+
+		let polygon = Visual::Polygon2D::new(vec![Position2D::from(0, 0), Position2D::from(2, 0), Position2D::from(1, 1), Position2D::from(2, 2), Position2D::from(0, 2)]);
+		renderer.draw_polygon(&polygon, Transform2D::new());
+		renderer.draw_capsule(&Visual::Capsule2D::new(1.0, 3.0), Transform2D::new());
+		assert_eq!(renderer.vertices().len() % 3, 0);
+		assert_eq!(renderer.draw_calls(), 1);
+	}
+
+	#[test]
+	#[should_panic]
+	fn renderer_draw_vertices_partial() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let vertex = Vertex::from(Position2D::new(), Color::WHITE);
+		renderer.draw_vertices(&[vertex, vertex], None);
+	}
+
+	#[test]
+	fn renderer_textures() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let first = renderer.create_texture(RgbaImage::new(4, 2));
+		let second = renderer.create_texture(RgbaImage::new(1, 1));
+		assert_ne!(first, second);
+		assert_eq!(first.size(), Size2D::from(4, 2));
+		renderer.unload_texture(first);
+		let (uploads, unloads) = renderer.take_textures();
+		assert_eq!(uploads.iter().map(|(texture, _)| *texture).collect::<Vec<_>>(), vec![first, second]);
+		assert_eq!(unloads, vec![first]);
+		assert_eq!(renderer.take_textures().0.len(), 0);
+	}
+
+	#[test]
+	fn renderer_draw_sprite() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let texture = renderer.create_texture(RgbaImage::new(4, 2));
+		let sprite = Sprite2D::new(texture);
+		renderer.draw_sprite(&sprite, Transform2D::from(Position2D::from(10, 10), Rotation2D::new(), Scale2D::new()));
+		renderer.draw_sprite(&sprite, Transform2D::new());
+		renderer.draw_rectangle(&Visual::Rectangle::new(1.0, 1.0), Transform2D::new());
+		assert_eq!(renderer.batches(), &[Batch { start: 0, count: 12, texture: Some(texture) }, Batch { start: 12, count: 6, texture: None }]);
+		assert_eq!(renderer.vertices()[0].position, [8.0, 9.0]);
+		assert_eq!(renderer.vertices()[0].uv, [0.0, 0.0]);
+		assert_eq!(renderer.vertices()[2].position, [12.0, 11.0]);
+		assert_eq!(renderer.vertices()[2].uv, [1.0, 1.0]);
+	}
+}
@@ -0,0 +1,365 @@
+use super::{Backend, BufferId, ProgramId, TextureId, Vertex};
+use crate::{Color, Window, Math::{Matrix4x4, Vector2D}};
+use image::{Rgba, RgbaImage};
+
+use std::collections::HashMap;
+use std::path::Path;
+
+/// `Backend` that rasterizes on the CPU into an RGBA image.
+///
+/// Produces the same picture as the OpenGL backend without needing a GPU,
+/// so frames can be checked in tests with `compare_golden`.
+#[derive(Clone, Debug)]
+pub struct SoftwareRenderer {
+	pub image: RgbaImage,
+	projection: Matrix4x4,
+	buffers: Vec<Vec<Vertex>>,
+	textures: HashMap<TextureId, RgbaImage>,
+	texture: Option<TextureId>,
+	next_texture: u32,
+}
+
+impl SoftwareRenderer {
+	/// A renderer drawing into an image the size of `window`.
+	pub fn new(window: Window) -> SoftwareRenderer {
+		SoftwareRenderer {
+			image: RgbaImage::new(window.size.x as u32, window.size.y as u32),
+			projection: Matrix4x4::new(),
+			buffers: Vec::new(),
+			textures: HashMap::new(),
+			texture: None,
+			next_texture: 0,
+		}
+	}
+
+	pub fn save<P: AsRef<Path>>(&self, path: P) -> image::ImageResult<()> {
+		self.image.save(path)
+	}
+}
+
+impl Backend for SoftwareRenderer {
+	fn create_program(&mut self, _vertex: &str, _fragment: &str) -> ProgramId {
+		ProgramId(0)
+	}
+
+	fn set_projection(&mut self, _program: ProgramId, projection: Matrix4x4) {
+		self.projection = projection;
+	}
+
+	fn create_buffer(&mut self) -> BufferId {
+		self.buffers.push(Vec::new());
+		BufferId(self.buffers.len() as u32 - 1)
+	}
+
+	fn upload_vertices(&mut self, buffer: BufferId, vertices: &[Vertex]) {
+		self.buffers[buffer.0 as usize] = vertices.to_vec();
+	}
+
+	fn create_texture(&mut self, width: u32, height: u32, pixels: &[u8]) -> TextureId {
+		let image = RgbaImage::from_raw(width, height, pixels.to_vec()).expect("texture pixels do not match its size");
+		self.next_texture += 1;
+		self.textures.insert(TextureId(self.next_texture), image);
+		TextureId(self.next_texture)
+	}
+
+	fn delete_texture(&mut self, texture: TextureId) {
+		self.textures.remove(&texture);
+	}
+
+	fn bind_texture(&mut self, texture: Option<TextureId>) {
+		self.texture = texture;
+	}
+
+	fn resize(&mut self, width: u32, height: u32) {
+		self.image = RgbaImage::new(width, height);
+	}
+
+	fn clear(&mut self, color: Color) {
+		let [r, g, b, a] = color.unit_interval();
+		let clear = Rgba([to_byte(r), to_byte(g), to_byte(b), to_byte(a)]);
+		for pixel in self.image.pixels_mut() {
+			*pixel = clear;
+		}
+	}
+
+	fn draw_triangles(&mut self, _program: ProgramId, buffer: BufferId, start: usize, count: usize) {
+		let textures = &self.textures;
+		let texture = self.texture.and_then(|texture| textures.get(&texture));
+		for triangle in self.buffers[buffer.0 as usize][start..start + count].chunks_exact(3) {
+			let mut corners = [(Vector2D::new(), triangle[0]); 3];
+			for (corner, vertex) in corners.iter_mut().zip(triangle.iter()) {
+				*corner = (project(self.projection, &self.image, vertex.position), *vertex);
+			}
+			fill_triangle(&mut self.image, texture, corners);
+		}
+	}
+}
+
+/// Compares `image` against the PNG at `path`, allowing every channel to be
+/// off by `tolerance`.
+///
+/// On a mismatch the rendered image is written next to the golden file as
+/// `<name>.actual.png`. Setting the `ENGINE_BLESS` environment variable
+/// overwrites the golden file with `image` instead.
+pub fn compare_golden<P: AsRef<Path>>(image: &RgbaImage, path: P, tolerance: u8) -> Result<(), String> {
+	let path = path.as_ref();
+	if std::env::var_os("ENGINE_BLESS").is_some() {
+		return image.save(path).map_err(|e| format!("could not write golden '{}': {}", path.display(), e));
+	}
+
+	let golden = image::open(path)
+		.map_err(|e| format!("could not open golden '{}': {}", path.display(), e))?
+		.into_rgba8();
+
+	let result = if golden.dimensions() != image.dimensions() {
+		Err(format!("'{}' is {:?} but the frame is {:?}", path.display(), golden.dimensions(), image.dimensions()))
+	} else {
+		let differing = golden.pixels().zip(image.pixels())
+			.filter(|(a, b)| a.0.iter().zip(b.0.iter()).any(|(x, y)| x.max(y) - x.min(y) > tolerance))
+			.count();
+		match differing {
+			0 => Ok(()),
+			n => Err(format!("{} pixels differ from '{}'", n, path.display())),
+		}
+	};
+
+	if result.is_err() {
+		let _ = image.save(path.with_extension("actual.png"));
+	}
+	result
+}
+
+/// Maps a world position through `projection` to pixel coordinates of `image`.
+fn project(projection: Matrix4x4, image: &RgbaImage, position: [f32; 2]) -> Vector2D {
+	let m = projection.identity;
+	let (x, y) = (position[0], position[1]);
+	let w = m[0][3] * x + m[1][3] * y + m[3][3];
+	let ndc_x = (m[0][0] * x + m[1][0] * y + m[3][0]) / w;
+	let ndc_y = (m[0][1] * x + m[1][1] * y + m[3][1]) / w;
+	Vector2D::from((ndc_x + 1.0) / 2.0 * image.width() as f32, (1.0 - ndc_y) / 2.0 * image.height() as f32)
+}
+
+/// Rasterizes one triangle given in pixel coordinates, sampling pixel
+/// centers. Shared edges follow the top-left rule, so no pixel is blended twice.
+fn fill_triangle(image: &mut RgbaImage, texture: Option<&RgbaImage>, mut corners: [(Vector2D, Vertex); 3]) {
+	let mut area = edge(corners[0].0, corners[1].0, corners[2].0);
+	if area == 0.0 {
+		return;
+	}
+	if area < 0.0 {
+		corners.swap(1, 2);
+		area = -area;
+	}
+	let points = [corners[0].0, corners[1].0, corners[2].0];
+
+	let (width, height) = (image.width() as f32, image.height() as f32);
+	let min_x = points.iter().map(|p| p.x).fold(f32::MAX, f32::min).max(0.0).floor() as u32;
+	let min_y = points.iter().map(|p| p.y).fold(f32::MAX, f32::min).max(0.0).floor() as u32;
+	let max_x = points.iter().map(|p| p.x).fold(f32::MIN, f32::max).min(width).ceil() as u32;
+	let max_y = points.iter().map(|p| p.y).fold(f32::MIN, f32::max).min(height).ceil() as u32;
+
+	let edges = [(1, 2), (2, 0), (0, 1)];
+	let top_left: Vec<bool> = edges.iter().map(|&(a, b)| is_top_left(points[a], points[b])).collect();
+
+	for y in min_y..max_y.min(image.height()) {
+		for x in min_x..max_x.min(image.width()) {
+			let center = Vector2D::from(x as f32 + 0.5, y as f32 + 0.5);
+			let mut weights = [0.0; 3];
+			let mut inside = true;
+			for (i, &(a, b)) in edges.iter().enumerate() {
+				weights[i] = edge(points[a], points[b], center) / area;
+				if weights[i] < 0.0 || (weights[i] == 0.0 && !top_left[i]) {
+					inside = false;
+					break;
+				}
+			}
+			if !inside {
+				continue;
+			}
+
+			let mut color = [0.0; 4];
+			for (channel, value) in color.iter_mut().enumerate() {
+				*value = (0..3).map(|i| corners[i].1.color[channel] * weights[i]).sum();
+			}
+			if let Some(texture) = texture {
+				let u: f32 = (0..3).map(|i| corners[i].1.uv[0] * weights[i]).sum();
+				let v: f32 = (0..3).map(|i| corners[i].1.uv[1] * weights[i]).sum();
+				let texel = sample(texture, u, v);
+				for (value, texel) in color.iter_mut().zip(texel.0.iter()) {
+					*value *= *texel as f32 / 255.0;
+				}
+			}
+			blend(image.get_pixel_mut(x, y), color);
+		}
+	}
+}
+
+/// Nearest-neighbour lookup with clamped coordinates, like `GL_NEAREST` and `GL_CLAMP_TO_EDGE`.
+/// An empty texture has no texel to clamp to and samples as transparent.
+fn sample(texture: &RgbaImage, u: f32, v: f32) -> Rgba<u8> {
+	if texture.width() == 0 || texture.height() == 0 {
+		return Rgba([0, 0, 0, 0]);
+	}
+	let x = ((u * texture.width() as f32).floor().max(0.0) as u32).min(texture.width() - 1);
+	let y = ((v * texture.height() as f32).floor().max(0.0) as u32).min(texture.height() - 1);
+	*texture.get_pixel(x, y)
+}
+
+fn edge(a: Vector2D, b: Vector2D, p: Vector2D) -> f32 {
+	(b.x - a.x) * (p.y - a.y) - (b.y - a.y) * (p.x - a.x)
+}
+
+/// With y pointing down and triangles wound so `edge` is positive inside,
+/// top edges run exactly horizontal to the right and left edges run up.
+fn is_top_left(a: Vector2D, b: Vector2D) -> bool {
+	(a.y == b.y && b.x < a.x) || b.y < a.y
+}
+
+/// Source-over blending of straight alpha, matching the blend function of `GlBackend`.
+fn blend(pixel: &mut Rgba<u8>, color: [f32; 4]) {
+	let alpha = color[3];
+	for (channel, (dst, src)) in pixel.0.iter_mut().zip(color.iter()).enumerate() {
+		let src_factor = if channel == 3 { 1.0 } else { alpha };
+		*dst = to_byte(src * src_factor + *dst as f32 / 255.0 * (1.0 - alpha));
+	}
+}
+
+fn to_byte(value: f32) -> u8 {
+	(value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Camera2D, Position2D, Rotation2D, Scale2D, Size2D, Sprite2D, Transform2D, Visual, Render::{Presenter, Renderer2D}};
+
+	fn window() -> Window {
+		Window::new().size(Size2D::from(64, 48)).color(Color::BLACK)
+	}
+
+	#[test]
+	fn software_clear() {
+		let mut renderer = Renderer2D::new(window());
+		renderer.clear_color = Color::RED;
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window()));
+		presenter.present(&mut renderer, window());
+		let software = presenter.backend;
+		assert_eq!(software.image.dimensions(), (64, 48));
+		assert!(software.image.pixels().all(|pixel| pixel.0 == [255, 0, 0, 255]));
+	}
+
+	#[test]
+	fn software_rectangle_pixels() {
+		let mut renderer = Renderer2D::new(window());
+		let mut rectangle = Visual::Rectangle::new(10.0, 4.0);
+		rectangle.color = Color::WHITE;
+		renderer.draw_rectangle(&rectangle, Transform2D::from(Position2D::from(10, 10), Rotation2D::new(), Scale2D::new()));
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window()));
+		presenter.present(&mut renderer, window());
+		let software = presenter.backend;
+
+		// covers exactly x 5..15 and y 8..12, with no seam along the diagonal
+		let covered = software.image.enumerate_pixels().filter(|(_, _, pixel)| pixel.0 == [255, 255, 255, 255]).count();
+		assert_eq!(covered, 40);
+		assert_eq!(software.image.get_pixel(5, 8).0, [255, 255, 255, 255]);
+		assert_eq!(software.image.get_pixel(14, 11).0, [255, 255, 255, 255]);
+		assert_eq!(software.image.get_pixel(15, 11).0, [0, 0, 0, 255]);
+	}
+
+	#[test]
+	fn software_blend() {
+		let mut renderer = Renderer2D::new(window());
+		let mut rectangle = Visual::Rectangle::new(64.0, 48.0);
+		rectangle.color = Color::from(255, 255, 255, 51);
+		renderer.draw_rectangle(&rectangle, Transform2D::from(Position2D::from(32, 24), Rotation2D::new(), Scale2D::new()));
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window()));
+		presenter.present(&mut renderer, window());
+		let software = presenter.backend;
+		assert_eq!(software.image.get_pixel(0, 0).0[..3], [51, 51, 51]);
+	}
+
+	#[test]
+	fn software_blend_alpha() {
+		// destination alpha accumulates coverage instead of being scaled by it twice
+		let mut renderer = Renderer2D::new(window());
+		renderer.clear_color = Color::from(0, 0, 0, 0);
+		let mut rectangle = Visual::Rectangle::new(64.0, 48.0);
+		rectangle.color = Color::from(255, 255, 255, 51);
+		renderer.draw_rectangle(&rectangle, Transform2D::from(Position2D::from(32, 24), Rotation2D::new(), Scale2D::new()));
+		renderer.draw_rectangle(&rectangle, Transform2D::from(Position2D::from(32, 24), Rotation2D::new(), Scale2D::new()));
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window()));
+		presenter.present(&mut renderer, window());
+		let software = presenter.backend;
+		assert_eq!(software.image.get_pixel(0, 0).0[3], 92);
+	}
+
+	#[test]
+	fn software_resize() {
+		let mut software = SoftwareRenderer::new(window());
+		software.resize(8, 4);
+		software.clear(Color::WHITE);
+		assert_eq!(software.image.dimensions(), (8, 4));
+		assert!(software.image.pixels().all(|pixel| pixel.0 == [255, 255, 255, 255]));
+	}
+
+	#[test]
+	fn software_sprite() {
+		let mut renderer = Renderer2D::new(window());
+		let mut checker = RgbaImage::new(2, 2);
+		checker.put_pixel(0, 0, Rgba([255, 0, 0, 255]));
+		checker.put_pixel(1, 0, Rgba([0, 255, 0, 255]));
+		checker.put_pixel(0, 1, Rgba([0, 0, 255, 255]));
+		checker.put_pixel(1, 1, Rgba([255, 255, 255, 0]));
+		let texture = renderer.create_texture(checker);
+		let sprite = Sprite2D::new(texture).size(Size2D::from(8, 8)).pivot(Position2D::new());
+		renderer.draw_sprite(&sprite, Transform2D::new());
+		renderer.draw_sprite(&sprite.flip_h(true).color(Color::from_unit_interval(1.0, 1.0, 1.0, 0.5)), Transform2D::from(Position2D::from(8, 0), Rotation2D::new(), Scale2D::new()));
+
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window()));
+		presenter.present(&mut renderer, window());
+		let image = &presenter.backend.image;
+		assert_eq!(image.get_pixel(1, 1).0, [255, 0, 0, 255]);
+		assert_eq!(image.get_pixel(6, 1).0, [0, 255, 0, 255]);
+		assert_eq!(image.get_pixel(1, 6).0, [0, 0, 255, 255]);
+		// a transparent texel leaves the clear color untouched
+		assert_eq!(image.get_pixel(6, 6).0, [0, 0, 0, 255]);
+		// flipped and tinted half transparent
+		assert_eq!(image.get_pixel(9, 1).0, [0, 128, 0, 255]);
+		assert_eq!(image.get_pixel(14, 1).0, [128, 0, 0, 255]);
+	}
+
+	#[test]
+	fn software_empty_texture() {
+		let mut renderer = Renderer2D::new(window());
+		let texture = renderer.create_texture(RgbaImage::new(0, 0));
+		renderer.draw_sprite(&Sprite2D::new(texture).size(Size2D::from(8, 8)), Transform2D::from(Position2D::from(8, 8), Rotation2D::new(), Scale2D::new()));
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window()));
+		presenter.present(&mut renderer, window());
+		assert!(presenter.backend.image.pixels().all(|pixel| pixel.0 == [0, 0, 0, 255]));
+	}
+
+	#[test]
+	fn software_golden_shapes() {
+		let mut renderer = Renderer2D::new(window());
+		renderer.camera = Camera2D::from(Vector2D::from(16.0, 12.0), 2.0);
+
+		let mut circle = Visual::Circle::new(5.0);
+		circle.color = Color::RED;
+		let mut polygon = Visual::Polygon2D::new(vec![
+			Position2D::from(0, 0), Position2D::from(8, 0), Position2D::from(8, 3),
+			Position2D::from(3, 3), Position2D::from(3, 8), Position2D::from(0, 8),
+		]);
+		polygon.color = Color::GREEN;
+		let mut triangle = Visual::Triangle::from(Position2D::from(0, 0), Position2D::from(20, 0), Position2D::from(0, 20));
+		triangle.color = Color::from(0, 0, 255, 128);
+
+		renderer.draw(&Visual::Shape2D::Circle(circle), Transform2D::from(Position2D::from(8, 8), Rotation2D::new(), Scale2D::new()));
+		renderer.draw(&Visual::Shape2D::Polygon(polygon), Transform2D::from(Position2D::from(18, 10), Rotation2D::from(45), Scale2D::new()));
+		renderer.draw(&Visual::Shape2D::Triangle(triangle), Transform2D::new());
+
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window()));
+		presenter.present(&mut renderer, window());
+		let software = presenter.backend;
+		compare_golden(&software.image, "resources/golden/shapes.png", 1).unwrap();
+	}
+}
@@ -0,0 +1,543 @@
+use super::{Renderer2D, Texture};
+use crate::{Color, Position2D, Rect2D, Size2D};
+use image::{Rgba, RgbaImage};
+
+use std::{collections::HashMap, fs, io, path::Path};
+
+/// Smallest width and height of a TrueType glyph atlas.
+const GLYPH_ATLAS_SIZE: u32 = 256;
+
+/// Horizontal placement of each line inside the text box.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TextAlign {
+	Left,
+	Center,
+	Right,
+}
+
+/// How a string is laid out and drawn.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TextStyle {
+	/// Line height of the font in pixels, which are world units at zoom 1.
+	pub size: f32,
+	pub color: Color,
+	pub align: TextAlign,
+	/// Lines are wrapped between words to stay within this width.
+	pub max_width: Option<f32>,
+	/// Factor applied to the distance between lines.
+	pub line_spacing: f32,
+}
+
+impl TextStyle {
+	pub const fn new(size: f32) -> TextStyle {
+		TextStyle { size, color: Color::new(), align: TextAlign::Left, max_width: None, line_spacing: 1.0 }
+	}
+
+	pub const fn color(mut self, color: Color) -> TextStyle {
+		self.color = color;
+		self
+	}
+
+	pub const fn align(mut self, align: TextAlign) -> TextStyle {
+		self.align = align;
+		self
+	}
+
+	pub const fn max_width(mut self, max_width: f32) -> TextStyle {
+		self.max_width = Some(max_width);
+		self
+	}
+
+	pub const fn line_spacing(mut self, line_spacing: f32) -> TextStyle {
+		self.line_spacing = line_spacing;
+		self
+	}
+}
+
+/// A glyph rasterized into the atlas of a font.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Glyph {
+	/// Where the glyph is in the atlas image, in pixels.
+	pub source: Rect2D,
+	/// Top left corner of the glyph relative to the pen position on the baseline.
+	pub offset: Position2D,
+	pub size: Size2D,
+	pub advance: f32,
+}
+
+/// A glyph placed by `Font::layout`, relative to the top left of the text box.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PlacedGlyph {
+	pub character: char,
+	pub position: Position2D,
+	pub size: Size2D,
+	pub source: Rect2D,
+}
+
+/// The result of laying out a string.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextLayout {
+	pub glyphs: Vec<PlacedGlyph>,
+	/// Width of the widest line, or the wrap width, by the height of all lines.
+	pub size: Size2D,
+}
+
+/// A TrueType/OpenType font or a bitmap font, together with the atlases
+/// its glyphs are rasterized into. Each whole pixel size gets its own atlas
+/// and texture; `unload` frees them once the font is no longer drawn.
+///
+/// ```rust,no_run
+/// use Engine::{Game, Context, Position2D, Render::{Font, TextStyle}};
+///
+/// struct Hud { font: Font }
+///
+/// impl Game for Hud {
+///     fn draw(&mut self, ctx: &mut Context) {
+///         let fps = format!("FPS: {:.0}", 1.0 / ctx.time.delta);
+///         let window = ctx.window;
+///         ctx.renderer.draw_text_screen(&mut self.font, &fps, &TextStyle::new(16.0), window, Position2D::from(8, 8));
+///     }
+/// }
+///
+/// let font = Font::load("resources/fonts/DejaVuSans.ttf").unwrap();
+/// Engine::RunGame(Engine::Window::new(), &mut Hud { font });
+/// ```
+pub struct Font {
+	source: FontSource,
+	atlases: HashMap<u32, GlyphAtlas>,
+}
+
+enum FontSource {
+	TrueType(fontdue::Font),
+	Bitmap { image: RgbaImage, cell: Size2D, first: char },
+}
+
+impl Font {
+	/// Loads a TrueType or OpenType font file.
+	pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Font> {
+		Font::from_bytes(fs::read(path)?).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+	}
+
+	pub fn from_bytes(bytes: Vec<u8>) -> Result<Font, String> {
+		let font = fontdue::Font::from_bytes(bytes, fontdue::FontSettings::default()).map_err(String::from)?;
+		Ok(Font { source: FontSource::TrueType(font), atlases: HashMap::new() })
+	}
+
+	/// Loads a bitmap font: an image of equally sized `cell`s holding
+	/// consecutive characters from `first` on, left to right and top to bottom.
+	pub fn load_bitmap<P: AsRef<Path>>(path: P, cell: Size2D, first: char) -> io::Result<Font> {
+		let image = image::open(path).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?.into_rgba8();
+		Ok(Font::from_bitmap(image, cell, first))
+	}
+
+	pub fn from_bitmap(image: RgbaImage, cell: Size2D, first: char) -> Font {
+		assert!(cell.x >= 1.0 && cell.y >= 1.0, "bitmap font cells must be at least a pixel large");
+		Font { source: FontSource::Bitmap { image, cell, first }, atlases: HashMap::new() }
+	}
+
+	/// The glyph of `character` at `size`, rasterizing it on first use.
+	/// TrueType glyphs are rasterized at `size` rounded to whole pixels and
+	/// scaled to `size`, so text at close sizes shares one atlas.
+	pub fn glyph(&mut self, character: char, size: f32) -> Glyph {
+		let source = &self.source;
+		let base = base_size(source, size);
+		let atlas = self.atlases.entry(base.to_bits()).or_insert_with(|| GlyphAtlas::from(source));
+		if let Some(glyph) = atlas.glyphs.get(&character) {
+			return glyph.scaled(size / base);
+		}
+
+		let glyph = match source {
+			FontSource::TrueType(font) => {
+				let (metrics, coverage) = font.rasterize(character, base);
+				let source = atlas.insert(metrics.width as u32, metrics.height as u32, &coverage);
+				Glyph {
+					source,
+					offset: Position2D { x: metrics.xmin as f32, y: -(metrics.ymin as f32 + metrics.height as f32) },
+					size: Size2D::from(metrics.width as u32, metrics.height as u32),
+					advance: metrics.advance_width,
+				}
+			},
+			FontSource::Bitmap { image, cell, first } => {
+				let columns = (image.width() as f32 / cell.x).max(1.0) as u32;
+				let index = (character as u32).wrapping_sub(*first as u32);
+				let position = Position2D { x: (index % columns) as f32 * cell.x, y: (index / columns) as f32 * cell.y };
+				// characters missing from the image keep their advance but draw nothing
+				let inside = character as u32 >= *first as u32 && position.y + cell.y <= image.height() as f32;
+				Glyph {
+					source: Rect2D { position, size: if inside { *cell } else { Size2D::new() } },
+					offset: Position2D { x: 0.0, y: -cell.y },
+					size: if inside { *cell } else { Size2D::new() },
+					advance: cell.x,
+				}
+			},
+		};
+		atlas.glyphs.insert(character, glyph);
+		glyph.scaled(size / base)
+	}
+
+	/// Extra space between two characters from the font's kerning table.
+	pub fn kerning(&self, left: char, right: char, size: f32) -> f32 {
+		match &self.source {
+			FontSource::TrueType(font) => font.horizontal_kern(left, right, size).unwrap_or(0.0),
+			FontSource::Bitmap { .. } => 0.0,
+		}
+	}
+
+	/// Distance from the top of a line to its baseline, and from one baseline to the next.
+	pub fn line_metrics(&self, size: f32) -> (f32, f32) {
+		match &self.source {
+			FontSource::TrueType(font) => font.horizontal_line_metrics(size)
+				.map_or((size, size), |metrics| (metrics.ascent, metrics.new_line_size)),
+			FontSource::Bitmap { .. } => (size, size),
+		}
+	}
+
+	/// Width of a single line of text, kerning included.
+	pub fn measure(&mut self, line: &str, size: f32) -> f32 {
+		let mut width = 0.0;
+		let mut previous = None;
+		for character in line.chars() {
+			if let Some(previous) = previous {
+				width += self.kerning(previous, character, size);
+			}
+			width += self.glyph(character, size).advance;
+			previous = Some(character);
+		}
+		width
+	}
+
+	/// Wraps and aligns `text` and places its glyphs, relative to the top
+	/// left corner of the text box.
+	pub fn layout(&mut self, text: &str, style: &TextStyle) -> TextLayout {
+		let size = style.size;
+		let (ascent, line_height) = self.line_metrics(size);
+		let line_height = line_height * style.line_spacing;
+
+		let mut lines = Vec::new();
+		for paragraph in text.split('\n') {
+			self.wrap(paragraph, style, &mut lines);
+		}
+		let widths: Vec<f32> = lines.iter().map(|line| self.measure(line, size)).collect();
+		let width = style.max_width.unwrap_or_else(|| widths.iter().cloned().fold(0.0, f32::max));
+
+		let mut glyphs = Vec::new();
+		for (index, line) in lines.iter().enumerate() {
+			let mut x = match style.align {
+				TextAlign::Left => 0.0,
+				TextAlign::Center => (width - widths[index]) / 2.0,
+				TextAlign::Right => width - widths[index],
+			};
+			let baseline = index as f32 * line_height + ascent;
+			let mut previous = None;
+			for character in line.chars() {
+				if let Some(previous) = previous {
+					x += self.kerning(previous, character, size);
+				}
+				let glyph = self.glyph(character, size);
+				if glyph.size.x > 0.0 && glyph.size.y > 0.0 {
+					glyphs.push(PlacedGlyph {
+						character,
+						position: Position2D { x: x + glyph.offset.x, y: baseline + glyph.offset.y },
+						size: glyph.size,
+						source: glyph.source,
+					});
+				}
+				x += glyph.advance;
+				previous = Some(character);
+			}
+		}
+
+		TextLayout { glyphs, size: Size2D { x: width, y: lines.len() as f32 * line_height } }
+	}
+
+	/// Greedily breaks a paragraph into lines between words, splitting
+	/// words that are wider than a line on their own.
+	fn wrap(&mut self, paragraph: &str, style: &TextStyle, lines: &mut Vec<String>) {
+		let max_width = match style.max_width {
+			Some(max_width) => max_width,
+			None => return lines.push(String::from(paragraph)),
+		};
+
+		let mut line = String::new();
+		for word in paragraph.split(' ') {
+			let candidate = if line.is_empty() { String::from(word) } else { format!("{} {}", line, word) };
+			if self.measure(&candidate, style.size) <= max_width {
+				line = candidate;
+				continue;
+			}
+			if !line.is_empty() {
+				lines.push(std::mem::take(&mut line));
+			}
+			for character in word.chars() {
+				line.push(character);
+				if line.chars().count() > 1 && self.measure(&line, style.size) > max_width {
+					line.pop();
+					lines.push(std::mem::replace(&mut line, character.to_string()));
+				}
+			}
+		}
+		lines.push(line);
+	}
+
+	/// The atlas texture for `size`, handing it to `renderer` again when
+	/// glyphs were added since the last upload.
+	pub(crate) fn texture(&mut self, size: f32, renderer: &mut Renderer2D) -> (Texture, Size2D) {
+		let atlas = self.atlas(size);
+		if atlas.dirty || atlas.texture.is_none() {
+			if let Some(texture) = atlas.texture.take() {
+				renderer.unload_texture(texture);
+			}
+			atlas.texture = Some(renderer.create_texture(atlas.image.clone()));
+			atlas.dirty = false;
+		}
+		(atlas.texture.unwrap(), Size2D::from(atlas.image.width(), atlas.image.height()))
+	}
+
+	/// Hands the textures of every atlas back to `renderer` to be freed and
+	/// drops the atlases. Glyphs are rasterized again when next used.
+	pub fn unload(&mut self, renderer: &mut Renderer2D) {
+		for (_, atlas) in self.atlases.drain() {
+			if let Some(texture) = atlas.texture {
+				renderer.unload_texture(texture);
+			}
+		}
+	}
+
+	fn atlas(&mut self, size: f32) -> &mut GlyphAtlas {
+		let source = &self.source;
+		self.atlases.entry(base_size(source, size).to_bits()).or_insert_with(|| GlyphAtlas::from(source))
+	}
+}
+
+/// The size glyphs for `size` are stored at, which keys their atlas.
+/// Bitmap fonts share their one image between every size.
+fn base_size(source: &FontSource, size: f32) -> f32 {
+	match source {
+		FontSource::TrueType(_) => size.round().max(1.0),
+		FontSource::Bitmap { cell, .. } => cell.y,
+	}
+}
+
+impl Glyph {
+	/// The glyph drawn `scale` times as large, from the same atlas pixels.
+	fn scaled(self, scale: f32) -> Glyph {
+		Glyph {
+			source: self.source,
+			offset: Position2D { x: self.offset.x * scale, y: self.offset.y * scale },
+			size: Size2D { x: self.size.x * scale, y: self.size.y * scale },
+			advance: self.advance * scale,
+		}
+	}
+}
+
+/// White glyph coverage packed on shelves, growing downwards when full.
+struct GlyphAtlas {
+	image: RgbaImage,
+	glyphs: HashMap<char, Glyph>,
+	texture: Option<Texture>,
+	dirty: bool,
+	x: u32,
+	y: u32,
+	shelf: u32,
+}
+
+impl GlyphAtlas {
+	fn new(width: u32, height: u32) -> GlyphAtlas {
+		GlyphAtlas { image: RgbaImage::new(width, height), glyphs: HashMap::new(), texture: None, dirty: true, x: 1, y: 1, shelf: 0 }
+	}
+
+	fn from(source: &FontSource) -> GlyphAtlas {
+		match source {
+			FontSource::TrueType(_) => GlyphAtlas::new(GLYPH_ATLAS_SIZE, GLYPH_ATLAS_SIZE),
+			FontSource::Bitmap { image, .. } => GlyphAtlas { image: image.clone(), ..GlyphAtlas::new(1, 1) },
+		}
+	}
+
+	fn insert(&mut self, width: u32, height: u32, coverage: &[u8]) -> Rect2D {
+		if width == 0 || height == 0 {
+			return Rect2D::new();
+		}
+		if self.x + width + 1 > self.image.width() {
+			self.x = 1;
+			self.y += self.shelf + 1;
+			self.shelf = 0;
+		}
+		while self.x + width + 1 > self.image.width() || self.y + height + 1 > self.image.height() {
+			self.grow(width);
+		}
+
+		for row in 0..height {
+			for column in 0..width {
+				let alpha = coverage[(row * width + column) as usize];
+				self.image.put_pixel(self.x + column, self.y + row, Rgba([255, 255, 255, alpha]));
+			}
+		}
+		let rect = Rect2D::from(self.x, self.y, width, height);
+		self.x += width + 1;
+		self.shelf = self.shelf.max(height);
+		self.dirty = true;
+		rect
+	}
+
+	/// Doubles the height, and the width too if `width` would not fit on a line.
+	fn grow(&mut self, width: u32) {
+		let new_width = if width + 2 > self.image.width() { (width + 2).next_power_of_two() } else { self.image.width() };
+		let mut image = RgbaImage::new(new_width, self.image.height() * 2);
+		image::imageops::replace(&mut image, &self.image, 0, 0);
+		self.image = image;
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{Camera2D, Size2D, Transform2D, Window, Math::Vector2D, Render::{Presenter, SoftwareRenderer, compare_golden}};
+
+	/// A bitmap font of 4x8 cells for the characters ' ' to '~' in 16 columns.
+	fn bitmap_font() -> Font {
+		Font::from_bitmap(RgbaImage::from_pixel(64, 48, Rgba([255, 255, 255, 255])), Size2D::from(4, 8), ' ')
+	}
+
+	fn dejavu() -> Font {
+		Font::load("resources/fonts/DejaVuSans.ttf").unwrap()
+	}
+
+	#[test]
+	fn text_style_new() {
+		let style = TextStyle::new(12.0).align(TextAlign::Right).max_width(100.0).line_spacing(1.5);
+		assert_eq!(style, TextStyle { size: 12.0, color: Color::new(), align: TextAlign::Right, max_width: Some(100.0), line_spacing: 1.5 });
+	}
+
+	#[test]
+	fn font_bitmap_glyph() {
+		let mut font = bitmap_font();
+		let glyph = font.glyph('A', 16.0);
+		assert_eq!(glyph.source, Rect2D::from(4, 16, 4, 8));
+		assert_eq!((glyph.size, glyph.advance), (Size2D::from(8, 16), 8.0));
+		assert_eq!((font.glyph('A', 8.0).size, font.glyph('A', 8.0).offset), (Size2D::from(4, 8), Position2D::from(0, -8)));
+		assert_eq!(font.glyph('\u{80}', 8.0).size, Size2D::new());
+	}
+
+	#[test]
+	fn font_layout_lines() {
+		let mut font = bitmap_font();
+		let layout = font.layout("ab\nc", &TextStyle::new(8.0));
+		let positions: Vec<(char, Position2D)> = layout.glyphs.iter().map(|glyph| (glyph.character, glyph.position)).collect();
+		assert_eq!(positions, vec![('a', Position2D::from(0, 0)), ('b', Position2D::from(4, 0)), ('c', Position2D::from(0, 8))]);
+		assert_eq!(layout.size, Size2D::from(8, 16));
+	}
+
+	#[test]
+	fn font_layout_align() {
+		let mut font = bitmap_font();
+		let center = font.layout("abcd\nab", &TextStyle::new(8.0).align(TextAlign::Center));
+		assert_eq!(center.glyphs[4].position, Position2D::from(4, 8));
+		let right = font.layout("abcd\nab", &TextStyle::new(8.0).align(TextAlign::Right).max_width(20.0));
+		assert_eq!(right.glyphs[0].position, Position2D::from(4, 0));
+		assert_eq!(right.glyphs[4].position, Position2D::from(12, 8));
+	}
+
+	#[test]
+	fn font_layout_wrap() {
+		let mut font = bitmap_font();
+		let style = TextStyle::new(8.0).max_width(24.0);
+		let mut lines = Vec::new();
+		font.wrap("one two three abcdefghij", &style, &mut lines);
+		assert_eq!(lines, vec!["one", "two", "three", "abcdef", "ghij"]);
+		assert_eq!(font.layout("one two", &style.line_spacing(2.0)).size, Size2D::from(24, 32));
+	}
+
+	#[test]
+	fn font_truetype_glyphs() {
+		let mut font = dejavu();
+		let glyph = font.glyph('A', 32.0);
+		assert!(glyph.size.x > 10.0 && glyph.size.y > 10.0);
+		assert!(glyph.offset.y < 0.0);
+		assert_eq!(font.glyph('A', 32.0), glyph);
+		assert_eq!(font.glyph(' ', 32.0).size, Size2D::new());
+
+		let (ascent, line_height) = font.line_metrics(32.0);
+		assert!(ascent > 0.0 && line_height > ascent);
+	}
+
+	#[test]
+	fn font_truetype_fractional_sizes() {
+		let mut font = dejavu();
+		let glyph = font.glyph('A', 32.0);
+		for &size in &[31.6, 32.25, 32.4999] {
+			let scaled = font.glyph('A', size);
+			assert_eq!(scaled.source, glyph.source);
+			assert!((scaled.size.x - glyph.size.x * size / 32.0).abs() < 1e-3);
+			assert!((scaled.advance - glyph.advance * size / 32.0).abs() < 1e-3);
+		}
+		assert_eq!(font.atlases.len(), 1);
+		font.glyph('A', 33.0);
+		assert_eq!(font.atlases.len(), 2);
+	}
+
+	#[test]
+	fn font_unload() {
+		let mut renderer = Renderer2D::new(Window::new());
+		let mut font = dejavu();
+		let (small, _) = font.texture(12.0, &mut renderer);
+		let (large, _) = font.texture(24.0, &mut renderer);
+		renderer.take_textures();
+		font.unload(&mut renderer);
+		assert!(font.atlases.is_empty());
+		let (uploads, unloads) = renderer.take_textures();
+		assert!(uploads.is_empty());
+		assert!(unloads.len() == 2 && unloads.contains(&small) && unloads.contains(&large));
+	}
+
+	#[test]
+	fn font_truetype_kerning() {
+		let mut font = dejavu();
+		assert!(font.kerning('A', 'V', 32.0) < 0.0);
+		let separate = font.measure("A", 32.0) + font.measure("V", 32.0);
+		assert!(font.measure("AV", 32.0) < separate);
+	}
+
+	#[test]
+	fn font_atlas_grows() {
+		let mut font = dejavu();
+		for character in (33u8..127).map(char::from) {
+			font.glyph(character, 48.0);
+		}
+		let atlas = font.atlas(48.0);
+		assert!(atlas.image.height() > GLYPH_ATLAS_SIZE);
+		let mut rects: Vec<Rect2D> = atlas.glyphs.values().map(|glyph| glyph.source).collect();
+		rects.retain(|rect| rect.size.x > 0.0);
+		for (i, a) in rects.iter().enumerate() {
+			assert!(rects[i + 1..].iter().all(|b| !a.intersects(*b)));
+		}
+	}
+
+	#[test]
+	fn renderer_draw_text() {
+		let window = Window::new().size(Size2D::from(64, 32)).color(Color::BLACK);
+		let mut renderer = Renderer2D::new(window);
+		let mut font = dejavu();
+		let style = TextStyle::new(16.0).color(Color::YELLOW);
+		renderer.draw_text(&mut font, "AV To", &style, Transform2D::from(Position2D::from(2, 4), crate::Rotation2D::new(), crate::Scale2D::new()));
+		assert_eq!(renderer.batches().len(), 1);
+		assert_eq!(renderer.vertices().len(), 4 * 6);
+
+		let mut presenter = Presenter::new(SoftwareRenderer::new(window));
+		presenter.present(&mut renderer, window);
+		compare_golden(&presenter.backend.image, "resources/golden/text.png", 1).unwrap();
+	}
+
+	#[test]
+	fn renderer_draw_text_screen() {
+		let window = Window::new().size(Size2D::from(64, 32));
+		let mut renderer = Renderer2D::new(window);
+		renderer.camera = Camera2D::from(Vector2D::from(100.0, 100.0), 2.0);
+		let mut font = bitmap_font();
+		renderer.draw_text_screen(&mut font, "a", &TextStyle::new(8.0), window, Position2D::from(32, 16));
+		let corner = renderer.vertices()[0].position;
+		let far = renderer.vertices()[2].position;
+		assert_eq!(corner, [100.0, 100.0]);
+		// 4x8 pixels on screen are 2x4 world units at zoom 2
+		assert_eq!(far, [102.0, 104.0]);
+	}
+}
@@ -1,17 +1,14 @@
 use crate::{
 	Position2D,
 	Rotation2D,
+	Scale2D,
+	Transform2D,
 	Color,
+	Math,
 };
 
-/*
-TODO: add shape enum
-
-enum Shape::
-impl Shape {
-	match shape
-}
-*/
+/// Segments used for a full circle unless a shape asks for a different count.
+pub const DEFAULT_SEGMENTS: u32 = 32;
 
 pub struct Triangle {
 	pub points: (Position2D, Position2D, Position2D),
@@ -38,6 +35,13 @@ impl Triangle {
 			color: Color::new(),
 		}
 	}
+
+	/// The triangle in the space of its owner, offset and rotation applied.
+	pub fn tessellate(&self) -> Vec<Position2D> {
+		let local = local_transform(self.offset, self.rotation);
+		let (a, b, c) = self.points;
+		vec![local.apply(a), local.apply(b), local.apply(c)]
+	}
 }
 
 pub struct Rectangle {
@@ -58,10 +62,24 @@ impl Rectangle {
 			color: Color::new(),
 		}
 	}
+
+	/// Two triangles covering the rectangle, centered on its offset.
+	pub fn tessellate(&self) -> Vec<Position2D> {
+		let local = local_transform(self.offset, self.rotation);
+		let (w, h) = (self.width / 2.0, self.height / 2.0);
+		let corners = [
+			local.apply(Position2D { x: -w, y: -h }),
+			local.apply(Position2D { x: w, y: -h }),
+			local.apply(Position2D { x: w, y: h }),
+			local.apply(Position2D { x: -w, y: h }),
+		];
+		fan(&corners)
+	}
 }
 
 pub struct Circle {
 	pub radius: f32,
+	pub segments: u32,
 	pub offset: Position2D,
 	pub color: Color,
 }
@@ -70,15 +88,26 @@ impl Circle {
 	pub const fn new(radius: f32) -> Circle {
 		Circle {
 			radius,
+			segments: DEFAULT_SEGMENTS,
 			offset: Position2D::new(),
 			color: Color::new(),
 		}
 	}
+
+	/// A triangle fan of `segments` slices around the offset.
+	pub fn tessellate(&self) -> Vec<Position2D> {
+		let segments = self.segments.max(3);
+		let outline: Vec<Position2D> = (0..segments)
+			.map(|i| self.offset + arc_point(self.radius, i as f32 / segments as f32 * Math::TWO_PIE))
+			.collect();
+		fan(&outline)
+	}
 }
 
 pub struct Capsule2D {
 	pub radius: f32,
 	pub height: f32,
+	pub segments: u32,
 	pub offset: Position2D,
 	pub rotation: Rotation2D,
 	pub color: Color,
@@ -89,11 +118,31 @@ impl Capsule2D {
 		Capsule2D {
 			radius,
 			height,
+			segments: DEFAULT_SEGMENTS,
 			offset: Position2D::new(),
 			rotation: Rotation2D::new(),
 			color: Color::new(),
 		}
 	}
+
+	/// A vertical capsule `height` tall including both caps, each cap using
+	/// half of `segments`.
+	pub fn tessellate(&self) -> Vec<Position2D> {
+		let local = local_transform(self.offset, self.rotation);
+		let half = (self.height / 2.0 - self.radius).max(0.0);
+		let cap = (self.segments / 2).max(2);
+		let mut outline = Vec::with_capacity(2 * cap as usize + 2);
+		// bottom cap from the right side to the left, then the top cap back
+		for i in 0..=cap {
+			let angle = i as f32 / cap as f32 * Math::PI;
+			outline.push(local.apply(arc_point(self.radius, angle) + Position2D { x: 0.0, y: half }));
+		}
+		for i in 0..=cap {
+			let angle = Math::PI + i as f32 / cap as f32 * Math::PI;
+			outline.push(local.apply(arc_point(self.radius, angle) + Position2D { x: 0.0, y: -half }));
+		}
+		fan(&outline)
+	}
 }
 
 pub struct Polygon2D {
@@ -112,11 +161,180 @@ impl Polygon2D {
 			color: Color::new(),
 		}
 	}
+
+	/// Triangles covering the polygon. Concave outlines are supported as
+	/// long as the edges do not cross each other.
+	pub fn tessellate(&self) -> Vec<Position2D> {
+		let local = local_transform(self.offset, self.rotation);
+		let points: Vec<Position2D> = self.points.iter().map(|point| local.apply(*point)).collect();
+		triangulate(&points).iter().flat_map(|triangle| triangle.iter().map(|&i| points[i])).collect()
+	}
 }
 
 pub enum Shape2D {
+	Triangle(Triangle),
 	Rectangle(Rectangle),
 	Circle(Circle),
 	Capsule(Capsule2D),
 	Polygon(Polygon2D),
+}
+
+impl Shape2D {
+	pub fn color(&self) -> Color {
+		match self {
+			Shape2D::Triangle(shape) => shape.color,
+			Shape2D::Rectangle(shape) => shape.color,
+			Shape2D::Circle(shape) => shape.color,
+			Shape2D::Capsule(shape) => shape.color,
+			Shape2D::Polygon(shape) => shape.color,
+		}
+	}
+
+	/// Triangle list, three points per triangle, covering the shape.
+	pub fn tessellate(&self) -> Vec<Position2D> {
+		match self {
+			Shape2D::Triangle(shape) => shape.tessellate(),
+			Shape2D::Rectangle(shape) => shape.tessellate(),
+			Shape2D::Circle(shape) => shape.tessellate(),
+			Shape2D::Capsule(shape) => shape.tessellate(),
+			Shape2D::Polygon(shape) => shape.tessellate(),
+		}
+	}
+}
+
+/// Splits a simple polygon into triangles by ear clipping and returns the
+/// point indices of each triangle. Works for either winding order.
+pub fn triangulate(points: &[Position2D]) -> Vec<[usize; 3]> {
+	let mut triangles = Vec::new();
+	if points.len() < 3 {
+		return triangles;
+	}
+
+	let mut remaining: Vec<usize> = (0..points.len()).collect();
+	if signed_area(points) < 0.0 {
+		remaining.reverse();
+	}
+
+	while remaining.len() > 3 {
+		let n = remaining.len();
+		let ear = (0..n).find(|&i| is_ear(points, &remaining, i));
+		// a self-intersecting or degenerate outline may have no ear left;
+		// clip a corner anyway so the loop always terminates
+		let i = ear.unwrap_or(0);
+		triangles.push([remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]]);
+		remaining.remove(i);
+	}
+	triangles.push([remaining[0], remaining[1], remaining[2]]);
+	triangles
+}
+
+fn is_ear(points: &[Position2D], remaining: &[usize], i: usize) -> bool {
+	let n = remaining.len();
+	let (prev, current, next) = (remaining[(i + n - 1) % n], remaining[i], remaining[(i + 1) % n]);
+	let (a, b, c) = (points[prev], points[current], points[next]);
+	if cross(a, b, c) <= 0.0 {
+		return false; // reflex or collinear corner
+	}
+	remaining.iter()
+		.filter(|&&j| j != prev && j != current && j != next)
+		.all(|&j| !in_triangle(points[j], a, b, c))
+}
+
+fn signed_area(points: &[Position2D]) -> f32 {
+	let n = points.len();
+	(0..n).map(|i| {
+		let (a, b) = (points[i], points[(i + 1) % n]);
+		a.x * b.y - b.x * a.y
+	}).sum::<f32>() / 2.0
+}
+
+fn cross(a: Position2D, b: Position2D, c: Position2D) -> f32 {
+	(b.x - a.x) * (c.y - a.y) - (b.y - a.y) * (c.x - a.x)
+}
+
+fn in_triangle(p: Position2D, a: Position2D, b: Position2D, c: Position2D) -> bool {
+	cross(a, b, p) >= 0.0 && cross(b, c, p) >= 0.0 && cross(c, a, p) >= 0.0
+}
+
+/// Triangle fan around the first point of a convex outline.
+fn fan(outline: &[Position2D]) -> Vec<Position2D> {
+	(1..outline.len().saturating_sub(1))
+		.flat_map(|i| vec![outline[0], outline[i], outline[i + 1]])
+		.collect()
+}
+
+fn arc_point(radius: f32, angle: f32) -> Position2D {
+	Position2D { x: radius * angle.cos(), y: radius * angle.sin() }
+}
+
+fn local_transform(offset: Position2D, rotation: Rotation2D) -> Transform2D {
+	Transform2D::from(offset, rotation, Scale2D::new())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn area(triangles: &[Position2D]) -> f32 {
+		triangles.chunks(3).map(|t| cross(t[0], t[1], t[2]).abs() / 2.0).sum()
+	}
+
+	#[test]
+	fn rectangle_tessellate() {
+		let mut rectangle = Rectangle::new(4.0, 2.0);
+		rectangle.offset = Position2D::from(1, 1);
+		let triangles = rectangle.tessellate();
+		assert_eq!(triangles.len(), 6);
+		assert_eq!(triangles[0], Position2D::from(-1, 0));
+		assert_eq!(area(&triangles), 8.0);
+	}
+
+	#[test]
+	fn circle_tessellate() {
+		let mut circle = Circle::new(2.0);
+		circle.segments = 64;
+		let triangles = circle.tessellate();
+		assert_eq!(triangles.len(), 62 * 3);
+		assert!((area(&triangles) - Math::PI * 4.0).abs() < 0.05);
+		assert!(triangles.iter().all(|p| ((p.x * p.x + p.y * p.y).sqrt() - 2.0).abs() < 1e-5));
+	}
+
+	#[test]
+	fn capsule_tessellate() {
+		let mut capsule = Capsule2D::new(1.0, 4.0);
+		capsule.segments = 128;
+		let triangles = capsule.tessellate();
+		assert!((area(&triangles) - (Math::PI + 4.0)).abs() < 0.01);
+		let top = triangles.iter().map(|p| p.y).fold(f32::MAX, f32::min);
+		assert!((top + 2.0).abs() < 1e-5);
+	}
+
+	#[test]
+	fn polygon_tessellate_concave() {
+		// an L shape, listed clockwise and counter-clockwise
+		let mut points = vec![
+			Position2D::from(0, 0), Position2D::from(2, 0), Position2D::from(2, 1),
+			Position2D::from(1, 1), Position2D::from(1, 2), Position2D::from(0, 2),
+		];
+		for _ in 0..2 {
+			let polygon = Polygon2D::new(points.clone());
+			let triangles = polygon.tessellate();
+			assert_eq!(triangles.len(), 4 * 3);
+			assert_eq!(area(&triangles), 3.0);
+			points.reverse();
+		}
+	}
+
+	#[test]
+	fn polygon_triangulate_small() {
+		assert!(triangulate(&[Position2D::new(), Position2D::from(1, 0)]).is_empty());
+		assert_eq!(triangulate(&[Position2D::new(), Position2D::from(1, 0), Position2D::from(0, 1)]), vec![[0, 1, 2]]);
+	}
+
+	#[test]
+	fn shape_tessellate() {
+		let shape = Shape2D::Triangle(Triangle::new());
+		assert_eq!(shape.tessellate().len(), 3);
+		assert_eq!(shape.color(), Color::new());
+	}
 }
\ No newline at end of file
@@ -1,24 +1,26 @@
-use Engine::{self, Window, Size2D, Color};
+use Engine::{self, Window, Size2D, Color, Game, Context, Position2D, Rotation2D, Scale2D, Transform2D, Math, Visual};
 
-fn main() {
-	//let window = Engine::Window::new();
-
-	//let shape0 = Engine::Visual::Triangle::new();
-
-	let win = Window::from("TestGame", Size2D::from(856, 482), Color::BLACK);
-
-	Engine::Run(win, start, update, end);
-	
+struct TestGame {
+	rectangle: Visual::Rectangle,
 }
 
-fn start() {
-	println!("Game started!");
-}
-
-fn update() {
+impl Game for TestGame {
+	fn start(&mut self, _ctx: &mut Context) {
+		println!("Game started!");
+	}
 
+	fn draw(&mut self, ctx: &mut Context) {
+		let rotation = Rotation2D::from_rad(ctx.time.total.sin() * Math::TWO_PIE_F64);
+		let transform = Transform2D::from(Position2D::from(400, 300), rotation, Scale2D::new());
+		ctx.renderer.draw_rectangle(&self.rectangle, transform);
+	}
 }
 
-fn end() {
+fn main() {
+	let win = Window::from("TestGame", Size2D::from(856, 482), Color::BLACK);
+
+	let mut rectangle = Visual::Rectangle::new(150.0, 100.0);
+	rectangle.color = Color::RED;
 
+	Engine::RunGame(win, &mut TestGame { rectangle });
 }
\ No newline at end of file
@@ -32,6 +32,36 @@
 //!     println!("Goodbye World :(");
 //! }
 //! ```
+//!
+//! ## Stateful Game Template
+//!
+//! ```rust,no_run
+//! use Engine::{Window, Size2D, Color, Game, Context};
+//!
+//! struct MyGame {
+//!     score: u32,
+//! }
+//!
+//! impl Game for MyGame {
+//!     fn start(&mut self, _ctx: &mut Context) {
+//!         println!("Hello World!");
+//!     }
+//!
+//!     fn update(&mut self, _ctx: &mut Context, _dt: f32) {
+//!         self.score += 1;
+//!     }
+//!
+//!     fn end(&mut self) {
+//!         println!("Final score: {}", self.score);
+//!     }
+//! }
+//!
+//! fn main() {
+//!     let window = Window::from("Title", Size2D::from(856, 482), Color::BLACK);
+//!
+//!     Engine::RunGame(window, &mut MyGame { score: 0 });
+//! }
+//! ```
 
 // * I'll name my functions however I want >:(
 #![allow(non_snake_case)]
@@ -40,7 +70,10 @@ pub(crate) extern crate glfw;
 pub(crate) extern crate gl;
 
 pub mod Collision;
+pub mod Physics;
 pub mod Visual;
+pub mod Input;
+pub mod Render;
 pub mod shader;
 pub mod Math;
 pub mod Debug;
@@ -48,6 +81,9 @@ pub mod Debug;
 mod Global;
 mod Object;
 
+#[cfg(test)]
+mod test_util;
+
 // * Global Imports
 pub use Global::*;
 pub use Object::*;
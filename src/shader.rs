@@ -13,6 +13,12 @@ pub fn Load(vertexFilePath: &str, fragmentFilePath: &str) -> GLuint {
 	let fragmentCode: String = std::fs::read_to_string(fragmentFilePath)
 		.expect("Could not open shader file!");
 
+	Compile(&vertexCode, &fragmentCode)
+}
+
+/// Compiles and links a shader program from GLSL source code.
+pub fn Compile(vertexCode: &str, fragmentCode: &str) -> GLuint {
+
 	// build and compile our shader program
 	// ------------------------------------
 	unsafe {
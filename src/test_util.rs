@@ -0,0 +1,48 @@
+//! Helpers shared by the unit tests of several modules.
+
+use crate::{Position2D, Rotation2D, Scale2D, Transform2D};
+use crate::{Physics::{BodyId, BodyType, PhysicsWorld2D, RigidBody2D}, Collision::{Circle, Collision2D, Rectangle, Shape2D}};
+
+/// Physics step used by the tests, 60 per second.
+pub(crate) const STEP: f32 = 1.0 / 60.0;
+
+/// Whether two floats are equal up to rounding errors.
+pub(crate) fn close(a: f32, b: f32) -> bool {
+	(a - b).abs() < 1e-4
+}
+
+pub(crate) fn close_position(a: Position2D, b: Position2D) -> bool {
+	close(a.x, b.x) && close(a.y, b.y)
+}
+
+/// An unrotated, unscaled transform at (x, y).
+pub(crate) fn at(x: f32, y: f32) -> Transform2D {
+	Transform2D::from(Position2D::from(x, y), Rotation2D::new(), Scale2D::new())
+}
+
+pub(crate) fn boxed(width: f32, height: f32) -> Collision2D {
+	Collision2D::new(Shape2D::Rectangle(Rectangle::new(width, height)))
+}
+
+pub(crate) fn ball(radius: f32) -> Collision2D {
+	Collision2D::new(Shape2D::Circle(Circle::new(radius)))
+}
+
+/// Adds a static body.
+pub(crate) fn solid(world: &mut PhysicsWorld2D, transform: Transform2D, collider: Collision2D) -> BodyId {
+	world.insert(RigidBody2D::new(transform).body_type(BodyType::Static), collider)
+}
+
+/// A world with a static floor whose top is at y = 0.
+pub(crate) fn with_ground() -> (PhysicsWorld2D, BodyId) {
+	let mut world = PhysicsWorld2D::new();
+	let ground = solid(&mut world, at(0.0, 50.0), boxed(2000.0, 100.0));
+	(world, ground)
+}
+
+/// Steps `world` by `STEP` for `seconds`.
+pub(crate) fn run(world: &mut PhysicsWorld2D, seconds: f32) {
+	for _ in 0..(seconds / STEP).round() as u32 {
+		world.step(STEP);
+	}
+}